@@ -1,6 +1,7 @@
 macro_rules! versioned_inner {
     ($name:ident, $field_type:ty, $error_from_string:ty, $s_from_string:ident, $version_from_string:ident, $inner_from_string:block) => {
         #[derive(PartialEq, Debug, Clone, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize))]
         pub struct $name(pub $field_type);
 
         impl From<$field_type> for $name {
@@ -103,6 +104,7 @@ macro_rules! versioned_field {
 macro_rules! general_section_inner {
     ($(#[$outer:meta])*, $section_name:ident, $($(#[$inner:meta])*, $field:ident, $field_type:ty)*, $parse_error:ty, $spacing:expr, $default_version:ident, $default_field_name:ident) => {
         #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize))]
         $(#[$outer])*
         pub struct $section_name {
             $(
@@ -170,6 +172,37 @@ macro_rules! general_section_inner {
 
                 Some(v.join("\n"))
             }
+
+            /// Returns every key of the section with its value as a string, in the order they are written.
+            /// - The value is `None` if the field is `None` or doesn't exist in the version.
+            pub fn fields(&self, version: crate::osu_file::types::Version) -> Vec<(&'static str, Option<String>)> {
+                vec![
+                    $(
+                        (
+                            stringify!($field_type),
+                            self.$field.as_ref().and_then(|value| crate::osu_file::types::VersionedToString::to_string(value, version)),
+                        ),
+                    )*
+                ]
+            }
+
+            /// Sets the field with the key name by parsing the value, or clears it if the value is `None`.
+            /// - The key names are the same as the ones returned by [`fields`][Self::fields].
+            pub fn set_field(&mut self, key: &str, value: Option<&str>, version: crate::osu_file::types::Version) -> Result<(), $parse_error> {
+                match key {
+                    $(
+                        stringify!($field_type) => {
+                            self.$field = match value {
+                                Some(value) => <$field_type as crate::osu_file::types::VersionedFromStr>::from_str(value, version).map_err(<$parse_error>::from)?,
+                                None => None,
+                            };
+                        }
+                    )*
+                    _ => return Err(<$parse_error>::InvalidKey),
+                }
+
+                Ok(())
+            }
         }
 
         impl Default for $section_name {
@@ -0,0 +1,276 @@
+//! Command line tool for inspecting and converting beatmaps.
+
+use std::io::{ErrorKind, Read, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use osu_file_parser::hitobjects::{HitObject, HitObjectParams};
+use osu_file_parser::*;
+
+const USAGE: &str = "Usage: osu-file <command> [options] <file>
+
+Commands:
+  info <file>                       Prints the metadata, difficulty and stats of a beatmap
+  validate <file>...                Prints the errors of .osu and .osb files
+  fmt [--write] <file>              Prints the beatmap serialized again, or overwrites the file with --write
+  convert --to-version <n> <file>   Prints the beatmap converted to another file format version,
+                                    and reports lost data to stderr
+  json <file>                       Prints the parsed beatmap as JSON
+
+Use `-` as the file to read from the standard input.";
+
+fn read_file(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut s = String::new();
+        std::io::stdin()
+            .read_to_string(&mut s)
+            .map_err(|err| format!("-: {err}"))?;
+        return Ok(s);
+    }
+
+    std::fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))
+}
+
+/// Prints the output, stopping quietly if the output is closed early such as when piped to `head`.
+fn print_output(output: &str) -> Result<(), String> {
+    match writeln!(std::io::stdout().lock(), "{output}") {
+        Err(err) if err.kind() != ErrorKind::BrokenPipe => Err(err.to_string()),
+        _ => Ok(()),
+    }
+}
+
+fn parse_osu_file(path: &str) -> Result<OsuFile, String> {
+    let s = read_file(path)?;
+
+    s.parse::<OsuFile>()
+        .map_err(|err| format!("{path}: {}", err.display_error_with_line(&s)))
+}
+
+/// Takes the only argument left, or returns an error for a missing or an extra argument.
+fn single_file(args: &[String]) -> Result<&str, String> {
+    match args {
+        [file] => Ok(file),
+        [] => Err("Missing file".to_string()),
+        _ => Err(format!("Unexpected argument `{}`", args[1])),
+    }
+}
+
+fn field<'a>(fields: &'a [(&'static str, Option<String>)], key: &str) -> &'a str {
+    fields
+        .iter()
+        .find(|(k, _)| *k == key)
+        .and_then(|(_, value)| value.as_deref())
+        .unwrap_or("-")
+}
+
+fn info(args: &[String]) -> Result<(), String> {
+    let osu_file = parse_osu_file(single_file(args)?)?;
+    let version = osu_file.version;
+
+    let metadata = osu_file
+        .metadata
+        .as_ref()
+        .map(|metadata| metadata.fields(version))
+        .unwrap_or_default();
+    let difficulty = osu_file
+        .difficulty
+        .as_ref()
+        .map(|difficulty| difficulty.fields(version))
+        .unwrap_or_default();
+
+    println!("File format: v{version}");
+    println!(
+        "Beatmap: {} - {} [{}]",
+        field(&metadata, "Artist"),
+        field(&metadata, "Title"),
+        field(&metadata, "Version")
+    );
+    println!("Creator: {}", field(&metadata, "Creator"));
+    println!(
+        "Beatmap ID: {}, set ID: {}",
+        field(&metadata, "BeatmapID"),
+        field(&metadata, "BeatmapSetID")
+    );
+    println!(
+        "HP: {}, CS: {}, OD: {}, AR: {}, slider multiplier: {}, slider tick rate: {}",
+        field(&difficulty, "HPDrainRate"),
+        field(&difficulty, "CircleSize"),
+        field(&difficulty, "OverallDifficulty"),
+        field(&difficulty, "ApproachRate"),
+        field(&difficulty, "SliderMultiplier"),
+        field(&difficulty, "SliderTickRate")
+    );
+
+    let hitobjects = osu_file
+        .hitobjects
+        .as_ref()
+        .map(|hitobjects| hitobjects.0.as_slice())
+        .unwrap_or_default();
+    let count = |f: fn(&HitObject) -> bool| hitobjects.iter().filter(|h| f(h)).count();
+    println!(
+        "Hit objects: {} ({} circles, {} sliders, {} spinners, {} holds)",
+        hitobjects.len(),
+        count(|h| matches!(h.obj_params, HitObjectParams::HitCircle)),
+        count(|h| matches!(h.obj_params, HitObjectParams::Slider(_))),
+        count(|h| matches!(h.obj_params, HitObjectParams::Spinner { .. })),
+        count(|h| matches!(h.obj_params, HitObjectParams::OsuManiaHold { .. })),
+    );
+
+    let times = hitobjects
+        .iter()
+        .filter_map(|h| h.time.to_f64())
+        .collect::<Vec<_>>();
+    if let (Some(first), Some(last)) = (
+        times.iter().copied().reduce(f64::min),
+        times.iter().copied().reduce(f64::max),
+    ) {
+        let length = ((last - first) / 1000.0) as u64;
+        println!("Length: {}:{:02}", length / 60, length % 60);
+    }
+
+    let timing_points = osu_file
+        .timing_points
+        .as_ref()
+        .map(|timing_points| timing_points.0.as_slice())
+        .unwrap_or_default();
+    let bpms = timing_points
+        .iter()
+        .filter_map(|t| t.calc_bpm())
+        .collect::<Vec<_>>();
+    println!("Timing points: {}", timing_points.len());
+    if let (Some(min), Some(max)) = (bpms.iter().min(), bpms.iter().max()) {
+        if min == max {
+            println!("BPM: {}", min.round_dp(2).normalize());
+        } else {
+            println!(
+                "BPM: {} - {}",
+                min.round_dp(2).normalize(),
+                max.round_dp(2).normalize()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn validate(args: &[String]) -> Result<(), String> {
+    if args.is_empty() {
+        return Err("Missing file".to_string());
+    }
+
+    let mut failed = false;
+
+    for path in args {
+        let s = read_file(path)?;
+        let result = if Path::new(path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("osb"))
+        {
+            Osb::from_str(&s, LATEST_VERSION)
+                .map(|_| ())
+                .map_err(|err| err.display_error_with_line(&s))
+        } else {
+            s.parse::<OsuFile>()
+                .map(|_| ())
+                .map_err(|err| err.display_error_with_line(&s))
+        };
+
+        match result {
+            Ok(()) => println!("{path}: ok"),
+            Err(err) => {
+                println!("{path}: {err}");
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        Err("Some files have errors".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn fmt(args: &[String]) -> Result<(), String> {
+    let (write, args) = match args {
+        [flag, args @ ..] if flag == "--write" => (true, args),
+        _ => (false, args),
+    };
+    let path = single_file(args)?;
+    let osu_file = parse_osu_file(path)?;
+
+    if write && path != "-" {
+        std::fs::write(path, osu_file.to_string()).map_err(|err| format!("{path}: {err}"))
+    } else {
+        print_output(&osu_file.to_string())
+    }
+}
+
+fn convert(args: &[String]) -> Result<(), String> {
+    let [flag, version, args @ ..] = args else {
+        return Err("Missing `--to-version <n>`".to_string());
+    };
+    if flag != "--to-version" {
+        return Err(format!("Unexpected argument `{flag}`"));
+    }
+    let version = version
+        .parse::<Version>()
+        .ok()
+        .filter(|version| {
+            (MIN_VERSION..=LATEST_VERSION).contains(version) || *version == LAZER_VERSION
+        })
+        .ok_or_else(|| {
+            format!(
+                "Invalid version `{version}`, expected {MIN_VERSION} ~ {LATEST_VERSION} or {LAZER_VERSION}"
+            )
+        })?;
+
+    let mut osu_file = parse_osu_file(single_file(args)?)?;
+    let report = osu_file
+        .convert_to(version)
+        .map_err(|err| err.to_string())?;
+    if !report.is_lossless() {
+        eprintln!("{report}");
+    }
+
+    print_output(&osu_file.to_string())
+}
+
+fn json(args: &[String]) -> Result<(), String> {
+    let osu_file = parse_osu_file(single_file(args)?)?;
+
+    print_output(&serde_json::to_string_pretty(&osu_file).map_err(|err| err.to_string())?)
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let Some((command, args)) = args.split_first() else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+
+    let result = match command.as_str() {
+        "info" => info(args),
+        "validate" => validate(args),
+        "fmt" => fmt(args),
+        "convert" => convert(args),
+        "json" => json(args),
+        "help" | "-h" | "--help" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => {
+            eprintln!("Unknown command `{command}`\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
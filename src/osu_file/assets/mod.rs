@@ -0,0 +1,336 @@
+//! Files referenced by a beatmap and its storyboard.
+//!
+//! Paths are compared case-insensitively and with `\` and `/` treated the same, like osu! does on Windows.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::Path;
+
+use super::events::storyboard::sprites::ObjectType;
+use super::events::{Event, Events};
+use super::hitobjects::types::SampleIndex as HitSampleIndex;
+use super::timingpoints::SampleIndex;
+use super::{FilePath, Osb, OsuFile};
+
+/// What a file is used for.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum AssetKind {
+    /// The beatmap's audio from `AudioFilename`.
+    Audio,
+    Background,
+    Video,
+    /// Image of a storyboard sprite.
+    Sprite,
+    /// Image of a single frame of a storyboard animation.
+    AnimationFrame,
+    /// Sound played by a storyboard sample.
+    StoryboardSample,
+    /// Sound from the `filename` of a hitobject's hitsample.
+    HitSample,
+}
+
+/// A file referenced by a beatmap or storyboard.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Asset {
+    /// Path as it was first referenced, without quotes.
+    pub path: String,
+    /// Everything the file is used for.
+    pub kinds: BTreeSet<AssetKind>,
+}
+
+/// Files referenced by beatmaps and storyboards of a mapset.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AssetInventory {
+    assets: BTreeMap<String, Asset>,
+    /// Names of custom hitsound files without the extension, which are only used if they exist.
+    hitsounds: BTreeSet<String>,
+}
+
+/// Result of comparing an [`AssetInventory`] with the files of a beatmap folder.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct AssetReport {
+    /// Referenced files that aren't in the folder, as they were referenced.
+    pub missing: Vec<String>,
+    /// Files in the folder that aren't referenced, relative to the folder with `/` as the separator.
+    /// - `.osu` and `.osb` files are never included.
+    pub unused: Vec<String>,
+}
+
+/// Normalizes a path for comparing.
+/// - Surrounding quotes and whitespace are removed.
+/// - `\` is replaced with `/`, and empty or `.` components are removed.
+/// - Letters are lowercased.
+pub fn normalize_path(path: &str) -> String {
+    let path = path.trim();
+    let path = path
+        .strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+        .unwrap_or(path);
+
+    path.split(['/', '\\'])
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+        .to_lowercase()
+}
+
+pub(crate) fn path_to_str(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    let path = path.trim();
+
+    path.strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// File names of each frame of an animation, which have the frame index before the extension.
+pub(crate) fn frame_paths(path: &str, frame_count: u32) -> Vec<String> {
+    let file_start = path.rfind(['/', '\\']).map_or(0, |i| i + 1);
+    let (stem, extension) = match path[file_start..].rfind('.') {
+        Some(i) => path.split_at(file_start + i),
+        None => (path, ""),
+    };
+
+    (0..frame_count)
+        .map(|i| format!("{stem}{i}{extension}"))
+        .collect()
+}
+
+const HITSOUND_SAMPLE_SETS: [&str; 3] = ["normal", "soft", "drum"];
+const HITSOUND_NAMES: [&str; 7] = [
+    "hitnormal",
+    "hitwhistle",
+    "hitfinish",
+    "hitclap",
+    "slidertick",
+    "sliderslide",
+    "sliderwhistle",
+];
+const HITSOUND_EXTENSIONS: [&str; 3] = ["wav", "ogg", "mp3"];
+
+impl AssetInventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects the assets of a beatmap, including the storyboard in [`OsuFile::osb`].
+    pub fn from_osu_file(osu_file: &OsuFile) -> Self {
+        let mut inventory = Self::new();
+        inventory.add_osu_file(osu_file);
+        inventory
+    }
+
+    /// Adds a referenced file.
+    pub fn add(&mut self, path: &str, kind: AssetKind) {
+        let normalized = normalize_path(path);
+        if normalized.is_empty() {
+            return;
+        }
+
+        self.assets
+            .entry(normalized)
+            .or_insert_with(|| Asset {
+                path: path.to_string(),
+                kinds: BTreeSet::new(),
+            })
+            .kinds
+            .insert(kind);
+    }
+
+    fn add_file_path(&mut self, path: &FilePath, kind: AssetKind) {
+        self.add(&path_to_str(path.get()), kind);
+    }
+
+    /// Adds custom hitsound files of a sample index.
+    /// - Index `1` also uses files without a number.
+    fn add_hitsound_index(&mut self, index: usize) {
+        let suffixes = if index == 1 {
+            vec![String::new(), index.to_string()]
+        } else {
+            vec![index.to_string()]
+        };
+
+        for sample_set in HITSOUND_SAMPLE_SETS {
+            for name in HITSOUND_NAMES {
+                for suffix in &suffixes {
+                    self.hitsounds
+                        .insert(format!("{sample_set}-{name}{suffix}"));
+                }
+            }
+        }
+    }
+
+    /// Adds the assets of a beatmap, including the storyboard in [`OsuFile::osb`].
+    /// - Custom hitsound files of the sample indexes used by timing points and hitobjects count as used if they exist, but are never missing.
+    pub fn add_osu_file(&mut self, osu_file: &OsuFile) {
+        if let Some(audio_filename) = osu_file
+            .general
+            .as_ref()
+            .and_then(|general| general.audio_filename.as_ref())
+        {
+            self.add(&path_to_str(&audio_filename.0), AssetKind::Audio);
+        }
+
+        if let Some(events) = &osu_file.events {
+            self.add_events(events);
+        }
+        if let Some(osb) = &osu_file.osb {
+            self.add_osb(osb);
+        }
+
+        if let Some(timing_points) = &osu_file.timing_points {
+            for timing_point in &timing_points.0 {
+                if let SampleIndex::Index(index) = timing_point.sample_index() {
+                    self.add_hitsound_index(index.get() as usize);
+                }
+            }
+        }
+
+        if let Some(hitobjects) = &osu_file.hitobjects {
+            for hitsample in hitobjects.0.iter().filter_map(|h| h.hitsample.as_ref()) {
+                if let HitSampleIndex::Index(index) = hitsample.index {
+                    self.add_hitsound_index(index.get());
+                }
+                self.add(&hitsample.filename, AssetKind::HitSample);
+            }
+        }
+    }
+
+    /// Adds the assets of a storyboard.
+    pub fn add_osb(&mut self, osb: &Osb) {
+        if let Some(events) = &osb.events {
+            self.add_events(events);
+        }
+    }
+
+    /// Adds the assets of events.
+    pub fn add_events(&mut self, events: &Events) {
+        for event in &events.0 {
+            match event {
+                Event::Background(background) => {
+                    self.add_file_path(&background.file_name, AssetKind::Background)
+                }
+                Event::Video(video) => self.add_file_path(&video.file_name, AssetKind::Video),
+                Event::SpriteLegacy(sprite) => {
+                    self.add_file_path(&sprite.file_name, AssetKind::Sprite)
+                }
+                Event::AnimationLegacy(animation) => {
+                    self.add_file_path(&animation.file_name, AssetKind::Sprite)
+                }
+                Event::SampleLegacy(sample) => {
+                    self.add_file_path(&sample.file_name, AssetKind::StoryboardSample)
+                }
+                Event::AudioSample(sample) => {
+                    self.add_file_path(&sample.filepath, AssetKind::StoryboardSample)
+                }
+                Event::StoryboardObject(object) => match &object.object_type {
+                    ObjectType::Sprite(sprite) => {
+                        self.add_file_path(&sprite.filepath, AssetKind::Sprite)
+                    }
+                    ObjectType::Animation(animation) => {
+                        let path = path_to_str(animation.filepath.get());
+                        for frame in frame_paths(&path, animation.frame_count) {
+                            self.add(&frame, AssetKind::AnimationFrame);
+                        }
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+
+    /// Referenced files, sorted by their normalized path.
+    pub fn assets(&self) -> impl Iterator<Item = &Asset> {
+        self.assets.values()
+    }
+
+    /// Gets a referenced file by its path, which is compared case-insensitively.
+    pub fn get(&self, path: &str) -> Option<&Asset> {
+        self.assets.get(&normalize_path(path))
+    }
+
+    /// Returns `true` if the file is used, either by being referenced or as a custom hitsound.
+    pub fn is_used(&self, path: &str) -> bool {
+        let path = normalize_path(path);
+        if self.assets.contains_key(&path) {
+            return true;
+        }
+
+        // custom hitsounds are only looked up in the root of the folder
+        match path.rsplit_once('.') {
+            Some((stem, extension)) => {
+                !stem.contains('/')
+                    && HITSOUND_EXTENSIONS.contains(&extension)
+                    && self.hitsounds.contains(stem)
+            }
+            None => false,
+        }
+    }
+
+    /// Compares the inventory with the files of a beatmap folder, given as paths relative to the folder.
+    pub fn report<I, S>(&self, files: I) -> AssetReport
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut existing = BTreeSet::new();
+        let mut unused = Vec::new();
+
+        for file in files {
+            let file = file.as_ref();
+            let normalized = normalize_path(file);
+
+            if normalized.ends_with(".osu") || normalized.ends_with(".osb") {
+                continue;
+            }
+            if !self.is_used(file) {
+                unused.push(file.replace('\\', "/"));
+            }
+
+            existing.insert(normalized);
+        }
+
+        let missing = self
+            .assets
+            .iter()
+            .filter(|(normalized, _)| !existing.contains(*normalized))
+            .map(|(_, asset)| asset.path.clone())
+            .collect();
+        unused.sort();
+
+        AssetReport { missing, unused }
+    }
+
+    /// Compares the inventory with the files in a beatmap folder and its subfolders.
+    pub fn check_folder<P: AsRef<Path>>(&self, folder: P) -> io::Result<AssetReport> {
+        let mut files = Vec::new();
+        list_files(folder.as_ref(), "", &mut files)?;
+
+        Ok(self.report(files))
+    }
+}
+
+pub(crate) fn list_files(folder: &Path, prefix: &str, files: &mut Vec<String>) -> io::Result<()> {
+    for entry in folder.read_dir()? {
+        let entry = entry?;
+        let path = format!("{prefix}{}", entry.file_name().to_string_lossy());
+
+        if entry.file_type()?.is_dir() {
+            list_files(&entry.path(), &format!("{path}/"), files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+impl OsuFile {
+    /// Collects every file referenced by the beatmap and its storyboard.
+    /// - See [`AssetInventory::add_osu_file`].
+    pub fn assets(&self) -> AssetInventory {
+        AssetInventory::from_osu_file(self)
+    }
+}
@@ -0,0 +1,36 @@
+use thiserror::Error;
+
+use crate::osu_file::diff::Section;
+use crate::osu_file::{self, osb};
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum BeatmapSetError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("The file {0} isn't valid UTF-8")]
+    InvalidUtf8(String),
+    #[error("Failed to parse the beatmap {name}")]
+    ParseOsuFile {
+        name: String,
+        #[source]
+        source: osu_file::Error<osu_file::ParseError>,
+    },
+    #[error("Failed to parse the storyboard {name}")]
+    ParseOsb {
+        name: String,
+        #[source]
+        source: osu_file::Error<osb::ParseError>,
+    },
+    #[error("There is no difficulty named {0}")]
+    UnknownDifficulty(String),
+    #[error("The section {0} doesn't have `key: value` fields")]
+    NotKeyValueSection(Section),
+    /// The value couldn't be set in a difficulty, in which case no difficulty is changed.
+    #[error("Failed to set `{key}` in {difficulty}: {reason}")]
+    InvalidField {
+        difficulty: String,
+        key: String,
+        reason: String,
+    },
+}
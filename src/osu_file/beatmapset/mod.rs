@@ -0,0 +1,373 @@
+//! A mapset folder, which is several .osu difficulties sharing one .osb storyboard and the same assets.
+
+pub mod error;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::assets::{path_to_str, AssetInventory};
+use super::diff::Section;
+use super::events::{Background, Event, Events};
+use super::{Difficulty, Editor, General, Metadata, Osb, OsuFile, VersionedToString};
+
+pub use error::*;
+
+/// Fields osu! expects to be the same in every difficulty of a mapset.
+pub const SHARED_FIELDS: [(Section, &str); 10] = [
+    (Section::General, "AudioFilename"),
+    (Section::General, "PreviewTime"),
+    (Section::Metadata, "Title"),
+    (Section::Metadata, "TitleUnicode"),
+    (Section::Metadata, "Artist"),
+    (Section::Metadata, "ArtistUnicode"),
+    (Section::Metadata, "Creator"),
+    (Section::Metadata, "Source"),
+    (Section::Metadata, "Tags"),
+    (Section::Metadata, "BeatmapSetID"),
+];
+
+/// Something that should be the same in every difficulty.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SetField {
+    /// A `key: value` field, which is one of [`SHARED_FIELDS`].
+    Field { section: Section, key: &'static str },
+    /// File name of the background image.
+    Background,
+    /// The shared .osb storyboard in [`OsuFile::osb`].
+    Storyboard,
+}
+
+impl SetField {
+    /// Value of the field in a difficulty.
+    pub fn value(&self, osu_file: &OsuFile) -> Option<String> {
+        let version = osu_file.version;
+
+        match *self {
+            SetField::Field { section, key } => {
+                let fields = match section {
+                    Section::General => osu_file.general.as_ref()?.fields(version),
+                    Section::Editor => osu_file.editor.as_ref()?.fields(version),
+                    Section::Metadata => osu_file.metadata.as_ref()?.fields(version),
+                    Section::Difficulty => osu_file.difficulty.as_ref()?.fields(version),
+                    _ => return None,
+                };
+
+                fields.into_iter().find(|(k, _)| *k == key)?.1
+            }
+            SetField::Background => background(osu_file),
+            SetField::Storyboard => osu_file.osb.as_ref()?.to_string(version),
+        }
+    }
+}
+
+/// A [`SetField`] that is different between difficulties.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Inconsistency {
+    pub field: SetField,
+    /// Each value with the names of the difficulties that have it, in the order the values were first found.
+    /// - The value is `None` if a difficulty doesn't have it.
+    /// - Storyboards are compared by their written .osb contents.
+    pub values: Vec<(Option<String>, Vec<String>)>,
+}
+
+/// Difficulties of a mapset.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BeatmapSet {
+    /// Difficulties by their file name.
+    /// - The shared storyboard is in [`OsuFile::osb`] of each difficulty.
+    pub difficulties: BTreeMap<String, OsuFile>,
+    /// File name of the shared .osb storyboard.
+    /// - If `None` when saving, the name is made from the metadata like osu! does.
+    pub osb_name: Option<String>,
+}
+
+/// Default .osb file name of a mapset, made from the metadata of the first beatmap that has it.
+/// - Format is `Artist - Title (Creator).osb`.
+pub(crate) fn default_osb_name<'a, I>(beatmaps: I) -> String
+where
+    I: IntoIterator<Item = &'a OsuFile>,
+{
+    let metadata = beatmaps
+        .into_iter()
+        .find_map(|osu_file| osu_file.metadata.as_ref());
+    let field = |f: fn(&Metadata) -> Option<String>| metadata.and_then(f).unwrap_or_default();
+
+    format!(
+        "{} - {} ({}).osb",
+        field(|m| m.artist.as_ref().map(|a| a.0.clone())),
+        field(|m| m.title.as_ref().map(|t| t.0.clone())),
+        field(|m| m.creator.as_ref().map(|c| c.0.clone())),
+    )
+}
+
+/// File name of the background image in the events, without quotes.
+fn background(osu_file: &OsuFile) -> Option<String> {
+    osu_file
+        .events
+        .as_ref()?
+        .0
+        .iter()
+        .find_map(|event| match event {
+            Event::Background(background) => Some(path_to_str(background.file_name.get())),
+            _ => None,
+        })
+}
+
+fn has_extension(name: &str, extension: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case(extension))
+}
+
+impl BeatmapSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the .osu and .osb files of a mapset folder, attaching the storyboard to each difficulty.
+    /// - Only files directly in the folder are loaded, like osu! does.
+    /// - If there are several .osb files, the first one by name is used.
+    pub fn open<P: AsRef<Path>>(folder: P) -> Result<Self, BeatmapSetError> {
+        let mut files = Vec::new();
+        for entry in folder.as_ref().read_dir()? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                files.push((
+                    entry.file_name().to_string_lossy().to_string(),
+                    entry.path(),
+                ));
+            }
+        }
+        files.sort();
+
+        let mut set = BeatmapSet::new();
+        let mut osb = None;
+
+        for (name, path) in files {
+            let is_osu = has_extension(&name, "osu");
+            if !is_osu && (!has_extension(&name, "osb") || osb.is_some()) {
+                continue;
+            }
+
+            let text = String::from_utf8(std::fs::read(path)?)
+                .map_err(|_| BeatmapSetError::InvalidUtf8(name.clone()))?;
+
+            if is_osu {
+                let osu_file =
+                    text.parse::<OsuFile>()
+                        .map_err(|source| BeatmapSetError::ParseOsuFile {
+                            name: name.clone(),
+                            source,
+                        })?;
+                set.difficulties.insert(name, osu_file);
+            } else {
+                osb = Some((name, text));
+            }
+        }
+
+        if let Some((name, osb)) = osb {
+            for osu_file in set.difficulties.values_mut() {
+                osu_file
+                    .append_osb(&osb)
+                    .map_err(|source| BeatmapSetError::ParseOsb {
+                        name: name.clone(),
+                        source,
+                    })?;
+            }
+            set.osb_name = Some(name);
+        }
+
+        Ok(set)
+    }
+
+    /// Writes every difficulty and the shared storyboard into a folder.
+    /// - The storyboard is taken from the first difficulty with [`OsuFile::osb`].
+    pub fn save<P: AsRef<Path>>(&self, folder: P) -> Result<(), BeatmapSetError> {
+        let folder = folder.as_ref();
+
+        for (name, osu_file) in &self.difficulties {
+            std::fs::write(folder.join(name), osu_file.to_string())?;
+        }
+
+        if let Some(osb) = self.difficulties.values().find_map(|o| o.osb_to_string()) {
+            std::fs::write(folder.join(self.osb_file_name()), osb)?;
+        }
+
+        Ok(())
+    }
+
+    /// File name of the .osb storyboard, made from the metadata if [`BeatmapSet::osb_name`] is `None`.
+    /// - Format is `Artist - Title (Creator).osb`.
+    pub fn osb_file_name(&self) -> String {
+        match &self.osb_name {
+            Some(name) => name.clone(),
+            None => default_osb_name(self.difficulties.values()),
+        }
+    }
+
+    /// The shared storyboard, taken from the first difficulty with [`OsuFile::osb`].
+    pub fn osb(&self) -> Option<&Osb> {
+        self.difficulties.values().find_map(|o| o.osb.as_ref())
+    }
+
+    /// Finds every [`SHARED_FIELDS`] field, background and storyboard that isn't the same in every difficulty.
+    pub fn check(&self) -> Vec<Inconsistency> {
+        let fields = SHARED_FIELDS
+            .iter()
+            .map(|(section, key)| SetField::Field {
+                section: *section,
+                key,
+            })
+            .chain([SetField::Background, SetField::Storyboard]);
+        let mut inconsistencies = Vec::new();
+
+        for field in fields {
+            let mut values: Vec<(Option<String>, Vec<String>)> = Vec::new();
+
+            for (name, osu_file) in &self.difficulties {
+                let value = field.value(osu_file);
+                match values.iter_mut().find(|(v, _)| *v == value) {
+                    Some((_, names)) => names.push(name.clone()),
+                    None => values.push((value, vec![name.clone()])),
+                }
+            }
+
+            if values.len() > 1 {
+                inconsistencies.push(Inconsistency { field, values });
+            }
+        }
+
+        inconsistencies
+    }
+
+    /// Runs an edit on every difficulty, with the file name of the difficulty.
+    pub fn edit<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &mut OsuFile),
+    {
+        for (name, osu_file) in &mut self.difficulties {
+            f(name, osu_file);
+        }
+    }
+
+    /// Sets a `key: value` field in every difficulty, or clears it if the value is `None`.
+    /// - The value is parsed with the version of each difficulty, and nothing is changed if it fails in any of them.
+    pub fn set_field(
+        &mut self,
+        section: Section,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<(), BeatmapSetError> {
+        macro_rules! set_fields {
+            ($field:ident, $section_type:ty) => {{
+                let mut sections = Vec::new();
+                for (name, osu_file) in &self.difficulties {
+                    let mut fields = osu_file.$field.clone().unwrap_or_else(<$section_type>::new);
+                    fields
+                        .set_field(key, value, osu_file.version)
+                        .map_err(|err| BeatmapSetError::InvalidField {
+                            difficulty: name.clone(),
+                            key: key.to_string(),
+                            reason: err.to_string(),
+                        })?;
+                    sections.push(fields);
+                }
+
+                for (osu_file, fields) in self.difficulties.values_mut().zip(sections) {
+                    osu_file.$field = Some(fields);
+                }
+            }};
+        }
+
+        match section {
+            Section::General => set_fields!(general, General),
+            Section::Editor => set_fields!(editor, Editor),
+            Section::Metadata => set_fields!(metadata, Metadata),
+            Section::Difficulty => set_fields!(difficulty, Difficulty),
+            _ => return Err(BeatmapSetError::NotKeyValueSection(section)),
+        }
+
+        Ok(())
+    }
+
+    /// Sets the background image of every difficulty.
+    /// - Difficulties without a background get one after the comments at the start of the events.
+    pub fn set_background(&mut self, file_name: &str) {
+        for osu_file in self.difficulties.values_mut() {
+            let events = osu_file.events.get_or_insert_with(Events::default);
+
+            let existing = events.0.iter_mut().find_map(|event| match event {
+                Event::Background(background) => Some(background),
+                _ => None,
+            });
+            match existing {
+                Some(background) => background.file_name = file_name.into(),
+                None => {
+                    let index = events
+                        .0
+                        .iter()
+                        .take_while(|event| matches!(event, Event::Comment(_)))
+                        .count();
+                    events.0.insert(
+                        index,
+                        Event::Background(Background {
+                            start_time: 0,
+                            file_name: file_name.into(),
+                            position: None,
+                            commands: Vec::new(),
+                        }),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Sets the shared storyboard of every difficulty, or removes it if `None`.
+    pub fn set_osb(&mut self, osb: Option<Osb>) {
+        for osu_file in self.difficulties.values_mut() {
+            osu_file.osb = osb.clone();
+        }
+    }
+
+    /// Copies the [`SHARED_FIELDS`] fields, background and storyboard of a difficulty to every other difficulty.
+    /// - If a field can't be set in a difficulty, nothing is changed.
+    pub fn sync_from(&mut self, name: &str) -> Result<(), BeatmapSetError> {
+        let source = self
+            .difficulties
+            .get(name)
+            .ok_or_else(|| BeatmapSetError::UnknownDifficulty(name.to_string()))?
+            .clone();
+
+        // fields are set on a copy so an error doesn't leave the set half synced
+        let mut synced = self.clone();
+        for (section, key) in SHARED_FIELDS {
+            let value = SetField::Field { section, key }.value(&source);
+            synced.set_field(section, key, value.as_deref())?;
+        }
+        *self = synced;
+
+        match background(&source) {
+            Some(background) => self.set_background(&background),
+            None => self.edit(|_, osu_file| {
+                if let Some(events) = &mut osu_file.events {
+                    events
+                        .0
+                        .retain(|event| !matches!(event, Event::Background(_)));
+                }
+            }),
+        }
+        self.set_osb(source.osb);
+
+        Ok(())
+    }
+
+    /// Collects every file referenced by the difficulties and the storyboard.
+    pub fn assets(&self) -> AssetInventory {
+        let mut inventory = AssetInventory::new();
+        for osu_file in self.difficulties.values() {
+            inventory.add_osu_file(osu_file);
+        }
+        inventory
+    }
+}
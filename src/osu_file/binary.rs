@@ -0,0 +1,154 @@
+//! Reading and writing of the little-endian binary types used by osu!'s binary files.
+
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BinaryError {
+    #[error("Unexpected end of data at byte {position}")]
+    UnexpectedEof { position: usize },
+    /// The string doesn't start with `0x00` or `0x0b`, or isn't valid UTF-8.
+    #[error("Invalid string at byte {position}")]
+    InvalidString { position: usize },
+}
+
+pub(crate) struct BinaryReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+macro_rules! read_num {
+    ($name:ident, $type:ty) => {
+        pub fn $name(&mut self) -> Result<$type, BinaryError> {
+            let bytes = self.bytes(std::mem::size_of::<$type>())?;
+            Ok(<$type>::from_le_bytes(bytes.try_into().unwrap()))
+        }
+    };
+}
+
+impl<'a> BinaryReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], BinaryError> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(BinaryError::UnexpectedEof {
+                position: self.bytes.len(),
+            })?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+
+        Ok(bytes)
+    }
+
+    read_num!(u8, u8);
+    read_num!(u16, u16);
+    #[cfg(feature = "db")]
+    read_num!(i16, i16);
+    read_num!(i32, i32);
+    read_num!(u32, u32);
+    read_num!(i64, i64);
+    #[cfg(feature = "db")]
+    read_num!(f32, f32);
+    read_num!(f64, f64);
+
+    pub fn bool(&mut self) -> Result<bool, BinaryError> {
+        Ok(self.u8()? != 0)
+    }
+
+    pub fn uleb128(&mut self) -> Result<u64, BinaryError> {
+        let mut value = 0u64;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                value |= ((byte & 0x7f) as u64) << shift;
+            }
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    /// Reads a string, which is `None` if it's marked as not existing with `0x00`.
+    pub fn string(&mut self) -> Result<Option<String>, BinaryError> {
+        let position = self.position;
+
+        match self.u8()? {
+            0x00 => Ok(None),
+            0x0b => {
+                let len = self.uleb128()? as usize;
+                let bytes = self.bytes(len)?;
+
+                String::from_utf8(bytes.to_vec())
+                    .map(Some)
+                    .map_err(|_| BinaryError::InvalidString { position })
+            }
+            _ => Err(BinaryError::InvalidString { position }),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct BinaryWriter {
+    pub bytes: Vec<u8>,
+}
+
+macro_rules! write_num {
+    ($name:ident, $type:ty) => {
+        pub fn $name(&mut self, value: $type) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    };
+}
+
+impl BinaryWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    write_num!(u8, u8);
+    write_num!(u16, u16);
+    #[cfg(feature = "db")]
+    write_num!(i16, i16);
+    write_num!(i32, i32);
+    write_num!(u32, u32);
+    write_num!(i64, i64);
+    #[cfg(feature = "db")]
+    write_num!(f32, f32);
+    write_num!(f64, f64);
+
+    pub fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    pub fn uleb128(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+
+            if value == 0 {
+                self.u8(byte);
+                return;
+            }
+            self.u8(byte | 0x80);
+        }
+    }
+
+    pub fn string(&mut self, value: Option<&str>) {
+        match value {
+            Some(value) => {
+                self.u8(0x0b);
+                self.uleb128(value.len() as u64);
+                self.bytes.extend_from_slice(value.as_bytes());
+            }
+            None => self.u8(0x00),
+        }
+    }
+}
@@ -19,6 +19,7 @@ pub use types::*;
 use super::{Error, Version, VersionedDefault, VersionedFromStr, VersionedToString, MIN_VERSION};
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Colours(pub Vec<Colour>);
 
 impl VersionedFromStr for Colours {
@@ -74,6 +75,7 @@ impl VersionedDefault for Colours {
 
 /// Struct representing a single `colour` component in the `Colours` section.
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum Colour {
     /// Additive combo colours.
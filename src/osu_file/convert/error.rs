@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+use crate::osu_file::{self, Version, LATEST_VERSION, LAZER_VERSION, MIN_VERSION};
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+/// Error used when an [`OsuFile`][crate::OsuFile] can't be converted to another version.
+pub enum ConvertError {
+    /// The target version isn't a version that can be written.
+    #[error(
+        "Invalid file version {0}, expected versions from {MIN_VERSION} ~ {LATEST_VERSION} or v{LAZER_VERSION}"
+    )]
+    InvalidVersion(Version),
+    /// The file written in the target version couldn't be read back.
+    #[error("Failed to read the file back in the target version")]
+    Reparse(#[from] osu_file::Error<osu_file::ParseError>),
+}
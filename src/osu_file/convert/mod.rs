@@ -0,0 +1,204 @@
+//! Conversion of an [`OsuFile`] to another file format version, with a report of what couldn't be kept.
+
+pub mod error;
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::diff::{Change, FieldChange, ObjectChange, Section};
+use super::{
+    Integer, OsuFile, Version, VersionedToString, LATEST_VERSION, LAZER_VERSION, MIN_VERSION,
+};
+
+pub use error::*;
+
+/// Data that didn't survive a conversion.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConversionLoss {
+    /// Can't be written in the target version, and was removed.
+    /// - `value` is the field or item as written in the original version.
+    Lost { section: Section, value: String },
+    /// Written in the target version, but reads back as a different value.
+    Approximated {
+        section: Section,
+        old: String,
+        new: String,
+    },
+}
+
+impl ConversionLoss {
+    /// Section the loss belongs to.
+    pub fn section(&self) -> Section {
+        match self {
+            ConversionLoss::Lost { section, .. } | ConversionLoss::Approximated { section, .. } => {
+                *section
+            }
+        }
+    }
+}
+
+/// Report of a conversion made with [`OsuFile::convert_to`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ConversionReport {
+    pub from: Version,
+    pub to: Version,
+    /// Everything lost or approximated, ordered by section.
+    pub losses: Vec<ConversionLoss>,
+}
+
+impl ConversionReport {
+    /// Returns `true` if nothing was lost or approximated.
+    pub fn is_lossless(&self) -> bool {
+        self.losses.is_empty()
+    }
+
+    /// Returns a human readable report of the losses.
+    /// - Lines starting with `-` are lost, and `~` are approximated.
+    pub fn report(&self) -> String {
+        let mut lines = vec![format!("osu file format v{} -> v{}", self.from, self.to)];
+        let mut current_section = None;
+
+        for loss in &self.losses {
+            let section = loss.section();
+            if current_section != Some(section) {
+                lines.push(format!("[{section}]"));
+                current_section = Some(section);
+            }
+
+            match loss {
+                ConversionLoss::Lost { value, .. } => lines.push(format!("- {value}")),
+                ConversionLoss::Approximated { old, new, .. } => {
+                    lines.push(format!("~ {old} -> {new}"))
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl Display for ConversionReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.report())
+    }
+}
+
+impl OsuFile {
+    /// Converts the file to another file format version.
+    /// - The file is written in the target version and read back, so everything kept is what the target version can store.
+    /// - Fields and sections the target version doesn't have, such as `OverlayPosition` before v14 or `Colours` before v5, are removed.
+    /// - Hitobject, timing point and event times are kept as they are, as the 24ms offset of v3 and v4 is applied when reading and writing those versions.
+    /// - `PreviewTime` is read as it's written, so it's moved by the 24ms offset when converting between v3 or v4 and later versions.
+    /// - The storyboard from [`append_osb`][Self::append_osb] is kept as it is.
+    pub fn convert_to(&mut self, version: Version) -> Result<ConversionReport, ConvertError> {
+        if !(MIN_VERSION..=LATEST_VERSION).contains(&version) && version != LAZER_VERSION {
+            return Err(ConvertError::InvalidVersion(version));
+        }
+
+        let from = self.version;
+        let mut shifted = self.clone();
+        shifted.shift_preview_time(old_time_offset(version) - old_time_offset(from));
+
+        let mut written = shifted.clone();
+        written.version = version;
+        let mut converted = OsuFile::from_str(&written.to_string())?;
+        converted.osb = self.osb.take();
+
+        // the moved preview time is the same point in the beatmap, so it's not a loss
+        let losses = shifted
+            .diff(&converted)
+            .changes
+            .into_iter()
+            .filter_map(|change| loss(change, from, version))
+            .collect();
+
+        *self = converted;
+
+        Ok(ConversionReport {
+            from,
+            to: version,
+            losses,
+        })
+    }
+}
+
+impl OsuFile {
+    fn shift_preview_time(&mut self, offset: Integer) {
+        if let Some(preview_time) = self
+            .general
+            .as_mut()
+            .and_then(|general| general.preview_time.as_mut())
+        {
+            // -1 means there is no preview time
+            if preview_time.0 != -1 {
+                preview_time.0 -= offset;
+            }
+        }
+    }
+}
+
+/// Offset of the times written in v3 and v4 files.
+fn old_time_offset(version: Version) -> Integer {
+    if (3..=4).contains(&version) {
+        24
+    } else {
+        0
+    }
+}
+
+fn loss(change: Change, from: Version, to: Version) -> Option<ConversionLoss> {
+    fn object_loss<T: VersionedToString>(
+        section: Section,
+        change: ObjectChange<T>,
+        from: Version,
+        to: Version,
+    ) -> Option<ConversionLoss> {
+        match change {
+            // only possible if the original version didn't write the item
+            ObjectChange::Added(_) => None,
+            ObjectChange::Removed(old) => Some(ConversionLoss::Lost {
+                section,
+                value: old.to_string(from).unwrap_or_default(),
+            }),
+            ObjectChange::Changed { old, new } | ObjectChange::Moved { old, new } => {
+                Some(ConversionLoss::Approximated {
+                    section,
+                    old: old.to_string(from).unwrap_or_default(),
+                    new: new.to_string(to).unwrap_or_default(),
+                })
+            }
+        }
+    }
+
+    match change {
+        Change::Field(FieldChange {
+            section,
+            key,
+            old: Some(old),
+            new,
+        }) => {
+            // colours are compared as whole lines
+            let line = |value: String| match section {
+                Section::Colours => value,
+                _ => format!("{key}: {value}"),
+            };
+
+            Some(match new {
+                Some(new) => ConversionLoss::Approximated {
+                    section,
+                    old: line(old),
+                    new: line(new),
+                },
+                None => ConversionLoss::Lost {
+                    section,
+                    value: line(old),
+                },
+            })
+        }
+        Change::HitObject(change) => object_loss(Section::HitObjects, change, from, to),
+        Change::TimingPoint(change) => object_loss(Section::TimingPoints, change, from, to),
+        Change::Event(change) => object_loss(Section::Events, change, from, to),
+        _ => None,
+    }
+}
@@ -0,0 +1,75 @@
+use std::io::{Read, Write};
+use std::path::Path;
+
+use super::{db_io, DbError};
+use crate::osu_file::binary::{BinaryReader, BinaryWriter};
+
+/// A named list of beatmaps.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Collection {
+    pub name: Option<String>,
+    /// MD5 hashes of the beatmaps' .osu files.
+    pub beatmap_md5s: Vec<Option<String>>,
+}
+
+/// The collection.db database, which holds the player's collections.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct CollectionDb {
+    /// Version of the game that wrote the database, such as `20250107`.
+    pub version: i32,
+    pub collections: Vec<Collection>,
+}
+
+impl CollectionDb {
+    pub fn new(version: i32) -> Self {
+        Self {
+            version,
+            collections: Vec::new(),
+        }
+    }
+
+    fn read(reader: &mut BinaryReader) -> Result<Self, DbError> {
+        let mut db = CollectionDb::new(reader.i32()?);
+
+        let collection_count = reader.i32()?;
+        for _ in 0..collection_count {
+            let mut collection = Collection {
+                name: reader.string()?,
+                beatmap_md5s: Vec::new(),
+            };
+
+            let beatmap_count = reader.i32()?;
+            for _ in 0..beatmap_count {
+                collection.beatmap_md5s.push(reader.string()?);
+            }
+
+            db.collections.push(collection);
+        }
+
+        Ok(db)
+    }
+
+    fn write_into(&self, writer: &mut BinaryWriter) -> Result<(), DbError> {
+        writer.i32(self.version);
+        writer.i32(self.collections.len() as i32);
+
+        for collection in &self.collections {
+            writer.string(collection.name.as_deref());
+            writer.i32(collection.beatmap_md5s.len() as i32);
+            for md5 in &collection.beatmap_md5s {
+                writer.string(md5.as_deref());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The collection with the name.
+    pub fn collection(&self, name: &str) -> Option<&Collection> {
+        self.collections
+            .iter()
+            .find(|collection| collection.name.as_deref() == Some(name))
+    }
+}
+
+db_io!(CollectionDb);
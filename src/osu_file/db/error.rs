@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+use crate::osu_file::binary::BinaryError;
+use crate::osu_file::replay::ReplayError;
+
+/// Error used when there was a problem reading or writing osu!'s databases.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum DbError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Binary(#[from] BinaryError),
+    /// Error reading or writing a score in scores.db.
+    #[error(transparent)]
+    Replay(#[from] ReplayError),
+    #[error("Unknown game mode {0}")]
+    UnknownMode(u8),
+    /// A star rating doesn't start with `0x08` or its value isn't marked as a float or double.
+    #[error("Invalid star rating entry")]
+    InvalidStarRating,
+}
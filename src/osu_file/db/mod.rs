@@ -0,0 +1,369 @@
+//! Reading and writing of the osu! client's osu!.db, collection.db and scores.db databases.
+//! - Each database keeps the `version` it was read with, and is written in the format of that version.
+
+pub mod collection;
+pub mod error;
+pub mod scores;
+pub mod types;
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+use super::binary::{BinaryReader, BinaryWriter};
+use super::general::Mode;
+use super::replay::{mode_from_u8, mode_to_u8, Mods};
+
+pub use collection::*;
+pub use error::*;
+pub use scores::*;
+pub use types::*;
+
+/// First version where difficulty values are floats and star ratings are cached.
+const FLOAT_DIFFICULTY_VERSION: i32 = 20140609;
+/// First version without the size of each beatmap entry.
+const NO_ENTRY_SIZE_VERSION: i32 = 20191106;
+/// First version where star ratings are floats instead of doubles.
+const FLOAT_STAR_RATING_VERSION: i32 = 20250107;
+
+/// Implements reading from bytes, a reader and a file, and writing to bytes, a writer and a file.
+macro_rules! db_io {
+    ($db:ty) => {
+        impl $db {
+            /// Reads the database from its bytes.
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, DbError> {
+                Self::read(&mut BinaryReader::new(bytes))
+            }
+
+            /// Reads the database.
+            pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, DbError> {
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes)?;
+                Self::from_bytes(&bytes)
+            }
+
+            /// Reads the database from a file.
+            pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DbError> {
+                Self::from_bytes(&std::fs::read(path)?)
+            }
+
+            /// Writes the database into bytes.
+            pub fn to_bytes(&self) -> Result<Vec<u8>, DbError> {
+                let mut writer = BinaryWriter::new();
+                self.write_into(&mut writer)?;
+                Ok(writer.bytes)
+            }
+
+            /// Writes the database.
+            pub fn write<W: Write>(&self, mut writer: W) -> Result<(), DbError> {
+                writer.write_all(&self.to_bytes()?)?;
+                Ok(())
+            }
+
+            /// Writes the database to a file.
+            pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), DbError> {
+                std::fs::write(path, self.to_bytes()?)?;
+                Ok(())
+            }
+        }
+    };
+}
+
+pub(crate) use db_io;
+
+fn read_mode(reader: &mut BinaryReader) -> Result<Mode, DbError> {
+    let mode = reader.u8()?;
+    mode_from_u8(mode).map_err(|_| DbError::UnknownMode(mode))
+}
+
+/// The osu!.db database, which holds the beatmaps the client knows about.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OsuDb {
+    /// Version of the game that wrote the database, such as `20250107`.
+    pub version: i32,
+    pub folder_count: i32,
+    /// Whether the account isn't locked.
+    pub account_unlocked: bool,
+    /// Time the account gets unlocked, in Windows ticks.
+    pub unlock_date: i64,
+    pub player_name: Option<String>,
+    pub beatmaps: Vec<DbBeatmap>,
+    /// Permissions of the player, as bit flags.
+    pub user_permissions: i32,
+}
+
+fn read_star_ratings(reader: &mut BinaryReader, version: i32) -> Result<Vec<(Mods, f64)>, DbError> {
+    let count = reader.i32()?;
+    let mut ratings = Vec::with_capacity(count.clamp(0, 64) as usize);
+
+    for _ in 0..count {
+        if reader.u8()? != 0x08 {
+            return Err(DbError::InvalidStarRating);
+        }
+        let mods = Mods(reader.i32()? as u32);
+        let rating = match reader.u8()? {
+            0x0c if version >= FLOAT_STAR_RATING_VERSION => reader.f32()? as f64,
+            0x0d => reader.f64()?,
+            _ => return Err(DbError::InvalidStarRating),
+        };
+
+        ratings.push((mods, rating));
+    }
+
+    Ok(ratings)
+}
+
+fn write_star_ratings(writer: &mut BinaryWriter, ratings: &[(Mods, f64)], version: i32) {
+    writer.i32(ratings.len() as i32);
+
+    for (mods, rating) in ratings {
+        writer.u8(0x08);
+        writer.i32(mods.0 as i32);
+        if version >= FLOAT_STAR_RATING_VERSION {
+            writer.u8(0x0c);
+            writer.f32(*rating as f32);
+        } else {
+            writer.u8(0x0d);
+            writer.f64(*rating);
+        }
+    }
+}
+
+fn read_difficulty_value(reader: &mut BinaryReader, version: i32) -> Result<f32, DbError> {
+    if version >= FLOAT_DIFFICULTY_VERSION {
+        Ok(reader.f32()?)
+    } else {
+        Ok(reader.u8()? as f32)
+    }
+}
+
+fn write_difficulty_value(writer: &mut BinaryWriter, value: f32, version: i32) {
+    if version >= FLOAT_DIFFICULTY_VERSION {
+        writer.f32(value);
+    } else {
+        writer.u8(value as u8);
+    }
+}
+
+impl DbBeatmap {
+    fn read(reader: &mut BinaryReader, version: i32) -> Result<Self, DbError> {
+        if version < NO_ENTRY_SIZE_VERSION {
+            reader.i32()?;
+        }
+
+        let mut beatmap = DbBeatmap {
+            artist: reader.string()?,
+            artist_unicode: reader.string()?,
+            title: reader.string()?,
+            title_unicode: reader.string()?,
+            creator: reader.string()?,
+            version: reader.string()?,
+            audio_filename: reader.string()?,
+            md5: reader.string()?,
+            osu_file_name: reader.string()?,
+            ranked_status: reader.u8()?.into(),
+            hitcircle_count: reader.u16()?,
+            slider_count: reader.u16()?,
+            spinner_count: reader.u16()?,
+            last_modified: reader.i64()?,
+            approach_rate: read_difficulty_value(reader, version)?,
+            circle_size: read_difficulty_value(reader, version)?,
+            hp_drain_rate: read_difficulty_value(reader, version)?,
+            overall_difficulty: read_difficulty_value(reader, version)?,
+            slider_multiplier: reader.f64()?,
+            ..Default::default()
+        };
+
+        if version >= FLOAT_DIFFICULTY_VERSION {
+            beatmap.star_ratings = StarRatings {
+                osu: read_star_ratings(reader, version)?,
+                taiko: read_star_ratings(reader, version)?,
+                catch: read_star_ratings(reader, version)?,
+                mania: read_star_ratings(reader, version)?,
+            };
+        }
+
+        beatmap.drain_time = reader.i32()?;
+        beatmap.total_time = reader.i32()?;
+        beatmap.preview_time = reader.i32()?;
+
+        let timing_point_count = reader.i32()?;
+        for _ in 0..timing_point_count {
+            beatmap.timing_points.push(DbTimingPoint {
+                beat_length: reader.f64()?,
+                time: reader.f64()?,
+                uninherited: reader.bool()?,
+            });
+        }
+
+        beatmap.beatmap_id = reader.i32()?;
+        beatmap.beatmap_set_id = reader.i32()?;
+        beatmap.thread_id = reader.i32()?;
+        for grade in &mut beatmap.grades {
+            *grade = reader.u8()?;
+        }
+        beatmap.local_offset = reader.i16()?;
+        beatmap.stack_leniency = reader.f32()?;
+        beatmap.mode = read_mode(reader)?;
+        beatmap.source = reader.string()?;
+        beatmap.tags = reader.string()?;
+        beatmap.online_offset = reader.i16()?;
+        beatmap.title_font = reader.string()?;
+        beatmap.unplayed = reader.bool()?;
+        beatmap.last_played = reader.i64()?;
+        beatmap.osz2 = reader.bool()?;
+        beatmap.folder_name = reader.string()?;
+        beatmap.last_checked = reader.i64()?;
+        beatmap.ignore_beatmap_sound = reader.bool()?;
+        beatmap.ignore_beatmap_skin = reader.bool()?;
+        beatmap.disable_storyboard = reader.bool()?;
+        beatmap.disable_video = reader.bool()?;
+        beatmap.visual_override = reader.bool()?;
+        if version < FLOAT_DIFFICULTY_VERSION {
+            beatmap.unknown = reader.i16()?;
+        }
+        beatmap.last_modified_seconds = reader.i32()?;
+        beatmap.mania_scroll_speed = reader.u8()?;
+
+        Ok(beatmap)
+    }
+
+    fn write_into(&self, writer: &mut BinaryWriter, version: i32) {
+        let mut entry = BinaryWriter::new();
+
+        for s in [
+            &self.artist,
+            &self.artist_unicode,
+            &self.title,
+            &self.title_unicode,
+            &self.creator,
+            &self.version,
+            &self.audio_filename,
+            &self.md5,
+            &self.osu_file_name,
+        ] {
+            entry.string(s.as_deref());
+        }
+        entry.u8(self.ranked_status.into());
+        entry.u16(self.hitcircle_count);
+        entry.u16(self.slider_count);
+        entry.u16(self.spinner_count);
+        entry.i64(self.last_modified);
+        for value in [
+            self.approach_rate,
+            self.circle_size,
+            self.hp_drain_rate,
+            self.overall_difficulty,
+        ] {
+            write_difficulty_value(&mut entry, value, version);
+        }
+        entry.f64(self.slider_multiplier);
+
+        if version >= FLOAT_DIFFICULTY_VERSION {
+            for ratings in [
+                &self.star_ratings.osu,
+                &self.star_ratings.taiko,
+                &self.star_ratings.catch,
+                &self.star_ratings.mania,
+            ] {
+                write_star_ratings(&mut entry, ratings, version);
+            }
+        }
+
+        entry.i32(self.drain_time);
+        entry.i32(self.total_time);
+        entry.i32(self.preview_time);
+
+        entry.i32(self.timing_points.len() as i32);
+        for timing_point in &self.timing_points {
+            entry.f64(timing_point.beat_length);
+            entry.f64(timing_point.time);
+            entry.bool(timing_point.uninherited);
+        }
+
+        entry.i32(self.beatmap_id);
+        entry.i32(self.beatmap_set_id);
+        entry.i32(self.thread_id);
+        for grade in self.grades {
+            entry.u8(grade);
+        }
+        entry.i16(self.local_offset);
+        entry.f32(self.stack_leniency);
+        entry.u8(mode_to_u8(self.mode));
+        entry.string(self.source.as_deref());
+        entry.string(self.tags.as_deref());
+        entry.i16(self.online_offset);
+        entry.string(self.title_font.as_deref());
+        entry.bool(self.unplayed);
+        entry.i64(self.last_played);
+        entry.bool(self.osz2);
+        entry.string(self.folder_name.as_deref());
+        entry.i64(self.last_checked);
+        entry.bool(self.ignore_beatmap_sound);
+        entry.bool(self.ignore_beatmap_skin);
+        entry.bool(self.disable_storyboard);
+        entry.bool(self.disable_video);
+        entry.bool(self.visual_override);
+        if version < FLOAT_DIFFICULTY_VERSION {
+            entry.i16(self.unknown);
+        }
+        entry.i32(self.last_modified_seconds);
+        entry.u8(self.mania_scroll_speed);
+
+        if version < NO_ENTRY_SIZE_VERSION {
+            writer.i32(entry.bytes.len() as i32);
+        }
+        writer.bytes.extend(entry.bytes);
+    }
+}
+
+impl OsuDb {
+    pub fn new(version: i32) -> Self {
+        Self {
+            version,
+            ..Default::default()
+        }
+    }
+
+    fn read(reader: &mut BinaryReader) -> Result<Self, DbError> {
+        let mut db = OsuDb {
+            version: reader.i32()?,
+            folder_count: reader.i32()?,
+            account_unlocked: reader.bool()?,
+            unlock_date: reader.i64()?,
+            player_name: reader.string()?,
+            ..Default::default()
+        };
+
+        let beatmap_count = reader.i32()?;
+        for _ in 0..beatmap_count {
+            db.beatmaps.push(DbBeatmap::read(reader, db.version)?);
+        }
+        db.user_permissions = reader.i32()?;
+
+        Ok(db)
+    }
+
+    fn write_into(&self, writer: &mut BinaryWriter) -> Result<(), DbError> {
+        writer.i32(self.version);
+        writer.i32(self.folder_count);
+        writer.bool(self.account_unlocked);
+        writer.i64(self.unlock_date);
+        writer.string(self.player_name.as_deref());
+        writer.i32(self.beatmaps.len() as i32);
+        for beatmap in &self.beatmaps {
+            beatmap.write_into(writer, self.version);
+        }
+        writer.i32(self.user_permissions);
+
+        Ok(())
+    }
+
+    /// The beatmap with the MD5 hash.
+    pub fn beatmap(&self, md5: &str) -> Option<&DbBeatmap> {
+        self.beatmaps
+            .iter()
+            .find(|beatmap| beatmap.md5.as_deref() == Some(md5))
+    }
+}
+
+db_io!(OsuDb);
@@ -0,0 +1,79 @@
+use std::io::{Read, Write};
+use std::path::Path;
+
+use super::{db_io, DbError};
+use crate::osu_file::binary::{BinaryReader, BinaryWriter};
+use crate::osu_file::replay::Replay;
+
+/// Local scores of a beatmap.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BeatmapScores {
+    /// MD5 hash of the beatmap's .osu file.
+    pub beatmap_md5: Option<String>,
+    /// Scores stored the same way as replays, but without any frames.
+    pub scores: Vec<Replay>,
+}
+
+/// The scores.db database, which holds the player's local scores.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScoresDb {
+    /// Version of the game that wrote the database, such as `20250107`.
+    pub version: i32,
+    pub beatmaps: Vec<BeatmapScores>,
+}
+
+impl ScoresDb {
+    pub fn new(version: i32) -> Self {
+        Self {
+            version,
+            beatmaps: Vec::new(),
+        }
+    }
+
+    fn read(reader: &mut BinaryReader) -> Result<Self, DbError> {
+        let mut db = ScoresDb::new(reader.i32()?);
+
+        let beatmap_count = reader.i32()?;
+        for _ in 0..beatmap_count {
+            let mut beatmap = BeatmapScores {
+                beatmap_md5: reader.string()?,
+                scores: Vec::new(),
+            };
+
+            let score_count = reader.i32()?;
+            for _ in 0..score_count {
+                beatmap.scores.push(Replay::read(reader)?);
+            }
+
+            db.beatmaps.push(beatmap);
+        }
+
+        Ok(db)
+    }
+
+    fn write_into(&self, writer: &mut BinaryWriter) -> Result<(), DbError> {
+        writer.i32(self.version);
+        writer.i32(self.beatmaps.len() as i32);
+
+        for beatmap in &self.beatmaps {
+            writer.string(beatmap.beatmap_md5.as_deref());
+            writer.i32(beatmap.scores.len() as i32);
+            for score in &beatmap.scores {
+                score.write_into(writer, false)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Scores of the beatmap with the MD5 hash.
+    pub fn scores(&self, beatmap_md5: &str) -> &[Replay] {
+        self.beatmaps
+            .iter()
+            .find(|beatmap| beatmap.beatmap_md5.as_deref() == Some(beatmap_md5))
+            .map(|beatmap| beatmap.scores.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+db_io!(ScoresDb);
@@ -0,0 +1,277 @@
+use rust_decimal::prelude::FromPrimitive;
+
+use crate::osu_file::general::Mode;
+use crate::osu_file::metadata::Metadata;
+use crate::osu_file::replay::Mods;
+use crate::osu_file::timingpoints::{Effects, SampleIndex, SampleSet, TimingPoint, Volume};
+use crate::osu_file::{Decimal, LATEST_VERSION};
+
+/// Ranked status of a beatmap in osu!.db.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RankedStatus {
+    Unknown,
+    Unsubmitted,
+    /// Pending, WIP or graveyard.
+    Pending,
+    Ranked,
+    Approved,
+    Qualified,
+    Loved,
+    /// A value that isn't known, which is kept as it is.
+    Other(u8),
+}
+
+impl From<u8> for RankedStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => RankedStatus::Unknown,
+            1 => RankedStatus::Unsubmitted,
+            2 => RankedStatus::Pending,
+            4 => RankedStatus::Ranked,
+            5 => RankedStatus::Approved,
+            6 => RankedStatus::Qualified,
+            7 => RankedStatus::Loved,
+            value => RankedStatus::Other(value),
+        }
+    }
+}
+
+impl From<RankedStatus> for u8 {
+    fn from(status: RankedStatus) -> Self {
+        match status {
+            RankedStatus::Unknown => 0,
+            RankedStatus::Unsubmitted => 1,
+            RankedStatus::Pending => 2,
+            RankedStatus::Ranked => 4,
+            RankedStatus::Approved => 5,
+            RankedStatus::Qualified => 6,
+            RankedStatus::Loved => 7,
+            RankedStatus::Other(value) => value,
+        }
+    }
+}
+
+/// Cached star ratings of a beatmap for each mode, by the mods they were calculated with.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StarRatings {
+    pub osu: Vec<(Mods, f64)>,
+    pub taiko: Vec<(Mods, f64)>,
+    pub catch: Vec<(Mods, f64)>,
+    pub mania: Vec<(Mods, f64)>,
+}
+
+impl StarRatings {
+    /// Star rating of the mode without mods, if it's cached.
+    pub fn nomod(&self, mode: Mode) -> Option<f64> {
+        let ratings = match mode {
+            Mode::Osu => &self.osu,
+            Mode::Taiko => &self.taiko,
+            Mode::Catch => &self.catch,
+            Mode::Mania => &self.mania,
+        };
+
+        ratings
+            .iter()
+            .find(|(mods, _)| *mods == Mods::NONE)
+            .map(|(_, rating)| *rating)
+    }
+}
+
+/// A timing point as stored in osu!.db, which only keeps the timing of the beatmap's timing points.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DbTimingPoint {
+    /// Beat length in milliseconds, or the negative inverse slider velocity multiplier for inherited points.
+    pub beat_length: f64,
+    /// Time in milliseconds.
+    pub time: f64,
+    pub uninherited: bool,
+}
+
+impl DbTimingPoint {
+    /// Converts to a [`TimingPoint`], with the fields osu!.db doesn't store set to their defaults.
+    /// - Returns `None` if the time or beat length isn't finite.
+    pub fn to_timing_point(&self) -> Option<TimingPoint> {
+        let time = rust_decimal::Decimal::from_f64(self.time)?;
+        let beat_length = rust_decimal::Decimal::from_f64(self.beat_length)?;
+
+        let mut timing_point = TimingPoint::new_uninherited(
+            0,
+            beat_length.into(),
+            4,
+            SampleSet::BeatmapDefault,
+            SampleIndex::OsuDefaultHitsounds,
+            Volume::new(100, LATEST_VERSION).unwrap(),
+            Effects::new(false, false),
+        );
+        *timing_point.time_mut() = Decimal::from(time);
+        timing_point.set_uninherited(self.uninherited);
+
+        Some(timing_point)
+    }
+
+    /// Converts from a [`TimingPoint`], or `None` if the time or beat length isn't a valid decimal.
+    pub fn from_timing_point(timing_point: &TimingPoint) -> Option<Self> {
+        Some(DbTimingPoint {
+            beat_length: timing_point.beat_length().to_f64()?,
+            time: timing_point.time().to_f64()?,
+            uninherited: timing_point.uninherited(),
+        })
+    }
+}
+
+/// A beatmap in osu!.db.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DbBeatmap {
+    pub artist: Option<String>,
+    pub artist_unicode: Option<String>,
+    pub title: Option<String>,
+    pub title_unicode: Option<String>,
+    pub creator: Option<String>,
+    /// Difficulty name.
+    pub version: Option<String>,
+    pub audio_filename: Option<String>,
+    /// MD5 hash of the .osu file.
+    pub md5: Option<String>,
+    /// Name of the .osu file.
+    pub osu_file_name: Option<String>,
+    pub ranked_status: RankedStatus,
+    pub hitcircle_count: u16,
+    pub slider_count: u16,
+    pub spinner_count: u16,
+    /// Last time the beatmap was modified, in Windows ticks.
+    pub last_modified: i64,
+    /// Approach rate, which is stored as a byte before version `20140609`.
+    pub approach_rate: f32,
+    pub circle_size: f32,
+    pub hp_drain_rate: f32,
+    pub overall_difficulty: f32,
+    pub slider_multiplier: f64,
+    /// Only exists since version `20140609`.
+    pub star_ratings: StarRatings,
+    /// Drain time in seconds.
+    pub drain_time: i32,
+    /// Total time in milliseconds.
+    pub total_time: i32,
+    /// Preview time in milliseconds.
+    pub preview_time: i32,
+    pub timing_points: Vec<DbTimingPoint>,
+    pub beatmap_id: i32,
+    pub beatmap_set_id: i32,
+    pub thread_id: i32,
+    /// Best grades in osu!, osu!taiko, osu!catch and osu!mania.
+    /// - `0` is SS with hidden or flashlight, up to `7` for D, and `9` if it wasn't played.
+    pub grades: [u8; 4],
+    pub local_offset: i16,
+    pub stack_leniency: f32,
+    pub mode: Mode,
+    pub source: Option<String>,
+    pub tags: Option<String>,
+    pub online_offset: i16,
+    pub title_font: Option<String>,
+    pub unplayed: bool,
+    /// Last time the beatmap was played, in Windows ticks.
+    pub last_played: i64,
+    pub osz2: bool,
+    /// Name of the beatmap's folder, relative to the songs folder.
+    pub folder_name: Option<String>,
+    /// Last time the beatmap was checked against the osu! servers, in Windows ticks.
+    pub last_checked: i64,
+    pub ignore_beatmap_sound: bool,
+    pub ignore_beatmap_skin: bool,
+    pub disable_storyboard: bool,
+    pub disable_video: bool,
+    pub visual_override: bool,
+    /// Unknown value that only exists before version `20140609`.
+    pub unknown: i16,
+    /// Seconds since the last modification, which is usually `0`.
+    pub last_modified_seconds: i32,
+    pub mania_scroll_speed: u8,
+}
+
+impl Default for DbBeatmap {
+    fn default() -> Self {
+        Self {
+            artist: None,
+            artist_unicode: None,
+            title: None,
+            title_unicode: None,
+            creator: None,
+            version: None,
+            audio_filename: None,
+            md5: None,
+            osu_file_name: None,
+            ranked_status: RankedStatus::Unknown,
+            hitcircle_count: 0,
+            slider_count: 0,
+            spinner_count: 0,
+            last_modified: 0,
+            approach_rate: 0.0,
+            circle_size: 0.0,
+            hp_drain_rate: 0.0,
+            overall_difficulty: 0.0,
+            slider_multiplier: 0.0,
+            star_ratings: StarRatings::default(),
+            drain_time: 0,
+            total_time: 0,
+            preview_time: 0,
+            timing_points: Vec::new(),
+            beatmap_id: 0,
+            beatmap_set_id: 0,
+            thread_id: 0,
+            grades: [9; 4],
+            local_offset: 0,
+            stack_leniency: 0.7,
+            mode: Mode::Osu,
+            source: None,
+            tags: None,
+            online_offset: 0,
+            title_font: None,
+            unplayed: true,
+            last_played: 0,
+            osz2: false,
+            folder_name: None,
+            last_checked: 0,
+            ignore_beatmap_sound: false,
+            ignore_beatmap_skin: false,
+            disable_storyboard: false,
+            disable_video: false,
+            visual_override: false,
+            unknown: 0,
+            last_modified_seconds: 0,
+            mania_scroll_speed: 0,
+        }
+    }
+}
+
+impl DbBeatmap {
+    /// The beatmap's metadata as a [`Metadata`] section.
+    pub fn metadata(&self) -> Metadata {
+        Metadata {
+            title: self.title.clone().map(Into::into),
+            title_unicode: self.title_unicode.clone().map(Into::into),
+            artist: self.artist.clone().map(Into::into),
+            artist_unicode: self.artist_unicode.clone().map(Into::into),
+            creator: self.creator.clone().map(Into::into),
+            version: self.version.clone().map(Into::into),
+            source: self.source.clone().map(Into::into),
+            tags: self.tags.as_ref().map(|tags| {
+                tags.split(' ')
+                    .filter(|tag| !tag.is_empty())
+                    .map(|tag| tag.to_string())
+                    .collect::<Vec<_>>()
+                    .into()
+            }),
+            beatmap_id: Some(self.beatmap_id.into()),
+            beatmap_set_id: Some(self.beatmap_set_id.into()),
+        }
+    }
+
+    /// Timing points converted with [`DbTimingPoint::to_timing_point`], skipping ones that can't be converted.
+    pub fn timing_points(&self) -> Vec<TimingPoint> {
+        self.timing_points
+            .iter()
+            .filter_map(DbTimingPoint::to_timing_point)
+            .collect()
+    }
+}
@@ -0,0 +1,575 @@
+//! Structural comparison between two [`OsuFile`]s.
+//!
+//! Unlike [`osu_str_eq`][crate::osu_str_eq], which only tells if two files are equal, this reports what is different in each section.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+
+use either::Either;
+
+use super::colours::Colour;
+use super::events::Event;
+use super::hitobjects::HitObject;
+use super::timingpoints::TimingPoint;
+use super::{Decimal, OsuFile, Version, VersionedToString};
+
+/// Sections of an .osu file.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Section {
+    General,
+    Editor,
+    Metadata,
+    Difficulty,
+    Events,
+    TimingPoints,
+    Colours,
+    HitObjects,
+}
+
+impl Section {
+    /// Name of the section as written in the file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Section::General => "General",
+            Section::Editor => "Editor",
+            Section::Metadata => "Metadata",
+            Section::Difficulty => "Difficulty",
+            Section::Events => "Events",
+            Section::TimingPoints => "TimingPoints",
+            Section::Colours => "Colours",
+            Section::HitObjects => "HitObjects",
+        }
+    }
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A change of a single `key: value` field.
+/// - `old` is `None` if the field was added, `new` is `None` if the field was removed.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct FieldChange {
+    pub section: Section,
+    pub key: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// A change of an item in a list section.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ObjectChange<T> {
+    /// Only exists in the new file.
+    Added(T),
+    /// Only exists in the old file.
+    Removed(T),
+    /// Exists in both files at the same time, but the contents are different.
+    Changed { old: T, new: T },
+    /// Exists in both files with the same contents, but at a different time.
+    Moved { old: T, new: T },
+}
+
+impl<T> ObjectChange<T> {
+    /// Returns the old value, if any.
+    pub fn old_value(&self) -> Option<&T> {
+        match self {
+            ObjectChange::Added(_) => None,
+            ObjectChange::Removed(old)
+            | ObjectChange::Changed { old, .. }
+            | ObjectChange::Moved { old, .. } => Some(old),
+        }
+    }
+
+    /// Returns the new value, if any.
+    pub fn new_value(&self) -> Option<&T> {
+        match self {
+            ObjectChange::Removed(_) => None,
+            ObjectChange::Added(new)
+            | ObjectChange::Changed { new, .. }
+            | ObjectChange::Moved { new, .. } => Some(new),
+        }
+    }
+}
+
+/// A single difference between two [`OsuFile`]s.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+#[allow(clippy::large_enum_variant)]
+pub enum Change {
+    /// The file format version is different.
+    Version {
+        old: Version,
+        new: Version,
+    },
+    /// A field in `General`, `Editor`, `Metadata`, `Difficulty` or `Colours` is different.
+    Field(FieldChange),
+    HitObject(ObjectChange<HitObject>),
+    TimingPoint(ObjectChange<TimingPoint>),
+    Event(ObjectChange<Event>),
+}
+
+impl Change {
+    /// Section the change belongs to.
+    /// - Returns `None` for the version change.
+    pub fn section(&self) -> Option<Section> {
+        match self {
+            Change::Version { .. } => None,
+            Change::Field(field) => Some(field.section),
+            Change::HitObject(_) => Some(Section::HitObjects),
+            Change::TimingPoint(_) => Some(Section::TimingPoints),
+            Change::Event(_) => Some(Section::Events),
+        }
+    }
+}
+
+/// List of differences between two [`OsuFile`]s, created with [`OsuFile::diff`].
+/// - The changes are ordered by section, and by time for hitobjects and timing points.
+/// - Use the `Display` trait or [`report`][Self::report] for a human readable report.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct OsuFileDiff {
+    /// Version of the old file, used to display old values.
+    pub old_version: Version,
+    /// Version of the new file, used to display new values.
+    pub new_version: Version,
+    pub changes: Vec<Change>,
+}
+
+impl OsuFileDiff {
+    /// Returns `true` if there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Iterates over the changes of a single section.
+    pub fn section(&self, section: Section) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(move |change| change.section() == Some(section))
+    }
+
+    /// Returns a human readable report of the changes.
+    /// - Lines starting with `+` are added, `-` are removed, `~` are changed, and `>` are moved.
+    pub fn report(&self) -> String {
+        let mut lines = Vec::new();
+        let mut current_section = None;
+
+        for change in &self.changes {
+            if let Some(section) = change.section() {
+                if current_section != Some(section) {
+                    lines.push(format!("[{section}]"));
+                    current_section = Some(section);
+                }
+            }
+
+            match change {
+                Change::Version { old, new } => {
+                    lines.push(format!("osu file format v{old} -> v{new}"))
+                }
+                Change::Field(FieldChange { key, old, new, .. }) => match (old, new) {
+                    (None, Some(new)) => lines.push(format!("+ {key}: {new}")),
+                    (Some(old), None) => lines.push(format!("- {key}: {old}")),
+                    (Some(old), Some(new)) => lines.push(format!("~ {key}: {old} -> {new}")),
+                    (None, None) => (),
+                },
+                Change::HitObject(change) => lines.push(self.object_change_line(change)),
+                Change::TimingPoint(change) => lines.push(self.object_change_line(change)),
+                Change::Event(change) => lines.push(self.object_change_line(change)),
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    fn object_change_line<T: VersionedToString>(&self, change: &ObjectChange<T>) -> String {
+        let old = |v: &T| v.to_string(self.old_version).unwrap_or_default();
+        let new = |v: &T| v.to_string(self.new_version).unwrap_or_default();
+
+        match change {
+            ObjectChange::Added(v) => format!("+ {}", new(v)),
+            ObjectChange::Removed(v) => format!("- {}", old(v)),
+            ObjectChange::Changed { old: o, new: n } => format!("~ {} -> {}", old(o), new(n)),
+            ObjectChange::Moved { old: o, new: n } => format!("> {} -> {}", old(o), new(n)),
+        }
+    }
+}
+
+impl Display for OsuFileDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.report())
+    }
+}
+
+impl OsuFile {
+    /// Compares `self` as the old file with `other` as the new file.
+    /// - `key: value` sections are compared by key.
+    /// - Hitobjects and timing points are matched by their time.
+    /// - Events are compared as an ordered list.
+    pub fn diff(&self, other: &OsuFile) -> OsuFileDiff {
+        let mut changes = Vec::new();
+
+        if self.version != other.version {
+            changes.push(Change::Version {
+                old: self.version,
+                new: other.version,
+            });
+        }
+
+        macro_rules! diff_fields {
+            ($field:ident, $section:expr) => {
+                let old = self
+                    .$field
+                    .as_ref()
+                    .map(|s| s.fields(self.version))
+                    .unwrap_or_default();
+                let new = other
+                    .$field
+                    .as_ref()
+                    .map(|s| s.fields(other.version))
+                    .unwrap_or_default();
+                changes.extend(
+                    diff_key_values($section, old, new)
+                        .into_iter()
+                        .map(Change::Field),
+                );
+            };
+        }
+
+        diff_fields!(general, Section::General);
+        diff_fields!(editor, Section::Editor);
+        diff_fields!(metadata, Section::Metadata);
+        diff_fields!(difficulty, Section::Difficulty);
+
+        let events = |osu_file: &OsuFile| {
+            osu_file
+                .events
+                .as_ref()
+                .map(|e| e.0.clone())
+                .unwrap_or_default()
+        };
+        changes.extend(
+            diff_sequence(&events(self), &events(other))
+                .into_iter()
+                .map(Change::Event),
+        );
+
+        let timing_points = |osu_file: &OsuFile| {
+            osu_file
+                .timing_points
+                .as_ref()
+                .map(|t| t.0.clone())
+                .unwrap_or_default()
+        };
+        changes.extend(
+            diff_timed(
+                &timing_points(self),
+                &timing_points(other),
+                |t| (t.time().clone(), t.uninherited()),
+                |t| t.time(),
+                |old, new| {
+                    let mut old = old.clone();
+                    *old.time_mut() = new.time().clone();
+                    old == *new
+                },
+            )
+            .into_iter()
+            .map(Change::TimingPoint),
+        );
+
+        let colours = |osu_file: &OsuFile| {
+            osu_file
+                .colours
+                .as_ref()
+                .map(|c| {
+                    c.0.iter()
+                        .map(|c| (colour_key(c), c.to_string(osu_file.version)))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        };
+        changes.extend(
+            diff_key_values(Section::Colours, colours(self), colours(other))
+                .into_iter()
+                .map(Change::Field),
+        );
+
+        let hitobjects = |osu_file: &OsuFile| {
+            osu_file
+                .hitobjects
+                .as_ref()
+                .map(|h| h.0.clone())
+                .unwrap_or_default()
+        };
+        changes.extend(
+            diff_timed(
+                &hitobjects(self),
+                &hitobjects(other),
+                |h| h.time.clone(),
+                |h| &h.time,
+                |old, new| {
+                    let mut old = old.clone();
+                    old.time = new.time.clone();
+                    old == *new
+                },
+            )
+            .into_iter()
+            .map(Change::HitObject),
+        );
+
+        OsuFileDiff {
+            old_version: self.version,
+            new_version: other.version,
+            changes,
+        }
+    }
+}
+
+/// Key used to match a colour between files.
+pub(crate) fn colour_key(colour: &Colour) -> String {
+    match colour {
+        Colour::Combo(num, _) => format!("Combo{num}"),
+        Colour::SliderTrackOverride(_) => "SliderTrackOverride".to_string(),
+        Colour::SliderBorder(_) => "SliderBorder".to_string(),
+    }
+}
+
+/// Sort key for times, where times that failed to parse as a decimal are ordered last.
+pub(crate) fn time_sort_key(time: &Decimal) -> Either<rust_decimal::Decimal, String> {
+    time.get().clone()
+}
+
+fn diff_key_values<K: AsRef<str>>(
+    section: Section,
+    old: Vec<(K, Option<String>)>,
+    new: Vec<(K, Option<String>)>,
+) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    let new_map = new
+        .iter()
+        .map(|(k, v)| (k.as_ref(), v.as_ref()))
+        .collect::<HashMap<_, _>>();
+    let old_map = old
+        .iter()
+        .map(|(k, v)| (k.as_ref(), v.as_ref()))
+        .collect::<HashMap<_, _>>();
+
+    for (key, old_value) in &old {
+        let old_value = old_value.as_ref();
+        let new_value = new_map.get(key.as_ref()).copied().flatten();
+
+        if old_value != new_value {
+            changes.push(FieldChange {
+                section,
+                key: key.as_ref().to_string(),
+                old: old_value.cloned(),
+                new: new_value.cloned(),
+            });
+        }
+    }
+    for (key, new_value) in &new {
+        if old_map.contains_key(key.as_ref()) {
+            continue;
+        }
+        if let Some(new_value) = new_value {
+            changes.push(FieldChange {
+                section,
+                key: key.as_ref().to_string(),
+                old: None,
+                new: Some(new_value.clone()),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Diffs items that are identified by their time.
+/// - `key` is used to match items between the files.
+/// - `same_except_time` tells if an unmatched removed and added item is the same item that was moved.
+pub(crate) fn diff_timed<T, K, FK, FT, FM>(
+    old: &[T],
+    new: &[T],
+    key: FK,
+    time: FT,
+    same_except_time: FM,
+) -> Vec<ObjectChange<T>>
+where
+    T: Clone + PartialEq,
+    K: Hash + Eq,
+    FK: Fn(&T) -> K,
+    FT: Fn(&T) -> &Decimal,
+    FM: Fn(&T, &T) -> bool,
+{
+    let mut new_by_key: HashMap<K, Vec<usize>> = HashMap::new();
+    for (i, item) in new.iter().enumerate() {
+        new_by_key.entry(key(item)).or_default().push(i);
+    }
+
+    let mut new_used = vec![false; new.len()];
+    let mut old_used = vec![false; old.len()];
+
+    // exact matches first, so that reordered items with the same key don't show up as changes
+    for (i, item) in old.iter().enumerate() {
+        if let Some(indexes) = new_by_key.get(&key(item)) {
+            if let Some(&j) = indexes.iter().find(|&&j| !new_used[j] && new[j] == *item) {
+                new_used[j] = true;
+                old_used[i] = true;
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+
+    for (i, item) in old.iter().enumerate() {
+        if old_used[i] {
+            continue;
+        }
+        if let Some(indexes) = new_by_key.get(&key(item)) {
+            if let Some(&j) = indexes.iter().find(|&&j| !new_used[j]) {
+                new_used[j] = true;
+                old_used[i] = true;
+                changes.push(ObjectChange::Changed {
+                    old: item.clone(),
+                    new: new[j].clone(),
+                });
+            }
+        }
+    }
+
+    let mut added = new_used
+        .iter()
+        .enumerate()
+        .filter(|(_, used)| !**used)
+        .map(|(j, _)| j)
+        .collect::<Vec<_>>();
+
+    for (i, item) in old.iter().enumerate() {
+        if old_used[i] {
+            continue;
+        }
+
+        match added.iter().position(|&j| same_except_time(item, &new[j])) {
+            Some(pos) => {
+                let j = added.remove(pos);
+                changes.push(ObjectChange::Moved {
+                    old: item.clone(),
+                    new: new[j].clone(),
+                });
+            }
+            None => changes.push(ObjectChange::Removed(item.clone())),
+        }
+    }
+
+    changes.extend(
+        added
+            .into_iter()
+            .map(|j| ObjectChange::Added(new[j].clone())),
+    );
+
+    changes.sort_by_key(|change| {
+        let item = change.new_value().or_else(|| change.old_value()).unwrap();
+        time_sort_key(time(item))
+    });
+
+    changes
+}
+
+/// Maximum size of the LCS table before falling back to comparing items by position.
+const LCS_TABLE_LIMIT: usize = 16_000_000;
+
+/// Diffs an ordered list of items using the longest common subsequence.
+pub(crate) fn diff_sequence<T: Clone + PartialEq>(old: &[T], new: &[T]) -> Vec<ObjectChange<T>> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(o, n)| o == n)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(o, n)| o == n)
+        .count();
+
+    let old = &old[prefix..old.len() - suffix];
+    let new = &new[prefix..new.len() - suffix];
+
+    // removed and added items, in order of appearance
+    let mut ops: Vec<Either<&T, &T>> = Vec::new();
+
+    if (old.len() + 1) * (new.len() + 1) > LCS_TABLE_LIMIT {
+        for i in 0..old.len().max(new.len()) {
+            match (old.get(i), new.get(i)) {
+                (Some(o), Some(n)) if o == n => (),
+                (o, n) => {
+                    ops.extend(o.map(Either::Left));
+                    ops.extend(n.map(Either::Right));
+                }
+            }
+        }
+    } else {
+        let width = new.len() + 1;
+        let mut table = vec![0u32; (old.len() + 1) * width];
+
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                table[i * width + j] = if old[i] == new[j] {
+                    table[(i + 1) * width + j + 1] + 1
+                } else {
+                    table[(i + 1) * width + j].max(table[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                i += 1;
+                j += 1;
+            } else if j >= new.len()
+                || (i < old.len() && table[(i + 1) * width + j] >= table[i * width + j + 1])
+            {
+                ops.push(Either::Left(&old[i]));
+                i += 1;
+            } else {
+                ops.push(Either::Right(&new[j]));
+                j += 1;
+            }
+        }
+    }
+
+    // pair up runs of removed items followed by added items as changes
+    let mut changes = Vec::new();
+    let mut index = 0;
+    while index < ops.len() {
+        let removed = ops[index..]
+            .iter()
+            .take_while(|op| op.is_left())
+            .map(|op| op.left().unwrap())
+            .collect::<Vec<_>>();
+        let added = ops[index + removed.len()..]
+            .iter()
+            .take_while(|op| op.is_right())
+            .map(|op| op.right().unwrap())
+            .collect::<Vec<_>>();
+        index += removed.len() + added.len();
+
+        for k in 0..removed.len().max(added.len()) {
+            changes.push(match (removed.get(k), added.get(k)) {
+                (Some(&old), Some(&new)) => ObjectChange::Changed {
+                    old: old.clone(),
+                    new: new.clone(),
+                },
+                (Some(&old), None) => ObjectChange::Removed(old.clone()),
+                (None, Some(&new)) => ObjectChange::Added(new.clone()),
+                (None, None) => unreachable!(),
+            });
+        }
+    }
+
+    changes
+}
@@ -19,6 +19,7 @@ use crate::{
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AudioSample {
     pub time: Integer,
     pub layer: Layer,
@@ -105,6 +106,7 @@ impl VersionedToString for AudioSample {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Volume(u8);
 
 impl VersionedDefault for Volume {
@@ -164,6 +166,7 @@ impl VersionedToString for Volume {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum Layer {
     Background,
@@ -11,7 +11,7 @@ use nom::{bytes::complete::tag, combinator::rest, sequence::preceded};
 
 use crate::events::storyboard::cmds::CommandProperties;
 use crate::helper::trait_ext::MapOptStringNewLine;
-use crate::osb::Variable;
+use crate::osb::{Variable, VariableToken};
 use crate::parsers::comma;
 
 use self::storyboard::cmds::Command;
@@ -26,6 +26,7 @@ pub use error::*;
 pub use normal_event::*;
 
 #[derive(Default, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Events(pub Vec<Event>);
 
 const OLD_VERSION_TIME_OFFSET: Integer = 24;
@@ -130,37 +131,33 @@ impl Events {
             // its a storyboard command
             if indent > 0 {
                 let cmd_parse = || {
-                    let line_without_header = match line.chars().position(|c| c == ',') {
-                        Some(i) => &line[i + 1..],
-                        None => line,
-                    };
-
-                    let mut line_with_variable: Option<String> = None;
-                    for variable in variables {
-                        let variable_full = format!("${}", variable.name);
-
-                        if line_without_header.contains(&variable_full) {
-                            let new_line = match line_with_variable {
-                                Some(line_with_variable) => {
-                                    line_with_variable.replace(&variable_full, &variable.value)
-                                }
-                                None => line.replace(&variable_full, &variable.value),
-                            };
-
-                            line_with_variable = Some(new_line);
-                        }
-                    }
-
-                    match line_with_variable {
-                        Some(line_with_variable) => Error::new_from_result_into(
-                            Command::from_str(&line_with_variable, version),
-                            line_index,
-                        ),
-                        None => Error::new_from_result_into(
+                    if variables.is_empty() {
+                        return Error::new_from_result_into(
                             Command::from_str(line, version),
                             line_index,
-                        ),
+                        );
                     }
+
+                    let (header, line_without_header) = match line.find(',') {
+                        Some(i) => line.split_at(i + 1),
+                        None => ("", line),
+                    };
+                    let tokens = VariableToken::tokenize(line_without_header, variables);
+                    // tokenize only returns variables that are defined
+                    let line_with_variables = format!(
+                        "{header}{}",
+                        VariableToken::expand(&tokens, variables).unwrap()
+                    );
+
+                    let command = Error::new_from_result_into(
+                        Command::from_str(&line_with_variables, version),
+                        line_index,
+                    )?;
+
+                    Ok(command.map(|command| Command {
+                        variables: Some(tokens),
+                        ..command
+                    }))
                 };
 
                 match events.0.last_mut() {
@@ -327,6 +324,7 @@ impl VersionedDefault for Events {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 /// All possible events types.
 pub enum Event {
@@ -352,8 +350,8 @@ impl Event {
     pub fn to_string_variables(&self, version: Version, variables: &[Variable]) -> Option<String> {
         match self {
             Event::Comment(comment) => Some(format!("//{comment}")),
-            Event::Background(background) => background.to_string(version),
-            Event::Video(video) => video.to_string(version),
+            Event::Background(background) => background.to_string_variables(version, variables),
+            Event::Video(video) => video.to_string_variables(version, variables),
             Event::Break(break_) => break_.to_string(version),
             Event::ColourTransformation(colour_trans) => colour_trans.to_string(version),
             Event::SpriteLegacy(sprite) => sprite.to_string_variables(version, variables),
@@ -45,6 +45,7 @@ fn time_to_string(time: Integer, version: Version) -> String {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Background {
     pub start_time: Integer,
     pub file_name: FilePath,
@@ -118,6 +119,7 @@ impl EventWithCommands for Background {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Video {
     pub start_time: Integer,
     pub file_name: FilePath,
@@ -209,6 +211,7 @@ impl EventWithCommands for Video {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Break {
     pub start_time: Integer,
     pub end_time: Integer,
@@ -274,6 +277,7 @@ impl VersionedToString for Break {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ColourTransformation {
     pub start_time: Integer,
     pub red: u8,
@@ -353,6 +357,7 @@ impl VersionedToString for ColourTransformation {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// Legacy version of Sprite event.
 pub struct SpriteLegacy {
     pub layer: LayerLegacy,
@@ -437,6 +442,7 @@ impl EventWithCommands for SpriteLegacy {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AnimationLegacy {
     pub layer: LayerLegacy,
     pub origin: OriginTypeLegacy,
@@ -517,6 +523,7 @@ impl EventWithCommands for AnimationLegacy {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SampleLegacy {
     pub time: Decimal,
     pub layer: LayerLegacy,
@@ -5,6 +5,7 @@ use crate::{
 use super::{ParseLayerLegacyError, ParseOriginTypeLegacyError};
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum OriginTypeLegacy {
     TopLeft,
@@ -75,6 +76,7 @@ impl VersionedFromStr for OriginTypeLegacy {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum LayerLegacy {
     Background,
@@ -0,0 +1,457 @@
+//! Fluent builders for storyboard objects.
+//!
+//! ```
+//! use osu_file_parser::events::storyboard::builder::{sprite, CommandBuilder, StoryboardBuilder};
+//! use osu_file_parser::events::storyboard::sprites::{Layer, OriginType};
+//! use osu_file_parser::events::storyboard::types::Easing;
+//!
+//! let osb = StoryboardBuilder::new()
+//!     .object(
+//!         sprite("sb/bg.png")
+//!             .layer(Layer::Background)
+//!             .origin(OriginType::Centre)
+//!             .fade(0, 1000, 0.0, 1.0, Easing::QuadOut)
+//!             .r#loop(4000, 3, |l| l.scale(0, 500, 1.0, 1.2, Easing::Linear)),
+//!     )
+//!     .build_osb()
+//!     .unwrap();
+//! ```
+
+use std::path::Path;
+
+use either::Either;
+use rust_decimal::prelude::FromPrimitive;
+
+use crate::osb::Osb;
+use crate::osu_file::events::{Event, Events};
+use crate::osu_file::{Decimal, FilePath, Integer, Position};
+
+use super::cmds::{Colours, Command, CommandProperties, ContinuingFields};
+use super::error::{BuildError, FilePathNotRelative};
+use super::sprites::{Animation, Layer, LoopType, Object, ObjectType, Origin, OriginType, Sprite};
+use super::types::{Easing, Parameter, TriggerType};
+
+fn decimal(value: f64) -> Result<Decimal, BuildError> {
+    rust_decimal::Decimal::from_f64(value)
+        .filter(|_| value.is_finite())
+        .map(|value| value.normalize().into())
+        .ok_or(BuildError::InvalidValue(value))
+}
+
+/// Commands being built, along with the first error found.
+#[derive(Debug, Default)]
+pub struct CommandList {
+    commands: Vec<Command>,
+    error: Option<BuildError>,
+    nested: bool,
+}
+
+impl CommandList {
+    fn fail(&mut self, error: BuildError) {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+    }
+
+    fn push(
+        &mut self,
+        start: Integer,
+        end: Integer,
+        properties: impl FnOnce(Option<Integer>) -> Result<CommandProperties, BuildError>,
+    ) {
+        if end < start {
+            return self.fail(BuildError::EndBeforeStart { start, end });
+        }
+
+        match properties(Some(end)) {
+            Ok(properties) => self.commands.push(Command::new(Some(start), properties)),
+            Err(error) => self.fail(error),
+        }
+    }
+
+    fn push_group(
+        &mut self,
+        start: Integer,
+        build: impl FnOnce(GroupBuilder) -> GroupBuilder,
+        properties: impl FnOnce(Vec<Command>) -> CommandProperties,
+    ) {
+        if self.nested {
+            return self.fail(BuildError::NestedGroup);
+        }
+
+        let group = build(GroupBuilder(CommandList {
+            nested: true,
+            ..Default::default()
+        }))
+        .0;
+
+        if let Some(error) = group.error {
+            return self.fail(error);
+        }
+        if group.commands.is_empty() {
+            return self.fail(BuildError::EmptyGroup);
+        }
+
+        self.commands
+            .push(Command::new(Some(start), properties(group.commands)));
+    }
+}
+
+/// Adds commands to an object, or to a loop or trigger of an object.
+/// - Times are in milliseconds, where the end time can't be before the start time.
+/// - Times of commands inside of loops and triggers are relative to the start of the loop or trigger.
+/// - Each method adds a command going from one value to another, [`Events::optimize`] can merge them into continuing fields.
+pub trait CommandBuilder: Sized {
+    #[doc(hidden)]
+    fn command_list(&mut self) -> &mut CommandList;
+
+    fn fade(mut self, start: Integer, end: Integer, from: f64, to: f64, easing: Easing) -> Self {
+        self.command_list().push(start, end, |end_time| {
+            Ok(CommandProperties::Fade {
+                easing,
+                end_time,
+                start_opacity: decimal(from)?,
+                continuing_opacities: vec![decimal(to)?],
+            })
+        });
+        self
+    }
+
+    fn move_(
+        mut self,
+        start: Integer,
+        end: Integer,
+        from: (f64, f64),
+        to: (f64, f64),
+        easing: Easing,
+    ) -> Self {
+        self.command_list().push(start, end, |end_time| {
+            Ok(CommandProperties::Move {
+                easing,
+                end_time,
+                positions_xy: ContinuingFields {
+                    start: (decimal(from.0)?, decimal(from.1)?),
+                    continuing: vec![(decimal(to.0)?, Some(decimal(to.1)?))],
+                },
+            })
+        });
+        self
+    }
+
+    fn move_x(mut self, start: Integer, end: Integer, from: f64, to: f64, easing: Easing) -> Self {
+        self.command_list().push(start, end, |end_time| {
+            Ok(CommandProperties::MoveX {
+                easing,
+                end_time,
+                start_x: decimal(from)?,
+                continuing_x: vec![decimal(to)?],
+            })
+        });
+        self
+    }
+
+    fn move_y(mut self, start: Integer, end: Integer, from: f64, to: f64, easing: Easing) -> Self {
+        self.command_list().push(start, end, |end_time| {
+            Ok(CommandProperties::MoveY {
+                easing,
+                end_time,
+                start_y: decimal(from)?,
+                continuing_y: vec![decimal(to)?],
+            })
+        });
+        self
+    }
+
+    fn scale(mut self, start: Integer, end: Integer, from: f64, to: f64, easing: Easing) -> Self {
+        self.command_list().push(start, end, |end_time| {
+            Ok(CommandProperties::Scale {
+                easing,
+                end_time,
+                start_scale: decimal(from)?,
+                continuing_scales: vec![decimal(to)?],
+            })
+        });
+        self
+    }
+
+    fn vector_scale(
+        mut self,
+        start: Integer,
+        end: Integer,
+        from: (f64, f64),
+        to: (f64, f64),
+        easing: Easing,
+    ) -> Self {
+        self.command_list().push(start, end, |end_time| {
+            Ok(CommandProperties::VectorScale {
+                easing,
+                end_time,
+                scales_xy: ContinuingFields {
+                    start: (decimal(from.0)?, decimal(from.1)?),
+                    continuing: vec![(decimal(to.0)?, Some(decimal(to.1)?))],
+                },
+            })
+        });
+        self
+    }
+
+    /// Rotation is in radians.
+    fn rotate(mut self, start: Integer, end: Integer, from: f64, to: f64, easing: Easing) -> Self {
+        self.command_list().push(start, end, |end_time| {
+            Ok(CommandProperties::Rotate {
+                easing,
+                end_time,
+                start_rotation: decimal(from)?,
+                continuing_rotations: vec![decimal(to)?],
+            })
+        });
+        self
+    }
+
+    fn colour(
+        mut self,
+        start: Integer,
+        end: Integer,
+        from: (u8, u8, u8),
+        to: (u8, u8, u8),
+        easing: Easing,
+    ) -> Self {
+        self.command_list().push(start, end, |end_time| {
+            Ok(CommandProperties::Colour {
+                easing,
+                end_time,
+                colours: Colours {
+                    start: from,
+                    continuing: vec![(to.0, Some(to.1), Some(to.2))],
+                },
+            })
+        });
+        self
+    }
+
+    /// The parameter is applied from `start` to `end`, or forever if they are the same.
+    fn parameter(mut self, start: Integer, end: Integer, parameter: Parameter) -> Self {
+        self.command_list().push(start, end, |end_time| {
+            Ok(CommandProperties::Parameter {
+                easing: Easing::Linear,
+                end_time,
+                parameter,
+                continuing_parameters: Vec::new(),
+            })
+        });
+        self
+    }
+
+    /// Adds a loop that runs the commands built by `build` `loop_count` times.
+    /// - Loops can't be inside of other loops or triggers.
+    fn r#loop(
+        mut self,
+        start: Integer,
+        loop_count: u32,
+        build: impl FnOnce(GroupBuilder) -> GroupBuilder,
+    ) -> Self {
+        if loop_count == 0 {
+            self.command_list().fail(BuildError::InvalidLoopCount);
+            return self;
+        }
+
+        self.command_list()
+            .push_group(start, build, |commands| CommandProperties::Loop {
+                loop_count,
+                commands,
+            });
+        self
+    }
+
+    /// Adds a trigger that runs the commands built by `build` when it's fired between `start` and `end`.
+    /// - Triggers can't be inside of loops or other triggers.
+    fn trigger(
+        self,
+        trigger_type: TriggerType,
+        start: Integer,
+        end: Integer,
+        build: impl FnOnce(GroupBuilder) -> GroupBuilder,
+    ) -> Self {
+        self.trigger_group(trigger_type, start, end, None, build)
+    }
+
+    /// Adds a trigger in a trigger group, where firing a trigger stops the commands of the previous firing in the same group.
+    fn trigger_group(
+        mut self,
+        trigger_type: TriggerType,
+        start: Integer,
+        end: Integer,
+        group_number: Option<Integer>,
+        build: impl FnOnce(GroupBuilder) -> GroupBuilder,
+    ) -> Self {
+        if end < start {
+            self.command_list()
+                .fail(BuildError::EndBeforeStart { start, end });
+            return self;
+        }
+
+        self.command_list()
+            .push_group(start, build, |commands| CommandProperties::Trigger {
+                trigger_type,
+                end_time: Some(end),
+                group_number,
+                commands,
+            });
+        self
+    }
+}
+
+/// Builds the commands of a loop or trigger.
+#[derive(Debug)]
+pub struct GroupBuilder(CommandList);
+
+impl CommandBuilder for GroupBuilder {
+    fn command_list(&mut self) -> &mut CommandList {
+        &mut self.0
+    }
+}
+
+/// Builds a storyboard [`Object`].
+/// - Objects are on the `Foreground` layer with the `Centre` origin at `(320, 240)` unless set otherwise.
+#[derive(Debug)]
+pub struct ObjectBuilder {
+    layer: Layer,
+    origin: OriginType,
+    position: Result<Position, BuildError>,
+    object_type: Result<ObjectType, BuildError>,
+    commands: CommandList,
+}
+
+fn relative_path(path: &Path) -> Result<FilePath, BuildError> {
+    if path.is_absolute() {
+        Err(FilePathNotRelative.into())
+    } else {
+        Ok(path.into())
+    }
+}
+
+/// Starts building a sprite with the image at `path`, relative to the beatmap folder.
+pub fn sprite<P: AsRef<Path>>(path: P) -> ObjectBuilder {
+    ObjectBuilder::new(
+        Sprite::new(path.as_ref())
+            .map(ObjectType::Sprite)
+            .map_err(Into::into),
+    )
+}
+
+/// Starts building an animation with the frames at `path`, relative to the beatmap folder.
+/// - `frame_delay` is the time between frames in milliseconds.
+pub fn animation<P: AsRef<Path>>(
+    path: P,
+    frame_count: u32,
+    frame_delay: f64,
+    loop_type: LoopType,
+) -> ObjectBuilder {
+    let animation = relative_path(path.as_ref()).and_then(|filepath| {
+        let frame_delay = rust_decimal::Decimal::from_f64(frame_delay)
+            .filter(|_| frame_delay.is_finite())
+            .ok_or(BuildError::InvalidValue(frame_delay))?
+            .normalize();
+
+        Ok(ObjectType::Animation(Animation {
+            frame_count,
+            frame_delay,
+            loop_type,
+            filepath,
+        }))
+    });
+
+    ObjectBuilder::new(animation)
+}
+
+impl ObjectBuilder {
+    fn new(object_type: Result<ObjectType, BuildError>) -> Self {
+        Self {
+            layer: Layer::Foreground,
+            origin: OriginType::Centre,
+            position: Ok(Position {
+                x: 320.into(),
+                y: 240.into(),
+            }),
+            object_type,
+            commands: CommandList::default(),
+        }
+    }
+
+    pub fn layer(mut self, layer: Layer) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn origin(mut self, origin: OriginType) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn position(mut self, x: f64, y: f64) -> Self {
+        self.position = decimal(x).and_then(|x| Ok(Position { x, y: decimal(y)? }));
+        self
+    }
+
+    /// Returns the object, or the first error found while building it.
+    pub fn build(self) -> Result<Object, BuildError> {
+        let object_type = self.object_type?;
+        let position = self.position?;
+
+        if let Some(error) = self.commands.error {
+            return Err(error);
+        }
+
+        Ok(Object {
+            layer: self.layer,
+            origin: Origin {
+                type_: Either::Left(self.origin),
+                shorthand: false,
+            },
+            position,
+            object_type,
+            commands: self.commands.commands,
+        })
+    }
+}
+
+impl CommandBuilder for ObjectBuilder {
+    fn command_list(&mut self) -> &mut CommandList {
+        &mut self.commands
+    }
+}
+
+/// Builds the events of a storyboard out of objects.
+#[derive(Debug, Default)]
+pub struct StoryboardBuilder {
+    objects: Vec<ObjectBuilder>,
+}
+
+impl StoryboardBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an object, which is drawn above the objects added before it on the same layer.
+    pub fn object(mut self, object: ObjectBuilder) -> Self {
+        self.objects.push(object);
+        self
+    }
+
+    /// Returns the events of the storyboard, or the first error found while building an object.
+    pub fn build_events(self) -> Result<Events, BuildError> {
+        self.objects
+            .into_iter()
+            .map(|object| object.build().map(Event::StoryboardObject))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Events)
+    }
+
+    /// Returns a `.osb` storyboard without variables.
+    pub fn build_osb(self) -> Result<Osb, BuildError> {
+        Ok(Osb {
+            variables: None,
+            events: Some(self.build_events()?),
+        })
+    }
+}
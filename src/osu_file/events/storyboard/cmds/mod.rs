@@ -2,10 +2,11 @@ pub mod error;
 pub mod types;
 
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 
 use super::error::*;
 use super::types::*;
-use crate::osb::Variable;
+use crate::osb::{Variable, VariableToken};
 use crate::osu_file::types::Decimal;
 use crate::osu_file::{Integer, Version, VersionedFromStr, VersionedToString};
 use crate::parsers::*;
@@ -21,10 +22,44 @@ use nom::Parser;
 pub use error::*;
 pub use types::*;
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+/// A storyboard command.
+/// - Two commands are equal if their times and properties are, regardless of how they were written with `[Variables]`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
 pub struct Command {
     pub start_time: Option<Integer>,
     pub properties: CommandProperties,
+    /// How the command was written with `[Variables]`, for the part after the command type.
+    /// - Used when writing the command as long as it still expands to the same values, so the original variable usage is kept.
+    /// - `None` for commands that weren't parsed with variables, which use variables for any matching fields when written.
+    pub variables: Option<Vec<VariableToken>>,
+}
+
+impl Command {
+    /// New command that isn't written with any specific variables.
+    pub fn new(start_time: Option<Integer>, properties: CommandProperties) -> Self {
+        Self {
+            start_time,
+            properties,
+            variables: None,
+        }
+    }
+}
+
+impl PartialEq for Command {
+    fn eq(&self, other: &Self) -> bool {
+        self.start_time == other.start_time && self.properties == other.properties
+    }
+}
+
+impl Eq for Command {}
+
+impl Hash for Command {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.start_time.hash(state);
+        self.properties.hash(state);
+    }
 }
 
 fn continuing_to_string<T>(continuing: &[T]) -> String
@@ -70,17 +105,55 @@ impl VersionedToString for Command {
 }
 
 impl Command {
+    /// End time of the command, or `None` if it's a `Loop` or the end time is empty.
+    pub fn end_time(&self) -> Option<Integer> {
+        match &self.properties {
+            CommandProperties::Loop { .. } => None,
+            CommandProperties::Fade { end_time, .. }
+            | CommandProperties::Move { end_time, .. }
+            | CommandProperties::MoveX { end_time, .. }
+            | CommandProperties::MoveY { end_time, .. }
+            | CommandProperties::Scale { end_time, .. }
+            | CommandProperties::VectorScale { end_time, .. }
+            | CommandProperties::Rotate { end_time, .. }
+            | CommandProperties::Colour { end_time, .. }
+            | CommandProperties::Parameter { end_time, .. }
+            | CommandProperties::Trigger { end_time, .. } => *end_time,
+        }
+    }
+
+    /// Mutable reference to the end time of the command, or `None` if it's a `Loop`.
+    pub fn end_time_mut(&mut self) -> Option<&mut Option<Integer>> {
+        match &mut self.properties {
+            CommandProperties::Loop { .. } => None,
+            CommandProperties::Fade { end_time, .. }
+            | CommandProperties::Move { end_time, .. }
+            | CommandProperties::MoveX { end_time, .. }
+            | CommandProperties::MoveY { end_time, .. }
+            | CommandProperties::Scale { end_time, .. }
+            | CommandProperties::VectorScale { end_time, .. }
+            | CommandProperties::Rotate { end_time, .. }
+            | CommandProperties::Colour { end_time, .. }
+            | CommandProperties::Parameter { end_time, .. }
+            | CommandProperties::Trigger { end_time, .. } => Some(end_time),
+        }
+    }
+
     pub fn to_string_variables(&self, version: Version, variables: &[Variable]) -> Option<String> {
         let end_time_to_string =
             |end_time: &Option<i32>| end_time.map_or("".to_string(), |t| t.to_string());
         let variable_replace = |header, cmd: String| {
-            let mut cmd = cmd;
+            if variables.is_empty() {
+                return format!("{header},{cmd}");
+            }
 
-            for variable in variables {
-                if cmd.contains(&variable.value) {
-                    cmd = cmd.replace(&variable.value, &format!("${}", variable.name));
+            let tokens = match &self.variables {
+                Some(tokens) if VariableToken::expand(tokens, variables).as_ref() == Some(&cmd) => {
+                    tokens.clone()
                 }
-            }
+                _ => VariableToken::substitute(&cmd, variables),
+            };
+            let cmd = tokens.iter().map(|token| token.to_string()).collect::<String>();
 
             format!("{header},{cmd}")
         };
@@ -283,6 +356,7 @@ impl Command {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum CommandProperties {
     Fade {
@@ -459,6 +533,7 @@ impl VersionedFromStr for Command {
         )
         .map(|(start_time, loop_count)| Command {
             start_time,
+            variables: None,
             properties: CommandProperties::Loop {
                 loop_count,
                 commands: Vec::new(),
@@ -530,6 +605,7 @@ impl VersionedFromStr for Command {
             .map(
                 |(trigger_type, start_time, (end_time, group_number))| Command {
                     start_time,
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type,
                         end_time,
@@ -577,6 +653,7 @@ impl VersionedFromStr for Command {
                 |((easing, (start_time, end_time)), start_r, start_g, start_b, continuing)| {
                     Command {
                         start_time,
+                        variables: None,
                         properties: CommandProperties::Colour {
                             easing,
                             end_time,
@@ -613,6 +690,7 @@ impl VersionedFromStr for Command {
             .map(
                 |((easing, (start_time, end_time)), parameter, continuing_parameters)| Command {
                     start_time,
+                    variables: None,
                     properties: CommandProperties::Parameter {
                         easing,
                         end_time,
@@ -653,6 +731,7 @@ impl VersionedFromStr for Command {
         .map(
             |((easing, (start_time, end_time)), start_x, start_y, continuing)| Command {
                 start_time,
+                variables: None,
                 properties: CommandProperties::Move {
                     easing,
                     end_time,
@@ -674,6 +753,7 @@ impl VersionedFromStr for Command {
         .map(
             |((easing, (start_time, end_time)), start_x, start_y, continuing)| Command {
                 start_time,
+                variables: None,
                 properties: CommandProperties::VectorScale {
                     easing,
                     end_time,
@@ -693,6 +773,7 @@ impl VersionedFromStr for Command {
         .map(
             |((easing, (start_time, end_time)), start_opacity, continuing_opacities)| Command {
                 start_time,
+                variables: None,
                 properties: CommandProperties::Fade {
                     easing,
                     end_time,
@@ -710,6 +791,7 @@ impl VersionedFromStr for Command {
         .map(
             |((easing, (start_time, end_time)), start_x, continuing_x)| Command {
                 start_time,
+                variables: None,
                 properties: CommandProperties::MoveX {
                     easing,
                     end_time,
@@ -727,6 +809,7 @@ impl VersionedFromStr for Command {
         .map(
             |((easing, (start_time, end_time)), start_y, continuing_y)| Command {
                 start_time,
+                variables: None,
                 properties: CommandProperties::MoveY {
                     easing,
                     end_time,
@@ -744,6 +827,7 @@ impl VersionedFromStr for Command {
         .map(
             |((easing, (start_time, end_time)), start_scale, continuing_scales)| Command {
                 start_time,
+                variables: None,
                 properties: CommandProperties::Scale {
                     easing,
                     end_time,
@@ -761,6 +845,7 @@ impl VersionedFromStr for Command {
         .map(
             |((easing, (start_time, end_time)), start_rotation, continuing_rotations)| Command {
                 start_time,
+                variables: None,
                 properties: CommandProperties::Rotate {
                     easing,
                     end_time,
@@ -1,6 +1,7 @@
 use super::*;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ContinuingFields<T> {
     pub(crate) start: (T, T),
     pub(crate) continuing: Vec<(T, Option<T>)>,
@@ -97,6 +98,7 @@ where
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Colours {
     pub start: (u8, u8, u8),
     pub continuing: Vec<(u8, Option<u8>, Option<u8>)>,
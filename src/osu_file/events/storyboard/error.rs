@@ -4,6 +4,7 @@ use thiserror::Error;
 use std::num::ParseIntError;
 
 use crate::helper::macros::verbose_error_to_error;
+use crate::Integer;
 
 #[derive(Debug, Error)]
 #[non_exhaustive]
@@ -277,3 +278,44 @@ pub enum ParseLayerError {
     #[error("Unknown `Layer` variant")]
     UnknownVariant,
 }
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum BuildError {
+    #[error("The end time {end} is before the start time {start}")]
+    EndBeforeStart { start: Integer, end: Integer },
+    #[error("The value {0} isn't a finite number")]
+    InvalidValue(f64),
+    #[error("A loop needs to run at least once")]
+    InvalidLoopCount,
+    #[error("Loops and triggers can't be inside of other loops or triggers")]
+    NestedGroup,
+    #[error("Loops and triggers need at least one command")]
+    EmptyGroup,
+    #[error(transparent)]
+    FilePathNotRelative(#[from] FilePathNotRelative),
+}
+
+#[cfg(feature = "render")]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum RenderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Failed to decode the png image {path}")]
+    PngDecode {
+        path: std::path::PathBuf,
+        #[source]
+        source: png::DecodingError,
+    },
+    #[error("Failed to decode the jpeg image {path}")]
+    JpegDecode {
+        path: std::path::PathBuf,
+        #[source]
+        source: jpeg_decoder::Error,
+    },
+    #[error("The image {0} isn't a png or jpeg image")]
+    UnsupportedImage(std::path::PathBuf),
+    #[error(transparent)]
+    PngEncode(#[from] png::EncodingError),
+}
@@ -0,0 +1,427 @@
+//! Evaluation of storyboard objects at a point in time.
+
+use either::Either;
+
+use crate::osu_file::Decimal;
+
+use super::cmds::{Command, CommandProperties};
+use super::flatten;
+use super::sprites::{Object, Origin, OriginType};
+use super::types::{Easing, Parameter};
+
+/// State of a storyboard [`Object`] at a point in time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ObjectState {
+    /// Position in storyboard pixels.
+    pub position: (f64, f64),
+    /// Uniform scale from the `S` command.
+    pub scale: f64,
+    /// Scale from the `V` command.
+    pub vector_scale: (f64, f64),
+    /// Clockwise rotation in radians.
+    pub rotation: f64,
+    /// Opacity from `0` to `1`.
+    pub opacity: f64,
+    /// Red, green and blue from `0` to `255`.
+    pub colour: (f64, f64, f64),
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+    pub additive: bool,
+    /// Point of the image that is placed at `position`, where `(0, 0)` is the top left and `(1, 1)` the bottom right.
+    /// - Flipping the image also flips the origin.
+    pub origin: (f64, f64),
+    /// If the object is drawn, which is when the time is inside of its lifetime and the opacity isn't `0`.
+    pub visible: bool,
+}
+
+impl ObjectState {
+    /// The uniform scale multiplied by the vector scale.
+    pub fn total_scale(&self) -> (f64, f64) {
+        (
+            self.scale * self.vector_scale.0,
+            self.scale * self.vector_scale.1,
+        )
+    }
+}
+
+impl Origin {
+    /// Point of the image the origin refers to, where `(0, 0)` is the top left and `(1, 1)` the bottom right.
+    /// - `Custom` and undocumented origins are treated as `TopLeft`.
+    pub fn anchor(&self) -> (f64, f64) {
+        match self.type_ {
+            Either::Left(type_) => match type_ {
+                OriginType::TopLeft | OriginType::Custom => (0.0, 0.0),
+                OriginType::Centre => (0.5, 0.5),
+                OriginType::CentreLeft => (0.0, 0.5),
+                OriginType::TopRight => (1.0, 0.0),
+                OriginType::BottomCentre => (0.5, 1.0),
+                OriginType::TopCentre => (0.5, 0.0),
+                OriginType::CentreRight => (1.0, 0.5),
+                OriginType::BottomLeft => (0.0, 1.0),
+                OriginType::BottomRight => (1.0, 1.0),
+            },
+            Either::Right(_) => (0.0, 0.0),
+        }
+    }
+}
+
+trait Lerp: Copy {
+    fn lerp(from: Self, to: Self, t: f64) -> Self;
+}
+
+impl Lerp for f64 {
+    fn lerp(from: Self, to: Self, t: f64) -> Self {
+        from + (to - from) * t
+    }
+}
+
+impl Lerp for (f64, f64) {
+    fn lerp(from: Self, to: Self, t: f64) -> Self {
+        (f64::lerp(from.0, to.0, t), f64::lerp(from.1, to.1, t))
+    }
+}
+
+impl Lerp for (f64, f64, f64) {
+    fn lerp(from: Self, to: Self, t: f64) -> Self {
+        (
+            f64::lerp(from.0, to.0, t),
+            f64::lerp(from.1, to.1, t),
+            f64::lerp(from.2, to.2, t),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Segment<V> {
+    start: f64,
+    end: f64,
+    easing: Easing,
+    from: V,
+    to: V,
+}
+
+/// Value of a timeline sorted by start time.
+/// - Before the first segment, the value is the starting value of the first segment.
+/// - Between segments, the value is the ending value of the last segment that started.
+fn value_at<V: Lerp>(segments: &[Segment<V>], time: f64) -> Option<V> {
+    let first = segments.first()?;
+
+    if time < first.start {
+        return Some(first.from);
+    }
+
+    let segment = segments.iter().rev().find(|s| s.start <= time)?;
+
+    if time >= segment.end {
+        Some(segment.to)
+    } else {
+        let progress = (time - segment.start) / (segment.end - segment.start);
+
+        Some(V::lerp(
+            segment.from,
+            segment.to,
+            segment.easing.apply(progress),
+        ))
+    }
+}
+
+/// Splits the values into segments of the command's duration, which is how continuing fields work.
+fn push_segments<V: Copy>(
+    out: &mut Vec<Segment<V>>,
+    start: f64,
+    end: f64,
+    easing: Easing,
+    values: &[V],
+) {
+    match values {
+        [] => (),
+        [value] => out.push(Segment {
+            start,
+            end,
+            easing,
+            from: *value,
+            to: *value,
+        }),
+        _ => {
+            let duration = end - start;
+
+            for (i, pair) in values.windows(2).enumerate() {
+                let start = start + duration * i as f64;
+
+                out.push(Segment {
+                    start,
+                    end: start + duration,
+                    easing,
+                    from: pair[0],
+                    to: pair[1],
+                });
+            }
+        }
+    }
+}
+
+fn numbers<'a>(values: impl IntoIterator<Item = &'a Decimal>) -> Option<Vec<f64>> {
+    values.into_iter().map(|v| v.to_f64()).collect()
+}
+
+/// Values of continuing fields where a missing 2nd value is the previous one.
+fn pairs(
+    start: &(Decimal, Decimal),
+    continuing: &[(Decimal, Option<Decimal>)],
+) -> Option<Vec<(f64, f64)>> {
+    let mut values = vec![(start.0.to_f64()?, start.1.to_f64()?)];
+
+    for (first, second) in continuing {
+        let previous = values.last().unwrap().1;
+        let second = match second {
+            Some(second) => second.to_f64()?,
+            None => previous,
+        };
+
+        values.push((first.to_f64()?, second));
+    }
+
+    Some(values)
+}
+
+/// Start and end time of a single segment of a command that isn't a `Loop` or `Trigger`.
+fn command_times(command: &Command) -> Option<(f64, f64)> {
+    flatten::command_times(command).map(|(start, end)| (start as f64, end as f64))
+}
+
+/// Every command of an [`Object`] split into timelines for each property.
+/// - Build once with [`Object::timeline`] to evaluate the object at many points in time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectTimeline {
+    position: (f64, f64),
+    origin: (f64, f64),
+    x: Vec<Segment<f64>>,
+    y: Vec<Segment<f64>>,
+    scale: Vec<Segment<f64>>,
+    vector_scale: Vec<Segment<(f64, f64)>>,
+    rotation: Vec<Segment<f64>>,
+    opacity: Vec<Segment<f64>>,
+    colour: Vec<Segment<(f64, f64, f64)>>,
+    parameters: Vec<(Parameter, f64, f64)>,
+    lifetime: Option<(f64, f64)>,
+}
+
+impl ObjectTimeline {
+    fn new(object: &Object) -> Self {
+        let mut timeline = Self {
+            position: (
+                object.position.x.to_f64().unwrap_or_default(),
+                object.position.y.to_f64().unwrap_or_default(),
+            ),
+            origin: object.origin.anchor(),
+            x: Vec::new(),
+            y: Vec::new(),
+            scale: Vec::new(),
+            vector_scale: Vec::new(),
+            rotation: Vec::new(),
+            opacity: Vec::new(),
+            colour: Vec::new(),
+            parameters: Vec::new(),
+            lifetime: None,
+        };
+
+        for command in &object.flatten().commands {
+            if timeline.push_command(command).is_some() {
+                let (start, end) = flatten::command_range(command).unwrap();
+                let (start, end) = (start as f64, end as f64);
+
+                timeline.lifetime = Some(match timeline.lifetime {
+                    Some((s, e)) => (s.min(start), e.max(end)),
+                    None => (start, end),
+                });
+            }
+        }
+
+        let by_start = |a: &Segment<_>, b: &Segment<_>| a.start.total_cmp(&b.start);
+        timeline.x.sort_by(by_start);
+        timeline.y.sort_by(by_start);
+        timeline.scale.sort_by(by_start);
+        timeline
+            .vector_scale
+            .sort_by(|a, b| a.start.total_cmp(&b.start));
+        timeline.rotation.sort_by(by_start);
+        timeline.opacity.sort_by(by_start);
+        timeline.colour.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+        timeline
+    }
+
+    /// Adds the segments of the command, returning `None` if it had invalid values.
+    fn push_command(&mut self, command: &Command) -> Option<()> {
+        let (start, end) = command_times(command)?;
+
+        match &command.properties {
+            CommandProperties::Fade {
+                easing,
+                start_opacity,
+                continuing_opacities,
+                ..
+            } => {
+                let values = numbers(std::iter::once(start_opacity).chain(continuing_opacities))?;
+                push_segments(&mut self.opacity, start, end, *easing, &values);
+            }
+            CommandProperties::Move {
+                easing,
+                positions_xy,
+                ..
+            } => {
+                let values = pairs(
+                    positions_xy.start_values(),
+                    positions_xy.continuing_fields(),
+                )?;
+                let (x, y): (Vec<_>, Vec<_>) = values.into_iter().unzip();
+                push_segments(&mut self.x, start, end, *easing, &x);
+                push_segments(&mut self.y, start, end, *easing, &y);
+            }
+            CommandProperties::MoveX {
+                easing,
+                start_x,
+                continuing_x,
+                ..
+            } => {
+                let values = numbers(std::iter::once(start_x).chain(continuing_x))?;
+                push_segments(&mut self.x, start, end, *easing, &values);
+            }
+            CommandProperties::MoveY {
+                easing,
+                start_y,
+                continuing_y,
+                ..
+            } => {
+                let values = numbers(std::iter::once(start_y).chain(continuing_y))?;
+                push_segments(&mut self.y, start, end, *easing, &values);
+            }
+            CommandProperties::Scale {
+                easing,
+                start_scale,
+                continuing_scales,
+                ..
+            } => {
+                let values = numbers(std::iter::once(start_scale).chain(continuing_scales))?;
+                push_segments(&mut self.scale, start, end, *easing, &values);
+            }
+            CommandProperties::VectorScale {
+                easing, scales_xy, ..
+            } => {
+                let values = pairs(scales_xy.start_values(), scales_xy.continuing_fields())?;
+                push_segments(&mut self.vector_scale, start, end, *easing, &values);
+            }
+            CommandProperties::Rotate {
+                easing,
+                start_rotation,
+                continuing_rotations,
+                ..
+            } => {
+                let values = numbers(std::iter::once(start_rotation).chain(continuing_rotations))?;
+                push_segments(&mut self.rotation, start, end, *easing, &values);
+            }
+            CommandProperties::Colour {
+                easing, colours, ..
+            } => {
+                let (r, g, b) = *colours.start_rgb();
+                let mut values = vec![(r as f64, g as f64, b as f64)];
+
+                for (r, g, b) in colours.continuing_fields() {
+                    let previous = *values.last().unwrap();
+
+                    values.push((
+                        *r as f64,
+                        g.map_or(previous.1, |g| g as f64),
+                        b.map_or(previous.2, |b| b as f64),
+                    ));
+                }
+
+                push_segments(&mut self.colour, start, end, *easing, &values);
+            }
+            CommandProperties::Parameter {
+                parameter,
+                continuing_parameters,
+                ..
+            } => {
+                let duration = end - start;
+
+                for (i, parameter) in std::iter::once(parameter)
+                    .chain(continuing_parameters)
+                    .enumerate()
+                {
+                    let start = start + duration * i as f64;
+                    self.parameters.push((*parameter, start, start + duration));
+                }
+            }
+            CommandProperties::Loop { .. } | CommandProperties::Trigger { .. } => return None,
+        }
+
+        Some(())
+    }
+
+    /// Earliest start and latest end time of the object's commands, or `None` if it has no commands.
+    pub fn lifetime(&self) -> Option<(f64, f64)> {
+        self.lifetime
+    }
+
+    /// Returns the state of the object at `time` in milliseconds.
+    pub fn state_at(&self, time: f64) -> ObjectState {
+        let parameter = |parameter: Parameter| {
+            self.parameters.iter().any(|(p, start, end)| {
+                *p == parameter
+                    && if start == end {
+                        // parameters without a duration last forever
+                        time >= *start
+                    } else {
+                        *start <= time && time <= *end
+                    }
+            })
+        };
+
+        let flip_horizontal = parameter(Parameter::ImageFlipHorizontal);
+        let flip_vertical = parameter(Parameter::ImageFlipVertical);
+        let opacity = value_at(&self.opacity, time).unwrap_or(1.0);
+
+        let mut origin = self.origin;
+        if flip_horizontal {
+            origin.0 = 1.0 - origin.0;
+        }
+        if flip_vertical {
+            origin.1 = 1.0 - origin.1;
+        }
+
+        ObjectState {
+            position: (
+                value_at(&self.x, time).unwrap_or(self.position.0),
+                value_at(&self.y, time).unwrap_or(self.position.1),
+            ),
+            scale: value_at(&self.scale, time).unwrap_or(1.0),
+            vector_scale: value_at(&self.vector_scale, time).unwrap_or((1.0, 1.0)),
+            rotation: value_at(&self.rotation, time).unwrap_or_default(),
+            opacity,
+            colour: value_at(&self.colour, time).unwrap_or((255.0, 255.0, 255.0)),
+            flip_horizontal,
+            flip_vertical,
+            additive: parameter(Parameter::UseAdditiveColourBlending),
+            origin,
+            visible: opacity > 0.0
+                && self
+                    .lifetime
+                    .is_some_and(|(start, end)| start <= time && time <= end),
+        }
+    }
+}
+
+impl Object {
+    /// Splits the commands into timelines for each property, to evaluate the object at many points in time.
+    pub fn timeline(&self) -> ObjectTimeline {
+        ObjectTimeline::new(self)
+    }
+
+    /// Returns the state of the object at `time` in milliseconds.
+    /// - Commands are evaluated like osu! does, where the first command of each type sets the value before it starts.
+    /// - Loops are unrolled, while triggers are ignored as they depend on gameplay.
+    pub fn state_at(&self, time: f64) -> ObjectState {
+        self.timeline().state_at(time)
+    }
+}
@@ -0,0 +1,336 @@
+//! Flattening of `Loop` and `Trigger` commands into commands with absolute times.
+
+use crate::osu_file::Integer;
+
+use super::cmds::{Command, CommandProperties};
+use super::sprites::Object;
+use super::types::{Addition, SampleSet, TriggerType};
+
+/// Commands of an object with absolute times.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct FlattenedCommands {
+    /// Commands with loops unrolled, sorted by start time.
+    /// - Contains no `Loop` or `Trigger` commands.
+    pub commands: Vec<Command>,
+    /// Triggers, which only run their commands when a gameplay event fires them.
+    pub triggers: Vec<TriggerGroup>,
+}
+
+/// A `Trigger` command with its commands.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct TriggerGroup {
+    pub trigger_type: TriggerType,
+    /// Start of the time the trigger can fire in.
+    pub start_time: Option<Integer>,
+    /// End of the time the trigger can fire in.
+    pub end_time: Option<Integer>,
+    /// Group of the trigger, where firing a trigger stops the commands of the previous firing in the same group.
+    /// - Defaults to `0`.
+    pub group_number: Integer,
+    /// Commands with times relative to when the trigger fires, with loops unrolled.
+    pub commands: Vec<Command>,
+}
+
+/// An event from gameplay that can fire a trigger.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TriggerEvent {
+    /// A hitsound being played.
+    HitSound {
+        time: Integer,
+        sample_set: SampleSet,
+        additions_sample_set: SampleSet,
+        whistle: bool,
+        finish: bool,
+        clap: bool,
+        /// Custom sample index, where `0` is the default samples.
+        custom_sample_set: usize,
+    },
+    /// The player started passing.
+    Passing { time: Integer },
+    /// The player started failing.
+    Failing { time: Integer },
+}
+
+impl TriggerEvent {
+    pub fn time(&self) -> Integer {
+        match self {
+            TriggerEvent::HitSound { time, .. }
+            | TriggerEvent::Passing { time }
+            | TriggerEvent::Failing { time } => *time,
+        }
+    }
+
+    /// Returns `true` if the event fires triggers of the type.
+    /// - `None` fields of `HitSound` triggers and the `All` sample set match anything.
+    pub fn fires(&self, trigger_type: &TriggerType) -> bool {
+        match (self, trigger_type) {
+            (TriggerEvent::Passing { .. }, TriggerType::Passing)
+            | (TriggerEvent::Failing { .. }, TriggerType::Failing) => true,
+            (
+                TriggerEvent::HitSound {
+                    sample_set,
+                    additions_sample_set,
+                    whistle,
+                    finish,
+                    clap,
+                    custom_sample_set,
+                    ..
+                },
+                TriggerType::HitSound {
+                    sample_set: trigger_sample_set,
+                    additions_sample_set: trigger_additions_sample_set,
+                    addition,
+                    custom_sample_set: trigger_custom_sample_set,
+                },
+            ) => {
+                let sample_set_matches =
+                    |trigger: &Option<SampleSet>, event: &SampleSet| match trigger {
+                        None | Some(SampleSet::All) => true,
+                        Some(trigger) => trigger == event,
+                    };
+                let addition_matches = match addition {
+                    None => true,
+                    Some(Addition::Whistle) => *whistle,
+                    Some(Addition::Finish) => *finish,
+                    Some(Addition::Clap) => *clap,
+                };
+
+                sample_set_matches(trigger_sample_set, sample_set)
+                    && sample_set_matches(trigger_additions_sample_set, additions_sample_set)
+                    && addition_matches
+                    && trigger_custom_sample_set.is_none_or(|c| c == *custom_sample_set)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Number of segments the command is split into by continuing fields.
+pub(crate) fn segment_count(properties: &CommandProperties) -> usize {
+    let count = match properties {
+        CommandProperties::Fade {
+            continuing_opacities: continuing,
+            ..
+        }
+        | CommandProperties::MoveX {
+            continuing_x: continuing,
+            ..
+        }
+        | CommandProperties::MoveY {
+            continuing_y: continuing,
+            ..
+        }
+        | CommandProperties::Scale {
+            continuing_scales: continuing,
+            ..
+        }
+        | CommandProperties::Rotate {
+            continuing_rotations: continuing,
+            ..
+        } => continuing.len(),
+        CommandProperties::Move { positions_xy, .. } => positions_xy.continuing_fields().len(),
+        CommandProperties::VectorScale { scales_xy, .. } => scales_xy.continuing_fields().len(),
+        CommandProperties::Colour { colours, .. } => colours.continuing_fields().len(),
+        CommandProperties::Parameter {
+            continuing_parameters,
+            ..
+        } => continuing_parameters.len() + 1,
+        CommandProperties::Loop { .. } | CommandProperties::Trigger { .. } => 1,
+    };
+
+    count.max(1)
+}
+
+/// Start and end time of a single segment of a command, where an empty or earlier end time is the start time.
+/// - Returns `None` for `Loop` and `Trigger` commands and commands without a start time.
+pub(crate) fn command_times(command: &Command) -> Option<(Integer, Integer)> {
+    if matches!(
+        command.properties,
+        CommandProperties::Loop { .. } | CommandProperties::Trigger { .. }
+    ) {
+        return None;
+    }
+
+    let start = command.start_time?;
+    let end = command.end_time().unwrap_or(start).max(start);
+
+    Some((start, end))
+}
+
+/// Start and end time of a command including all of its continuing fields.
+/// - Returns `None` for `Loop` and `Trigger` commands and commands without a start time.
+pub fn command_range(command: &Command) -> Option<(Integer, Integer)> {
+    let (start, end) = command_times(command)?;
+    let count = segment_count(&command.properties) as Integer;
+
+    Some((
+        start,
+        start.saturating_add((end - start).saturating_mul(count)),
+    ))
+}
+
+/// Earliest start and latest end of the commands.
+pub(crate) fn commands_range(commands: &[Command]) -> Option<(Integer, Integer)> {
+    commands
+        .iter()
+        .filter_map(command_range)
+        .reduce(|(s, e), (cs, ce)| (s.min(cs), e.max(ce)))
+}
+
+pub(crate) fn shift_command(command: &Command, offset: Integer) -> Command {
+    let mut command = command.clone();
+
+    if let Some(start_time) = &mut command.start_time {
+        *start_time = start_time.saturating_add(offset);
+    }
+    if let Some(Some(end_time)) = command.end_time_mut() {
+        *end_time = end_time.saturating_add(offset);
+    }
+
+    command
+}
+
+fn flatten_into(commands: &[Command], offset: Integer, out: &mut FlattenedCommands) {
+    for command in commands {
+        match &command.properties {
+            CommandProperties::Loop {
+                loop_count,
+                commands,
+            } => {
+                let Some(loop_start) = command.start_time else {
+                    continue;
+                };
+
+                let mut inner = FlattenedCommands::default();
+                flatten_into(commands, 0, &mut inner);
+
+                // each iteration lasts from the earliest start to the latest end of the commands
+                let duration =
+                    commands_range(&inner.commands).map_or(0, |(start, end)| end - start);
+
+                for i in 0..(*loop_count).max(1) as Integer {
+                    let iteration = offset
+                        .saturating_add(loop_start)
+                        .saturating_add(duration.saturating_mul(i));
+
+                    out.commands
+                        .extend(inner.commands.iter().map(|c| shift_command(c, iteration)));
+                    out.triggers
+                        .extend(inner.triggers.iter().map(|t| TriggerGroup {
+                            start_time: t.start_time.map(|s| s.saturating_add(iteration)),
+                            end_time: t.end_time.map(|e| e.saturating_add(iteration)),
+                            ..t.clone()
+                        }));
+                }
+            }
+            CommandProperties::Trigger {
+                trigger_type,
+                end_time,
+                group_number,
+                commands,
+            } => {
+                let mut inner = FlattenedCommands::default();
+                flatten_into(commands, 0, &mut inner);
+
+                out.triggers.push(TriggerGroup {
+                    trigger_type: *trigger_type,
+                    start_time: command.start_time.map(|s| s.saturating_add(offset)),
+                    end_time: end_time.map(|e| e.saturating_add(offset)),
+                    group_number: group_number.unwrap_or_default(),
+                    commands: inner.commands,
+                });
+            }
+            _ => out.commands.push(shift_command(command, offset)),
+        }
+    }
+}
+
+/// Unrolls loops and collects triggers of the commands.
+/// - Each loop iteration lasts from the earliest start to the latest end of the commands inside of it.
+pub fn flatten_commands(commands: &[Command]) -> FlattenedCommands {
+    let mut flattened = FlattenedCommands::default();
+    flatten_into(commands, 0, &mut flattened);
+
+    flattened
+        .commands
+        .sort_by_key(|c| c.start_time.unwrap_or(Integer::MIN));
+
+    flattened
+}
+
+impl FlattenedCommands {
+    /// Earliest start and latest end time of the commands, ignoring triggers.
+    pub fn lifetime(&self) -> Option<(Integer, Integer)> {
+        commands_range(&self.commands)
+    }
+
+    /// Start time of the commands, ignoring triggers.
+    pub fn start_time(&self) -> Option<Integer> {
+        self.lifetime().map(|(start, _)| start)
+    }
+
+    /// End time of the commands, ignoring triggers.
+    pub fn end_time(&self) -> Option<Integer> {
+        self.lifetime().map(|(_, end)| end)
+    }
+
+    /// Returns the commands along with the commands of every trigger fired by `events`, sorted by start time.
+    /// - A trigger fires for events inside of its start and end time.
+    /// - When a trigger fires, commands of the previous firing in the same group that haven't started yet are dropped.
+    pub fn expand_triggers(&self, events: &[TriggerEvent]) -> Vec<Command> {
+        let mut events = events.to_vec();
+        events.sort_by_key(|e| e.time());
+
+        let mut commands = self.commands.clone();
+        // (group, firing time, commands) of each firing
+        let mut firings: Vec<(Integer, Integer, &[Command])> = Vec::new();
+
+        for trigger in &self.triggers {
+            for event in &events {
+                let time = event.time();
+                let in_window = trigger.start_time.is_none_or(|s| s <= time)
+                    && trigger.end_time.is_none_or(|e| time <= e);
+
+                if in_window && event.fires(&trigger.trigger_type) {
+                    firings.push((trigger.group_number, time, &trigger.commands));
+                }
+            }
+        }
+
+        firings.sort_by_key(|(group, time, _)| (*group, *time));
+
+        for (i, (group, time, trigger_commands)) in firings.iter().enumerate() {
+            let next_firing = firings
+                .get(i + 1)
+                .filter(|(next_group, _, _)| next_group == group)
+                .map(|(_, next_time, _)| *next_time);
+
+            commands.extend(
+                trigger_commands
+                    .iter()
+                    .map(|c| shift_command(c, *time))
+                    .filter(|c| match (next_firing, c.start_time) {
+                        (Some(next_firing), Some(start_time)) => start_time < next_firing,
+                        _ => true,
+                    }),
+            );
+        }
+
+        commands.sort_by_key(|c| c.start_time.unwrap_or(Integer::MIN));
+        commands
+    }
+}
+
+impl Object {
+    /// Unrolls loops and collects triggers of the object's commands.
+    pub fn flatten(&self) -> FlattenedCommands {
+        flatten_commands(&self.commands)
+    }
+
+    /// Earliest start and latest end time of the object's commands, ignoring triggers.
+    /// - The object is only drawn during this time.
+    pub fn lifetime(&self) -> Option<(Integer, Integer)> {
+        self.flatten().lifetime()
+    }
+}
@@ -0,0 +1,231 @@
+//! Estimation of storyboard load, which is how much of the screen is drawn each frame.
+
+use crate::osb::Osb;
+use crate::osu_file::assets::{frame_paths, path_to_str};
+use crate::osu_file::events::{Event, Events};
+
+use super::eval::ObjectTimeline;
+use super::sprites::{Object, ObjectType};
+
+/// Options for [`Events::load`] and [`Osb::load`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoadOptions {
+    /// Milliseconds between sampled frames.
+    /// - Defaults to 60 frames per second.
+    pub frame_interval: f64,
+    /// Size of the screen in storyboard pixels.
+    /// - Defaults to `640x480`, use `854x480` for widescreen storyboards.
+    pub screen_size: (f64, f64),
+    /// Load above which frames are reported in [`LoadReport::exceeding`].
+    /// - Defaults to `5`.
+    pub threshold: f64,
+    /// Most frames that are sampled.
+    /// - If the storyboard is too long, the frames are sampled further apart than [`frame_interval`][Self::frame_interval].
+    /// - Defaults to `100000`, which is over 27 minutes at 60 frames per second.
+    pub max_frames: usize,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            frame_interval: 1000.0 / 60.0,
+            screen_size: (640.0, 480.0),
+            threshold: 5.0,
+            max_frames: 100_000,
+        }
+    }
+}
+
+/// Load of a single object.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ObjectLoad {
+    /// Index of the object's event in [`Events`].
+    pub index: usize,
+    /// Highest load of the object on its own.
+    pub peak: f64,
+}
+
+/// Frames where the load is above the threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadRange {
+    /// Time of the first frame above the threshold.
+    pub start: f64,
+    /// Time of the last frame above the threshold.
+    pub end: f64,
+    /// Highest load in the range.
+    pub peak: f64,
+    /// Objects drawn in the range, with the highest load first.
+    pub objects: Vec<ObjectLoad>,
+}
+
+/// Estimated storyboard load.
+/// - Load is the drawn area of the visible objects divided by the screen area, so `1` is the screen drawn over once.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoadReport {
+    /// Highest load of a frame.
+    pub peak: f64,
+    /// Time of the frame with the highest load.
+    pub peak_time: f64,
+    /// Average load of the frames between the start of the first object and the end of the last object.
+    pub average: f64,
+    /// Milliseconds between the sampled frames.
+    /// - More than [`LoadOptions::frame_interval`] if there would be more than [`LoadOptions::max_frames`] frames.
+    pub frame_interval: f64,
+    /// Time and load of each sampled frame.
+    pub frames: Vec<(f64, f64)>,
+    /// Ranges of frames where the load is above [`LoadOptions::threshold`].
+    pub exceeding: Vec<LoadRange>,
+    /// Indexes of objects that were skipped because their image size isn't known.
+    pub unknown_sizes: Vec<usize>,
+}
+
+struct LoadObject {
+    index: usize,
+    timeline: ObjectTimeline,
+    lifetime: (f64, f64),
+    size: (f64, f64),
+}
+
+impl LoadObject {
+    /// Load of the object at `time`, which is `0` if it isn't visible.
+    fn load_at(&self, time: f64, screen_area: f64) -> f64 {
+        if time < self.lifetime.0 || self.lifetime.1 < time {
+            return 0.0;
+        }
+
+        let state = self.timeline.state_at(time);
+        if !state.visible {
+            return 0.0;
+        }
+
+        let (scale_x, scale_y) = state.total_scale();
+        (self.size.0 * scale_x * self.size.1 * scale_y).abs() / screen_area
+    }
+}
+
+/// Path of the image the object is drawn with, which is the first frame for animations.
+fn image_path(object: &Object) -> Option<String> {
+    match &object.object_type {
+        ObjectType::Sprite(sprite) => Some(path_to_str(sprite.filepath.get())),
+        ObjectType::Animation(animation) => {
+            frame_paths(&path_to_str(animation.filepath.get()), 1).pop()
+        }
+    }
+}
+
+impl Events {
+    /// Estimates the storyboard load of the storyboard objects, by sampling frames from the start of the first object to the end of the last one.
+    /// - `image_size` returns the width and height of an image from its path as written in the storyboard.
+    /// - Opacity doesn't lower the load, as the object is still drawn.
+    /// - Triggers are ignored as they depend on gameplay.
+    pub fn load<F>(&self, options: &LoadOptions, mut image_size: F) -> LoadReport
+    where
+        F: FnMut(&str) -> Option<(u32, u32)>,
+    {
+        let mut report = LoadReport::default();
+        let mut objects = Vec::new();
+
+        for (index, event) in self.0.iter().enumerate() {
+            let Event::StoryboardObject(object) = event else {
+                continue;
+            };
+            let timeline = object.timeline();
+            let Some(lifetime) = timeline.lifetime() else {
+                continue;
+            };
+
+            match image_path(object).and_then(|path| image_size(&path)) {
+                Some((width, height)) => objects.push(LoadObject {
+                    index,
+                    timeline,
+                    lifetime,
+                    size: (width as f64, height as f64),
+                }),
+                None => report.unknown_sizes.push(index),
+            }
+        }
+
+        let start = objects.iter().map(|o| o.lifetime.0).reduce(f64::min);
+        let end = objects.iter().map(|o| o.lifetime.1).reduce(f64::max);
+        let (Some(start), Some(end)) = (start, end) else {
+            return report;
+        };
+
+        let screen_area = options.screen_size.0 * options.screen_size.1;
+        let mut interval = options.frame_interval.max(f64::EPSILON);
+        let max_frames = options.max_frames.max(1);
+        let mut frame_count = ((end - start) / interval).floor() + 1.0;
+        if frame_count > max_frames as f64 {
+            frame_count = max_frames as f64;
+            if max_frames > 1 {
+                interval = (end - start) / (max_frames - 1) as f64;
+            }
+        }
+        let frame_count = frame_count as usize;
+        report.frame_interval = interval;
+        let mut range: Option<LoadRange> = None;
+
+        for frame in 0..frame_count {
+            let time = start + frame as f64 * interval;
+            let loads = objects
+                .iter()
+                .map(|object| (object.index, object.load_at(time, screen_area)))
+                .filter(|(_, load)| *load > 0.0)
+                .collect::<Vec<_>>();
+            let load = loads.iter().map(|(_, load)| load).sum::<f64>();
+
+            report.frames.push((time, load));
+            if load > report.peak {
+                report.peak = load;
+                report.peak_time = time;
+            }
+
+            if load <= options.threshold {
+                report.exceeding.extend(range.take());
+                continue;
+            }
+
+            let range = range.get_or_insert_with(|| LoadRange {
+                start: time,
+                end: time,
+                peak: load,
+                objects: Vec::new(),
+            });
+            range.end = time;
+            range.peak = range.peak.max(load);
+
+            for (index, load) in loads {
+                match range.objects.iter_mut().find(|o| o.index == index) {
+                    Some(object) => object.peak = object.peak.max(load),
+                    None => range.objects.push(ObjectLoad { index, peak: load }),
+                }
+            }
+        }
+        report.exceeding.extend(range);
+
+        for range in &mut report.exceeding {
+            range
+                .objects
+                .sort_by(|a, b| b.peak.total_cmp(&a.peak).then(a.index.cmp(&b.index)));
+        }
+
+        report.average =
+            report.frames.iter().map(|(_, load)| load).sum::<f64>() / report.frames.len() as f64;
+
+        report
+    }
+}
+
+impl Osb {
+    /// Estimates the storyboard load of the storyboard objects.
+    /// - See [`Events::load`].
+    pub fn load<F>(&self, options: &LoadOptions, image_size: F) -> LoadReport
+    where
+        F: FnMut(&str) -> Option<(u32, u32)>,
+    {
+        match &self.events {
+            Some(events) => events.load(options, image_size),
+            None => LoadReport::default(),
+        }
+    }
+}
@@ -1,4 +1,12 @@
 pub mod error;
+pub mod eval;
+pub mod flatten;
+pub mod load;
+pub mod optimize;
+#[cfg(feature = "render")]
+pub mod render;
 pub mod sprites;
 pub mod types;
+pub mod validate;
+pub mod builder;
 pub mod cmds;
@@ -0,0 +1,1092 @@
+//! Rewriting of storyboards into fewer commands that are drawn the same.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::osb::{Osb, Variable};
+use crate::osu_file::events::{Event, Events};
+use crate::osu_file::{Decimal, Integer, VersionedToString, LATEST_VERSION};
+
+use super::cmds::{Colours, Command, CommandProperties, ContinuingFields};
+use super::flatten::{
+    command_range, command_times, commands_range, flatten_commands, segment_count, shift_command,
+};
+use super::sprites::Object;
+use super::types::Parameter;
+
+/// Which passes [`Events::optimize`] and [`Osb::optimize`] run.
+/// - Every pass is enabled by default.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct OptimizeOptions {
+    /// Merges consecutive commands of the same type into a single command with continuing fields.
+    pub merge_commands: bool,
+    /// Folds repeated sequences of commands into `Loop` commands.
+    pub fold_loops: bool,
+    /// Removes commands that don't change how the object is drawn.
+    /// - Same value commands that don't change the value.
+    /// - Commands before the object is first visible.
+    /// - Commands after the object is faded out for good.
+    pub remove_redundant: bool,
+    /// Removes objects that are never visible.
+    pub drop_invisible: bool,
+    /// Replaces the `[Variables]` of an [`Osb`] with variables for repeated command values.
+    pub variables: bool,
+}
+
+impl Default for OptimizeOptions {
+    fn default() -> Self {
+        Self {
+            merge_commands: true,
+            fold_loops: true,
+            remove_redundant: true,
+            drop_invisible: true,
+            variables: true,
+        }
+    }
+}
+
+/// What was changed by [`Events::optimize`] or [`Osb::optimize`].
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct OptimizeReport {
+    /// Commands that were merged into the previous command.
+    pub merged_commands: usize,
+    /// `Loop` commands that were created.
+    pub folded_loops: usize,
+    /// Commands that were removed.
+    pub removed_commands: usize,
+    /// Objects that were removed.
+    pub dropped_objects: usize,
+    /// Variables in the new `[Variables]` section.
+    pub variables: usize,
+}
+
+/// Values of a command, with missing continuing fields filled in.
+#[derive(Clone, Debug, PartialEq)]
+enum Values {
+    Numbers(Vec<Decimal>),
+    Pairs(Vec<(Decimal, Decimal)>),
+    Colours(Vec<(u8, u8, u8)>),
+    Parameters(Vec<Parameter>),
+}
+
+fn values(properties: &CommandProperties) -> Option<Values> {
+    let numbers = |start: &Decimal, continuing: &[Decimal]| {
+        Values::Numbers(std::iter::once(start).chain(continuing).cloned().collect())
+    };
+    let pairs = |fields: &ContinuingFields<Decimal>| {
+        let mut values = vec![fields.start.clone()];
+
+        for (first, second) in &fields.continuing {
+            let second = second
+                .clone()
+                .unwrap_or_else(|| values.last().unwrap().1.clone());
+            values.push((first.clone(), second));
+        }
+
+        Values::Pairs(values)
+    };
+
+    let values = match properties {
+        CommandProperties::Fade {
+            start_opacity: start,
+            continuing_opacities: continuing,
+            ..
+        }
+        | CommandProperties::MoveX {
+            start_x: start,
+            continuing_x: continuing,
+            ..
+        }
+        | CommandProperties::MoveY {
+            start_y: start,
+            continuing_y: continuing,
+            ..
+        }
+        | CommandProperties::Scale {
+            start_scale: start,
+            continuing_scales: continuing,
+            ..
+        }
+        | CommandProperties::Rotate {
+            start_rotation: start,
+            continuing_rotations: continuing,
+            ..
+        } => numbers(start, continuing),
+        CommandProperties::Move { positions_xy, .. } => pairs(positions_xy),
+        CommandProperties::VectorScale { scales_xy, .. } => pairs(scales_xy),
+        CommandProperties::Colour { colours, .. } => {
+            let mut values = vec![colours.start];
+
+            for (r, g, b) in &colours.continuing {
+                let previous = *values.last().unwrap();
+                values.push((*r, g.unwrap_or(previous.1), b.unwrap_or(previous.2)));
+            }
+
+            Values::Colours(values)
+        }
+        CommandProperties::Parameter {
+            parameter,
+            continuing_parameters,
+            ..
+        } => Values::Parameters(
+            std::iter::once(parameter)
+                .chain(continuing_parameters)
+                .copied()
+                .collect(),
+        ),
+        CommandProperties::Loop { .. } | CommandProperties::Trigger { .. } => return None,
+    };
+
+    Some(values)
+}
+
+fn set_values(properties: &mut CommandProperties, values: Values) {
+    match (properties, values) {
+        (
+            CommandProperties::Fade {
+                start_opacity: start,
+                continuing_opacities: continuing,
+                ..
+            }
+            | CommandProperties::MoveX {
+                start_x: start,
+                continuing_x: continuing,
+                ..
+            }
+            | CommandProperties::MoveY {
+                start_y: start,
+                continuing_y: continuing,
+                ..
+            }
+            | CommandProperties::Scale {
+                start_scale: start,
+                continuing_scales: continuing,
+                ..
+            }
+            | CommandProperties::Rotate {
+                start_rotation: start,
+                continuing_rotations: continuing,
+                ..
+            },
+            Values::Numbers(mut values),
+        ) => {
+            *continuing = values.split_off(1);
+            *start = values.remove(0);
+        }
+        (
+            CommandProperties::Move {
+                positions_xy: fields,
+                ..
+            }
+            | CommandProperties::VectorScale {
+                scales_xy: fields, ..
+            },
+            Values::Pairs(mut values),
+        ) => {
+            let continuing = values.split_off(1);
+
+            *fields = ContinuingFields {
+                start: values.remove(0),
+                continuing: continuing.into_iter().map(|(x, y)| (x, Some(y))).collect(),
+            };
+        }
+        (CommandProperties::Colour { colours, .. }, Values::Colours(values)) => {
+            *colours = Colours {
+                start: values[0],
+                continuing: values[1..]
+                    .iter()
+                    .map(|(r, g, b)| (*r, Some(*g), Some(*b)))
+                    .collect(),
+            };
+        }
+        (
+            CommandProperties::Parameter {
+                parameter,
+                continuing_parameters,
+                ..
+            },
+            Values::Parameters(mut values),
+        ) => {
+            *continuing_parameters = values.split_off(1);
+            *parameter = values[0];
+        }
+        _ => unreachable!("values are always of the command's type"),
+    }
+}
+
+fn same_number(a: &Decimal, b: &Decimal) -> bool {
+    matches!((a.to_f64(), b.to_f64()), (Some(a), Some(b)) if a == b)
+}
+
+/// Joins the values where `b` continues from the last value of `a`.
+/// - A single value is the value staying the same for the whole command.
+fn chain<T: Clone>(mut a: Vec<T>, mut b: Vec<T>, eq: impl Fn(&T, &T) -> bool) -> Option<Vec<T>> {
+    if a.len() == 1 {
+        a.push(a[0].clone());
+    }
+    if b.len() == 1 {
+        b.push(b[0].clone());
+    }
+    if !eq(a.last()?, &b[0]) {
+        return None;
+    }
+
+    a.extend(b.into_iter().skip(1));
+    Some(a)
+}
+
+fn merge_values(a: Values, b: Values) -> Option<Values> {
+    let values = match (a, b) {
+        (Values::Numbers(a), Values::Numbers(b)) => Values::Numbers(chain(a, b, same_number)?),
+        (Values::Pairs(a), Values::Pairs(b)) => Values::Pairs(chain(a, b, |a, b| {
+            same_number(&a.0, &b.0) && same_number(&a.1, &b.1)
+        })?),
+        (Values::Colours(a), Values::Colours(b)) => Values::Colours(chain(a, b, |a, b| a == b)?),
+        (Values::Parameters(mut a), Values::Parameters(b)) => {
+            a.extend(b);
+            Values::Parameters(a)
+        }
+        _ => return None,
+    };
+
+    Some(values)
+}
+
+fn easing(properties: &CommandProperties) -> Option<super::types::Easing> {
+    match properties {
+        CommandProperties::Fade { easing, .. }
+        | CommandProperties::Move { easing, .. }
+        | CommandProperties::MoveX { easing, .. }
+        | CommandProperties::MoveY { easing, .. }
+        | CommandProperties::Scale { easing, .. }
+        | CommandProperties::VectorScale { easing, .. }
+        | CommandProperties::Rotate { easing, .. }
+        | CommandProperties::Colour { easing, .. }
+        | CommandProperties::Parameter { easing, .. } => Some(*easing),
+        CommandProperties::Loop { .. } | CommandProperties::Trigger { .. } => None,
+    }
+}
+
+/// Property of an object that commands change.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+enum Channel {
+    X,
+    Y,
+    Scale,
+    VectorScale,
+    Rotation,
+    Opacity,
+    Colour,
+    Parameter,
+}
+
+fn channels(properties: &CommandProperties) -> &'static [Channel] {
+    match properties {
+        CommandProperties::Fade { .. } => &[Channel::Opacity],
+        CommandProperties::Move { .. } => &[Channel::X, Channel::Y],
+        CommandProperties::MoveX { .. } => &[Channel::X],
+        CommandProperties::MoveY { .. } => &[Channel::Y],
+        CommandProperties::Scale { .. } => &[Channel::Scale],
+        CommandProperties::VectorScale { .. } => &[Channel::VectorScale],
+        CommandProperties::Rotate { .. } => &[Channel::Rotation],
+        CommandProperties::Colour { .. } => &[Channel::Colour],
+        CommandProperties::Parameter { .. } => &[Channel::Parameter],
+        CommandProperties::Loop { .. } | CommandProperties::Trigger { .. } => &[],
+    }
+}
+
+/// A part of a command that changes a channel from one value to another.
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    /// Index of the top level command the segment is from.
+    unit: usize,
+    /// Order of the segment in evaluation, which is by start time, then command start time, then command order.
+    key: (Integer, Integer, usize),
+    start: Integer,
+    end: Integer,
+    from: [f64; 3],
+    to: [f64; 3],
+}
+
+impl Segment {
+    fn is_zero(&self) -> bool {
+        self.from[0] == 0.0 && self.to[0] == 0.0
+    }
+}
+
+/// A command with loops unrolled.
+struct Entry {
+    unit: usize,
+    start: Integer,
+    end: Integer,
+}
+
+/// Segments of every command of an object, the same way they are evaluated by [`Object::timeline`].
+struct Model {
+    entries: Vec<Entry>,
+    channels: HashMap<Channel, Vec<Segment>>,
+    has_triggers: bool,
+}
+
+/// `(channel, start, end, from, to)` of a segment.
+type Piece = (Channel, Integer, Integer, [f64; 3], [f64; 3]);
+
+fn push_pieces(
+    out: &mut Vec<Piece>,
+    channel: Channel,
+    (start, end): (Integer, Integer),
+    values: &[[f64; 3]],
+) {
+    let duration = end - start;
+
+    if let [value] = values {
+        out.push((channel, start, end, *value, *value));
+        return;
+    }
+
+    for (i, pair) in values.windows(2).enumerate() {
+        let start = start + duration * i as Integer;
+        out.push((channel, start, start + duration, pair[0], pair[1]));
+    }
+}
+
+/// Segments of a command, or `None` if it has invalid values.
+fn segments(command: &Command) -> Option<Vec<Piece>> {
+    let times = command_times(command)?;
+    let mut out = Vec::new();
+
+    match values(&command.properties)? {
+        Values::Numbers(values) => {
+            let values = values
+                .iter()
+                .map(|v| v.to_f64().map(|v| [v, 0.0, 0.0]))
+                .collect::<Option<Vec<_>>>()?;
+            push_pieces(&mut out, channels(&command.properties)[0], times, &values);
+        }
+        Values::Pairs(values) => {
+            let values = values
+                .iter()
+                .map(|(x, y)| Some((x.to_f64()?, y.to_f64()?)))
+                .collect::<Option<Vec<_>>>()?;
+
+            if let CommandProperties::Move { .. } = command.properties {
+                let xs = values
+                    .iter()
+                    .map(|(x, _)| [*x, 0.0, 0.0])
+                    .collect::<Vec<_>>();
+                let ys = values
+                    .iter()
+                    .map(|(_, y)| [*y, 0.0, 0.0])
+                    .collect::<Vec<_>>();
+                push_pieces(&mut out, Channel::X, times, &xs);
+                push_pieces(&mut out, Channel::Y, times, &ys);
+            } else {
+                let values = values
+                    .iter()
+                    .map(|(x, y)| [*x, *y, 0.0])
+                    .collect::<Vec<_>>();
+                push_pieces(&mut out, Channel::VectorScale, times, &values);
+            }
+        }
+        Values::Colours(values) => {
+            let values = values
+                .iter()
+                .map(|(r, g, b)| [*r as f64, *g as f64, *b as f64])
+                .collect::<Vec<_>>();
+            push_pieces(&mut out, Channel::Colour, times, &values);
+        }
+        Values::Parameters(values) => {
+            let (start, end) = times;
+            let duration = end - start;
+
+            for i in 0..values.len() as Integer {
+                let start = start + duration * i;
+                out.push((
+                    Channel::Parameter,
+                    start,
+                    start + duration,
+                    [0.0; 3],
+                    [0.0; 3],
+                ));
+            }
+        }
+    }
+
+    Some(out)
+}
+
+impl Model {
+    /// Returns `None` if a command has invalid values, as those are skipped when evaluating.
+    fn new(commands: &[Command]) -> Option<Self> {
+        let mut model = Model {
+            entries: Vec::new(),
+            channels: HashMap::new(),
+            has_triggers: false,
+        };
+        let mut order = 0;
+
+        for (unit, command) in commands.iter().enumerate() {
+            match &command.properties {
+                CommandProperties::Trigger { .. } => model.has_triggers = true,
+                CommandProperties::Loop { .. } => {
+                    let flattened = flatten_commands(std::slice::from_ref(command));
+                    model.has_triggers |= !flattened.triggers.is_empty();
+
+                    for command in &flattened.commands {
+                        model.push(unit, command, &mut order)?;
+                    }
+                }
+                _ => model.push(unit, command, &mut order)?,
+            }
+        }
+
+        for segments in model.channels.values_mut() {
+            segments.sort_by_key(|s| s.key);
+        }
+
+        Some(model)
+    }
+
+    fn push(&mut self, unit: usize, command: &Command, order: &mut usize) -> Option<()> {
+        let (start, end) = command_range(command)?;
+
+        for (channel, segment_start, segment_end, from, to) in segments(command)? {
+            self.channels.entry(channel).or_default().push(Segment {
+                unit,
+                key: (segment_start, start, *order),
+                start: segment_start,
+                end: segment_end,
+                from,
+                to,
+            });
+            *order += 1;
+        }
+        self.entries.push(Entry { unit, start, end });
+
+        Some(())
+    }
+
+    fn channel(&self, channel: Channel) -> &[Segment] {
+        self.channels.get(&channel).map_or(&[], |s| s.as_slice())
+    }
+
+    /// Segments of each unit by channel.
+    fn units(&self) -> HashMap<usize, Vec<(Channel, Segment)>> {
+        let mut units: HashMap<usize, Vec<(Channel, Segment)>> = HashMap::new();
+
+        for (channel, segments) in &self.channels {
+            for segment in segments {
+                units
+                    .entry(segment.unit)
+                    .or_default()
+                    .push((*channel, *segment));
+            }
+        }
+
+        units
+    }
+
+    fn is_invisible(&self) -> bool {
+        !self.has_triggers
+            && (self.entries.is_empty()
+                || self
+                    .channels
+                    .get(&Channel::Opacity)
+                    .is_some_and(|opacity| opacity.iter().all(Segment::is_zero)))
+    }
+}
+
+/// Units removed in a single pass, where units that other removals depend on are pinned so they aren't removed.
+#[derive(Default)]
+struct Removal {
+    removed: HashSet<usize>,
+    pinned: HashSet<usize>,
+}
+
+impl Removal {
+    fn is_kept(&self, unit: usize) -> bool {
+        !self.removed.contains(&unit)
+    }
+
+    fn remove(&mut self, unit: usize, witnesses: Vec<usize>) {
+        self.removed.insert(unit);
+        self.pinned.extend(witnesses);
+    }
+}
+
+#[derive(Clone, Copy)]
+enum RedundantPass {
+    SameValue,
+    AfterFadeOut,
+    BeforeVisible,
+}
+
+/// Removes same value commands that don't change the value they are setting.
+fn remove_same_value(model: &Model, commands: &[Command], removal: &mut Removal) {
+    let mut units = model.units().into_iter().collect::<Vec<_>>();
+    units.sort_by_key(|(unit, _)| *unit);
+
+    'units: for (unit, segments) in units {
+        if removal.pinned.contains(&unit)
+            || matches!(commands[unit].properties, CommandProperties::Loop { .. })
+        {
+            continue;
+        }
+        let Some(entry) = model.entries.iter().find(|e| e.unit == unit) else {
+            continue;
+        };
+
+        let mut witnesses = Vec::new();
+
+        for (channel, segment) in &segments {
+            // continuing segments chain, so every segment of the channel has the same value
+            if *channel == Channel::Parameter || segment.from != segment.to {
+                continue 'units;
+            }
+
+            let others = || {
+                model
+                    .channel(*channel)
+                    .iter()
+                    .filter(|s| s.unit != unit && removal.is_kept(s.unit))
+            };
+
+            let Some(previous) = others().rfind(|s| s.start < entry.start) else {
+                continue 'units;
+            };
+            if previous.end > entry.start
+                || previous.to != segment.from
+                || others().any(|s| entry.start <= s.start && s.start <= entry.end)
+            {
+                continue 'units;
+            }
+            witnesses.push(previous.unit);
+        }
+
+        // the lifetime of the object can't change
+        let others = || {
+            model
+                .entries
+                .iter()
+                .filter(|e| e.unit != unit && removal.is_kept(e.unit))
+        };
+        let (Some(first), Some(last)) = (
+            others().find(|e| e.start <= entry.start),
+            others().find(|e| e.end >= entry.end),
+        ) else {
+            continue;
+        };
+        witnesses.extend([first.unit, last.unit]);
+
+        removal.remove(unit, witnesses);
+    }
+}
+
+/// Removes commands that only start after the object is faded out for good.
+fn remove_after_fade_out(model: &Model, removal: &mut Removal) {
+    let Some(last_fade) = model.channel(Channel::Opacity).last() else {
+        return;
+    };
+    if last_fade.to[0] != 0.0 {
+        return;
+    }
+    let faded_out = last_fade.end;
+    removal.pinned.insert(last_fade.unit);
+
+    let mut units = model.units().into_iter().collect::<Vec<_>>();
+    units.sort_by_key(|(unit, _)| *unit);
+
+    'units: for (unit, segments) in units {
+        if removal.pinned.contains(&unit)
+            || model
+                .entries
+                .iter()
+                .any(|e| e.unit == unit && e.start < faded_out)
+        {
+            continue;
+        }
+
+        let mut witnesses = Vec::new();
+
+        for (channel, _) in &segments {
+            match channel {
+                Channel::Opacity => continue 'units,
+                Channel::Parameter => continue,
+                _ => (),
+            }
+
+            // the command can't be the first of its channel, as that sets the value before it starts
+            let Some(witness) = model
+                .channel(*channel)
+                .iter()
+                .find(|s| s.unit != unit && removal.is_kept(s.unit) && s.start < faded_out)
+            else {
+                continue 'units;
+            };
+            witnesses.push(witness.unit);
+        }
+
+        removal.remove(unit, witnesses);
+    }
+}
+
+/// Removes commands that end before the object is first visible, where a later command takes over their channel.
+fn remove_before_visible(model: &Model, removal: &mut Removal) {
+    let opacity = model.channel(Channel::Opacity);
+    let Some(first_fade) = opacity.first() else {
+        return;
+    };
+    if first_fade.from[0] != 0.0 {
+        return;
+    }
+    let Some(visible_fade) = opacity.iter().find(|s| !s.is_zero()) else {
+        return;
+    };
+    let visible = visible_fade.start;
+    removal.pinned.extend([first_fade.unit, visible_fade.unit]);
+
+    let mut units = model.units().into_iter().collect::<Vec<_>>();
+    units.sort_by_key(|(unit, _)| *unit);
+
+    'units: for (unit, segments) in units {
+        if removal.pinned.contains(&unit)
+            || model
+                .entries
+                .iter()
+                .any(|e| e.unit == unit && e.end >= visible)
+        {
+            continue;
+        }
+
+        let mut witnesses = Vec::new();
+
+        for (channel, segment) in &segments {
+            match channel {
+                Channel::Opacity => continue 'units,
+                // parameters without a duration last forever
+                Channel::Parameter if segment.start == segment.end => continue 'units,
+                Channel::Parameter => continue,
+                _ => (),
+            }
+
+            let last_start = segments
+                .iter()
+                .filter(|(c, _)| c == channel)
+                .map(|(_, s)| s.start)
+                .max()
+                .unwrap();
+            let Some(witness) = model.channel(*channel).iter().find(|s| {
+                s.unit != unit
+                    && removal.is_kept(s.unit)
+                    && last_start < s.start
+                    && s.start <= visible
+            }) else {
+                continue 'units;
+            };
+            witnesses.push(witness.unit);
+        }
+
+        removal.remove(unit, witnesses);
+    }
+}
+
+fn remove_redundant(commands: &mut Vec<Command>) -> usize {
+    let mut removed = 0;
+
+    loop {
+        let mut changed = false;
+
+        for pass in [
+            RedundantPass::SameValue,
+            RedundantPass::AfterFadeOut,
+            RedundantPass::BeforeVisible,
+        ] {
+            let Some(model) = Model::new(commands) else {
+                return removed;
+            };
+            // triggers can change any value at any time
+            if model.has_triggers {
+                return removed;
+            }
+
+            let mut removal = Removal::default();
+            match pass {
+                RedundantPass::SameValue => remove_same_value(&model, commands, &mut removal),
+                RedundantPass::AfterFadeOut => remove_after_fade_out(&model, &mut removal),
+                RedundantPass::BeforeVisible => remove_before_visible(&model, &mut removal),
+            }
+
+            if !removal.removed.is_empty() {
+                let mut unit = 0;
+                commands.retain(|_| {
+                    unit += 1;
+                    !removal.removed.contains(&(unit - 1))
+                });
+                removed += removal.removed.len();
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return removed;
+        }
+    }
+}
+
+/// Merges commands of the same type where the next one continues from the previous one with the same duration.
+fn merge_commands(commands: &mut Vec<Command>) -> usize {
+    let Some(model) = Model::new(commands) else {
+        return 0;
+    };
+
+    // commands merged into each other, by index in `commands`
+    let mut groups = (0..commands.len()).collect::<Vec<_>>();
+    let mut list = std::mem::take(commands)
+        .into_iter()
+        .enumerate()
+        .collect::<Vec<_>>();
+    let mut merged = 0;
+
+    let mut i = 0;
+    while i < list.len() {
+        let mut j = i + 1;
+
+        while j < list.len() {
+            let (a_unit, a) = &list[i];
+            let (b_unit, b) = &list[j];
+
+            if std::mem::discriminant(&a.properties) != std::mem::discriminant(&b.properties) {
+                j += 1;
+                continue;
+            }
+
+            let Some(merged_command) = merge_pair(a, b, |start, end| {
+                // other commands of the same channels in between change the evaluation order
+                let (a_group, b_group) = (groups[*a_unit], groups[*b_unit]);
+
+                model.channels.iter().any(|(channel, segments)| {
+                    channels(&a.properties).contains(channel)
+                        && segments.iter().any(|s| {
+                            let group = groups[s.unit];
+                            group != a_group && group != b_group && s.start <= end && start <= s.end
+                        })
+                })
+            }) else {
+                break;
+            };
+
+            let b_unit = *b_unit;
+            let a_unit = *a_unit;
+            for group in groups.iter_mut() {
+                if *group == b_unit {
+                    *group = a_unit;
+                }
+            }
+
+            list[i].1 = merged_command;
+            list.remove(j);
+            merged += 1;
+        }
+
+        i += 1;
+    }
+
+    *commands = list.into_iter().map(|(_, command)| command).collect();
+    merged
+}
+
+fn merge_pair(
+    a: &Command,
+    b: &Command,
+    conflicts: impl Fn(Integer, Integer) -> bool,
+) -> Option<Command> {
+    let (a_start, a_end) = (a.start_time?, a.end_time()?);
+    let (b_start, b_end) = (b.start_time?, b.end_time()?);
+    let duration = a_end - a_start;
+
+    if duration <= 0
+        || b_end - b_start != duration
+        || b_start != a_start + duration * segment_count(&a.properties) as Integer
+        || easing(&a.properties)? != easing(&b.properties)?
+    {
+        return None;
+    }
+
+    let values = merge_values(values(&a.properties)?, values(&b.properties)?)?;
+    let (_, end) = command_range(b)?;
+    if conflicts(a_start, end) {
+        return None;
+    }
+
+    let mut merged = a.clone();
+    set_values(&mut merged.properties, values);
+
+    Some(merged)
+}
+
+/// Folds runs of commands that repeat with a fixed period into `Loop` commands.
+fn fold_loops(commands: &mut Vec<Command>) -> usize {
+    let mut folded = 0;
+    let mut i = 0;
+
+    while i < commands.len() {
+        // (block length, iterations, lines saved)
+        let mut best: Option<(usize, usize, usize)> = None;
+        let mut range: Option<(Integer, Integer)> = None;
+
+        for length in 1..=(commands.len() - i) / 2 {
+            let command = &commands[i + length - 1];
+            if command.start_time.is_none()
+                || matches!(
+                    command.properties,
+                    CommandProperties::Loop { .. } | CommandProperties::Trigger { .. }
+                )
+            {
+                break;
+            }
+
+            range = match (range, command_range(command)) {
+                (Some((s, e)), Some((cs, ce))) => Some((s.min(cs), e.max(ce))),
+                (range, command) => range.or(command),
+            };
+            let Some((start, end)) = range else {
+                continue;
+            };
+            let period = end - start;
+            if period <= 0 {
+                continue;
+            }
+
+            let block = &commands[i..i + length];
+            let mut iterations = 1;
+            while i + (iterations + 1) * length <= commands.len()
+                // only times and values are compared, so commands written with variables still match
+                && block.iter().enumerate().all(|(j, command)| {
+                    commands[i + iterations * length + j]
+                        == shift_command(command, period * iterations as Integer)
+                })
+            {
+                iterations += 1;
+            }
+
+            // the loop takes a line of its own
+            let lines = iterations * length;
+            if lines > length + 1 && best.is_none_or(|(_, _, saved)| lines - length - 1 > saved) {
+                best = Some((length, iterations, lines - length - 1));
+            }
+        }
+
+        if let Some((length, iterations, _)) = best {
+            let block = &commands[i..i + length];
+            let (start, _) = commands_range(block).unwrap();
+            let loop_command = Command::new(
+                Some(start),
+                CommandProperties::Loop {
+                    loop_count: iterations as u32,
+                    commands: block.iter().map(|c| shift_command(c, -start)).collect(),
+                },
+            );
+
+            commands.splice(i..i + length * iterations, [loop_command]);
+            folded += 1;
+        }
+
+        i += 1;
+    }
+
+    folded
+}
+
+impl Object {
+    fn optimize(&mut self, options: &OptimizeOptions, report: &mut OptimizeReport) {
+        if options.remove_redundant {
+            report.removed_commands += remove_redundant(&mut self.commands);
+        }
+        if options.merge_commands {
+            report.merged_commands += merge_commands(&mut self.commands);
+        }
+        if options.fold_loops {
+            report.folded_loops += fold_loops(&mut self.commands);
+        }
+    }
+
+    /// Returns `true` if the object is never visible, which is if it has no commands or is always faded out.
+    /// - Objects with triggers are treated as visible.
+    pub fn is_invisible(&self) -> bool {
+        Model::new(&self.commands).is_some_and(|model| model.is_invisible())
+    }
+}
+
+impl Events {
+    /// Rewrites storyboard objects with fewer commands, without changing how they are drawn.
+    /// - Objects are only changed the ways [`Object::state_at`] can tell apart, so commands inside of triggers are left as is.
+    /// - Objects with triggers only have their commands merged and folded.
+    /// - Commands with values that aren't valid numbers stop redundant commands of the object from being removed.
+    pub fn optimize(&mut self, options: &OptimizeOptions) -> OptimizeReport {
+        let mut report = OptimizeReport::default();
+
+        if options.drop_invisible {
+            let count = self.0.len();
+            self.0.retain(
+                |event| !matches!(event, Event::StoryboardObject(object) if object.is_invisible()),
+            );
+            report.dropped_objects = count - self.0.len();
+        }
+
+        for event in self.0.iter_mut() {
+            if let Event::StoryboardObject(object) = event {
+                object.optimize(options, &mut report);
+            }
+        }
+
+        report
+    }
+}
+
+/// Values of the command as written after its times, if they are only made of numbers.
+fn command_values(command: &Command) -> Option<String> {
+    if matches!(
+        command.properties,
+        CommandProperties::Parameter { .. }
+            | CommandProperties::Loop { .. }
+            | CommandProperties::Trigger { .. }
+    ) {
+        return None;
+    }
+
+    let command = command.to_string(LATEST_VERSION)?;
+    let values = command.splitn(5, ',').nth(4)?;
+
+    values
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '-'))
+        .then(|| values.to_string())
+}
+
+fn count_values(commands: &[Command], counts: &mut HashMap<String, usize>) {
+    for command in commands {
+        match &command.properties {
+            CommandProperties::Loop { commands, .. }
+            | CommandProperties::Trigger { commands, .. } => count_values(commands, counts),
+            _ => {
+                if let Some(values) = command_values(command) {
+                    *counts.entry(values).or_default() += 1;
+                }
+            }
+        }
+    }
+}
+
+fn clear_variables(commands: &mut [Command]) {
+    for command in commands {
+        command.variables = None;
+
+        if let CommandProperties::Loop { commands, .. }
+        | CommandProperties::Trigger { commands, .. } = &mut command.properties
+        {
+            clear_variables(commands);
+        }
+    }
+}
+
+/// Name of the variable with letters only, so it can't be confused with numbers.
+fn variable_name(mut index: usize, width: usize) -> String {
+    let mut name = vec![b'a'; width];
+
+    for letter in name.iter_mut().rev() {
+        *letter = b'a' + (index % 26) as u8;
+        index /= 26;
+    }
+
+    String::from_utf8(name).unwrap()
+}
+
+/// Picks variables for command values that are repeated enough to make the file smaller.
+fn pick_variables(events: &Events) -> Vec<Variable> {
+    let mut counts = HashMap::new();
+
+    for event in &events.0 {
+        let commands = match event {
+            Event::SpriteLegacy(sprite) => &sprite.commands,
+            Event::AnimationLegacy(animation) => &animation.commands,
+            Event::SampleLegacy(sample) => &sample.commands,
+            Event::StoryboardObject(object) => &object.commands,
+            _ => continue,
+        };
+        count_values(commands, &mut counts);
+    }
+
+    // names are the same length so one can't be the start of another
+    let mut width = 1;
+    loop {
+        // `$name=value\n` costs a line, while each use saves the value minus `$name`
+        let mut picked = counts
+            .iter()
+            .filter_map(|(value, count)| {
+                let saved = (*count * value.len()).checked_sub(*count * (width + 1))?;
+                let saved = saved.checked_sub(width + value.len() + 3)?;
+                (saved > 0).then_some((value, saved))
+            })
+            .collect::<Vec<_>>();
+
+        if picked.len() > 26usize.pow(width as u32) {
+            width += 1;
+            continue;
+        }
+
+        // the `[Variables]` line and the empty line after the section
+        if picked.iter().map(|(_, saved)| saved).sum::<usize>() <= "[Variables]\n\n".len() {
+            return Vec::new();
+        }
+
+        // longer values first, so they are replaced before values inside of them
+        picked.sort_by(|(a, a_saved), (b, b_saved)| {
+            b.len()
+                .cmp(&a.len())
+                .then(b_saved.cmp(a_saved))
+                .then(a.cmp(b))
+        });
+
+        return picked
+            .into_iter()
+            .enumerate()
+            .map(|(i, (value, _))| Variable {
+                name: variable_name(i, width),
+                value: value.clone(),
+            })
+            .collect();
+    }
+}
+
+impl Osb {
+    /// Rewrites the storyboard with fewer commands, without changing how it's drawn.
+    /// - See [`Events::optimize`] for what is done to the events.
+    /// - If [`OptimizeOptions::variables`] is `true`, the `[Variables]` section is replaced with variables for repeated command values.
+    pub fn optimize(&mut self, options: &OptimizeOptions) -> OptimizeReport {
+        let mut report = match &mut self.events {
+            Some(events) => events.optimize(options),
+            None => OptimizeReport::default(),
+        };
+
+        if options.variables {
+            let variables = self.events.as_ref().map(pick_variables).unwrap_or_default();
+
+            // commands are written with the new variables instead of how they were written before
+            for event in self.events.iter_mut().flat_map(|events| events.0.iter_mut()) {
+                match event {
+                    Event::Background(background) => clear_variables(&mut background.commands),
+                    Event::Video(video) => clear_variables(&mut video.commands),
+                    Event::SpriteLegacy(sprite) => clear_variables(&mut sprite.commands),
+                    Event::AnimationLegacy(animation) => clear_variables(&mut animation.commands),
+                    Event::SampleLegacy(sample) => clear_variables(&mut sample.commands),
+                    Event::StoryboardObject(object) => clear_variables(&mut object.commands),
+                    _ => (),
+                }
+            }
+
+            report.variables = variables.len();
+            self.variables = (!variables.is_empty()).then_some(variables);
+        }
+
+        report
+    }
+}
@@ -0,0 +1,506 @@
+//! CPU rendering of storyboard frames, for comparing how storyboards look without osu!.
+//!
+//! Images are sampled with nearest neighbour filtering onto an opaque black screen.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use either::Either;
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::osb::Osb;
+use crate::osu_file::assets::{frame_paths, list_files, normalize_path, path_to_str};
+use crate::osu_file::events::types::{LayerLegacy, OriginTypeLegacy};
+use crate::osu_file::events::{Event, Events};
+use crate::osu_file::{FilePath, OsuFile, Position};
+
+use super::cmds::Command;
+use super::error::RenderError;
+use super::eval::ObjectState;
+use super::sprites::{Layer, LoopType, Object, ObjectType, Origin, OriginType, Sprite};
+
+/// Width of the storyboard area in storyboard pixels.
+const STORYBOARD_WIDTH: f64 = 640.0;
+/// Width of the screen for widescreen storyboards in storyboard pixels.
+const WIDESCREEN_WIDTH: f64 = 854.0;
+const STORYBOARD_HEIGHT: f64 = 480.0;
+
+/// An image with 8 bit red, green, blue and alpha channels, stored row by row.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// New image filled with `colour`.
+    pub fn new(width: u32, height: u32, colour: [u8; 4]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: colour.repeat(width as usize * height as usize),
+        }
+    }
+
+    /// Colour of the pixel, or `None` if it's outside of the image.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[i..i + 4].try_into().ok()
+    }
+
+    /// Encodes the image as a png file.
+    pub fn to_png(&self) -> Result<Vec<u8>, RenderError> {
+        let mut png = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+
+        Ok(png)
+    }
+
+    /// Decodes a png or jpeg image.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, RenderError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)?;
+
+        if bytes.starts_with(b"\x89PNG") {
+            decode_png(&bytes).map_err(|source| RenderError::PngDecode {
+                path: path.to_owned(),
+                source,
+            })
+        } else if bytes.starts_with(&[0xFF, 0xD8]) {
+            decode_jpeg(&bytes).map_err(|source| RenderError::JpegDecode {
+                path: path.to_owned(),
+                source,
+            })
+        } else {
+            Err(RenderError::UnsupportedImage(path.to_owned()))
+        }
+    }
+}
+
+fn decode_png(bytes: &[u8]) -> Result<RgbaImage, png::DecodingError> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+
+    let mut buf = vec![0; reader.output_buffer_size().unwrap_or_default()];
+    let info = reader.next_frame(&mut buf)?;
+    let buf = &buf[..info.buffer_size()];
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buf.to_vec(),
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        // palettes are expanded to rgb by the transformations
+        png::ColorType::Grayscale | png::ColorType::Indexed => {
+            buf.iter().flat_map(|p| [*p, *p, *p, 255]).collect()
+        }
+    };
+
+    Ok(RgbaImage {
+        width: info.width,
+        height: info.height,
+        pixels,
+    })
+}
+
+fn decode_jpeg(bytes: &[u8]) -> Result<RgbaImage, jpeg_decoder::Error> {
+    let mut decoder = jpeg_decoder::Decoder::new(bytes);
+    let buf = decoder.decode()?;
+    let info = decoder.info().unwrap();
+
+    let pixels = match info.pixel_format {
+        jpeg_decoder::PixelFormat::L8 => buf.iter().flat_map(|p| [*p, *p, *p, 255]).collect(),
+        jpeg_decoder::PixelFormat::L16 => buf
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], 255])
+            .collect(),
+        jpeg_decoder::PixelFormat::RGB24 => buf
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        jpeg_decoder::PixelFormat::CMYK32 => buf
+            .chunks_exact(4)
+            .flat_map(|p| {
+                let k = p[3] as u16;
+                let channel = |c: u8| (c as u16 * k / 255) as u8;
+                [channel(p[0]), channel(p[1]), channel(p[2]), 255]
+            })
+            .collect(),
+    };
+
+    Ok(RgbaImage {
+        width: info.width as u32,
+        height: info.height as u32,
+        pixels,
+    })
+}
+
+/// Renders storyboard frames with images from a beatmap folder.
+/// - Images are looked up case-insensitively and only loaded once.
+/// - Objects with missing images aren't drawn.
+#[derive(Clone, Debug)]
+pub struct Renderer {
+    folder: PathBuf,
+    files: Option<HashMap<String, String>>,
+    images: HashMap<String, Option<Rc<RgbaImage>>>,
+    widescreen: bool,
+    height: u32,
+    passing: bool,
+}
+
+/// Order of the layers, where higher layers are drawn on top.
+fn layer_order(layer: Layer) -> u8 {
+    match layer {
+        Layer::Background => 0,
+        Layer::Fail => 1,
+        Layer::Pass => 2,
+        Layer::Foreground => 3,
+        Layer::Overlay => 4,
+    }
+}
+
+fn legacy_layer(layer: LayerLegacy) -> Layer {
+    match layer {
+        LayerLegacy::Background | LayerLegacy::Video => Layer::Background,
+        LayerLegacy::Fail => Layer::Fail,
+        LayerLegacy::Pass => Layer::Pass,
+        LayerLegacy::Foreground => Layer::Foreground,
+        LayerLegacy::Overlay => Layer::Overlay,
+    }
+}
+
+fn legacy_origin(origin: OriginTypeLegacy) -> OriginType {
+    match origin {
+        OriginTypeLegacy::TopLeft => OriginType::TopLeft,
+        OriginTypeLegacy::Centre => OriginType::Centre,
+        OriginTypeLegacy::CentreLeft => OriginType::CentreLeft,
+        OriginTypeLegacy::TopRight => OriginType::TopRight,
+        OriginTypeLegacy::BottomCentre => OriginType::BottomCentre,
+        OriginTypeLegacy::TopCentre => OriginType::TopCentre,
+        OriginTypeLegacy::Custom => OriginType::Custom,
+        OriginTypeLegacy::CentreRight => OriginType::CentreRight,
+        OriginTypeLegacy::BottomLeft => OriginType::BottomLeft,
+        OriginTypeLegacy::BottomRight => OriginType::BottomRight,
+    }
+}
+
+/// Legacy sprites drawn as storyboard objects.
+fn legacy_object(
+    layer: LayerLegacy,
+    origin: OriginTypeLegacy,
+    file_name: &FilePath,
+    position: &Option<Position>,
+    commands: &[Command],
+) -> Object {
+    Object {
+        layer: legacy_layer(layer),
+        origin: Origin {
+            type_: Either::Left(legacy_origin(origin)),
+            shorthand: false,
+        },
+        position: position.clone().unwrap_or_default(),
+        object_type: ObjectType::Sprite(Sprite {
+            filepath: file_name.clone(),
+        }),
+        commands: commands.to_vec(),
+    }
+}
+
+/// Objects of the events that can be drawn.
+fn objects(events: &Events) -> Vec<Object> {
+    events
+        .0
+        .iter()
+        .filter_map(|event| match event {
+            Event::StoryboardObject(object) => Some(object.clone()),
+            Event::SpriteLegacy(sprite) => Some(legacy_object(
+                sprite.layer,
+                sprite.origin,
+                &sprite.file_name,
+                &sprite.position,
+                &sprite.commands,
+            )),
+            Event::AnimationLegacy(animation) => Some(legacy_object(
+                animation.layer,
+                animation.origin,
+                &animation.file_name,
+                &animation.position,
+                &animation.commands,
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Path of the image the object shows at `time`.
+fn image_path(object: &Object, time: f64, start: f64) -> Option<String> {
+    match &object.object_type {
+        ObjectType::Sprite(sprite) => Some(path_to_str(sprite.filepath.get())),
+        ObjectType::Animation(animation) => {
+            let frame_count = animation.frame_count as usize;
+            let delay = animation.frame_delay.to_f64().unwrap_or_default();
+            let frame = if delay > 0.0 {
+                ((time - start) / delay).floor().max(0.0) as usize
+            } else {
+                0
+            };
+            let frame = match animation.loop_type {
+                LoopType::LoopForever => frame.checked_rem(frame_count)?,
+                LoopType::LoopOnce => frame.min(frame_count.checked_sub(1)?),
+            };
+
+            frame_paths(&path_to_str(animation.filepath.get()), frame as u32 + 1).pop()
+        }
+    }
+}
+
+impl Renderer {
+    /// Renderer for 4:3 storyboards at 640x480, with images from `folder`.
+    pub fn new<P: AsRef<Path>>(folder: P) -> Self {
+        Self {
+            folder: folder.as_ref().to_owned(),
+            files: None,
+            images: HashMap::new(),
+            widescreen: false,
+            height: STORYBOARD_HEIGHT as u32,
+            passing: true,
+        }
+    }
+
+    /// Renders the 16:9 screen, where the 4:3 storyboard area is in the middle.
+    pub fn widescreen(mut self, widescreen: bool) -> Self {
+        self.widescreen = widescreen;
+        self
+    }
+
+    /// Uses `WidescreenStoryboard` of the beatmap.
+    pub fn widescreen_of(self, osu_file: &OsuFile) -> Self {
+        let widescreen = osu_file
+            .general
+            .as_ref()
+            .and_then(|general| general.widescreen_storyboard.as_ref())
+            .is_some_and(|widescreen| widescreen.0);
+
+        self.widescreen(widescreen)
+    }
+
+    /// Height of the rendered images in pixels, where the width follows the aspect ratio.
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Draws the `Pass` layer if `true`, or the `Fail` layer if `false`.
+    /// - Defaults to `true`.
+    pub fn passing(mut self, passing: bool) -> Self {
+        self.passing = passing;
+        self
+    }
+
+    /// Width and height of the rendered images in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        let width = if self.widescreen {
+            WIDESCREEN_WIDTH
+        } else {
+            STORYBOARD_WIDTH
+        };
+        let scale = self.height as f64 / STORYBOARD_HEIGHT;
+
+        ((width * scale).round() as u32, self.height)
+    }
+
+    fn image(&mut self, path: &str) -> Result<Option<Rc<RgbaImage>>, RenderError> {
+        let path = normalize_path(path);
+        if let Some(image) = self.images.get(&path) {
+            return Ok(image.clone());
+        }
+
+        if self.files.is_none() {
+            let mut files = Vec::new();
+            list_files(&self.folder, "", &mut files)?;
+            self.files = Some(files.into_iter().map(|f| (normalize_path(&f), f)).collect());
+        }
+
+        let image = match self.files.as_ref().and_then(|files| files.get(&path)) {
+            Some(file) => Some(Rc::new(RgbaImage::open(self.folder.join(file))?)),
+            None => None,
+        };
+        self.images.insert(path, image.clone());
+
+        Ok(image)
+    }
+
+    /// Renders the storyboard objects and legacy sprites of the events at `time` in milliseconds.
+    /// - Layers are drawn from `Background` to `Overlay`, and objects of the same layer in the order of the events.
+    /// - Triggers are ignored as they depend on gameplay.
+    pub fn render(&mut self, events: &Events, time: f64) -> Result<RgbaImage, RenderError> {
+        self.render_objects(objects(events), time)
+    }
+
+    /// Renders the storyboard of an .osb file at `time` in milliseconds.
+    pub fn render_osb(&mut self, osb: &Osb, time: f64) -> Result<RgbaImage, RenderError> {
+        let objects = osb.events.as_ref().map(objects).unwrap_or_default();
+        self.render_objects(objects, time)
+    }
+
+    /// Renders the storyboard of a beatmap, including the storyboard in [`OsuFile::osb`], at `time` in milliseconds.
+    /// - Objects of the beatmap are drawn on top of objects of the .osb file in the same layer.
+    pub fn render_osu_file(
+        &mut self,
+        osu_file: &OsuFile,
+        time: f64,
+    ) -> Result<RgbaImage, RenderError> {
+        let mut all_objects = Vec::new();
+        if let Some(events) = osu_file.osb.as_ref().and_then(|osb| osb.events.as_ref()) {
+            all_objects.extend(objects(events));
+        }
+        if let Some(events) = &osu_file.events {
+            all_objects.extend(objects(events));
+        }
+
+        self.render_objects(all_objects, time)
+    }
+
+    fn render_objects(
+        &mut self,
+        mut objects: Vec<Object>,
+        time: f64,
+    ) -> Result<RgbaImage, RenderError> {
+        let (width, height) = self.size();
+        let mut frame = RgbaImage::new(width, height, [0, 0, 0, 255]);
+
+        let hidden_layer = if self.passing {
+            Layer::Fail
+        } else {
+            Layer::Pass
+        };
+        objects.retain(|object| object.layer != hidden_layer);
+        objects.sort_by_key(|object| layer_order(object.layer));
+
+        for object in &objects {
+            let timeline = object.timeline();
+            let state = timeline.state_at(time);
+            if !state.visible {
+                continue;
+            }
+
+            let start = timeline.lifetime().map_or(0.0, |(start, _)| start);
+            let Some(path) = image_path(object, time, start) else {
+                continue;
+            };
+            if let Some(image) = self.image(&path)? {
+                self.draw(&mut frame, &image, &state);
+            }
+        }
+
+        Ok(frame)
+    }
+
+    fn draw(&self, frame: &mut RgbaImage, image: &RgbaImage, state: &ObjectState) {
+        let scale = self.height as f64 / STORYBOARD_HEIGHT;
+        let offset_x = if self.widescreen {
+            (WIDESCREEN_WIDTH - STORYBOARD_WIDTH) / 2.0
+        } else {
+            0.0
+        };
+
+        let (scale_x, scale_y) = state.total_scale();
+        let (scale_x, scale_y) = (scale_x * scale, scale_y * scale);
+        if scale_x == 0.0 || scale_y == 0.0 || image.width == 0 || image.height == 0 {
+            return;
+        }
+
+        let (image_width, image_height) = (image.width as f64, image.height as f64);
+        let anchor = (state.origin.0 * image_width, state.origin.1 * image_height);
+        let position = (
+            (state.position.0 + offset_x) * scale,
+            state.position.1 * scale,
+        );
+        let (sin, cos) = state.rotation.sin_cos();
+
+        // screen position of a point of the image
+        let to_screen = |x: f64, y: f64| {
+            let (x, y) = ((x - anchor.0) * scale_x, (y - anchor.1) * scale_y);
+            (
+                position.0 + x * cos - y * sin,
+                position.1 + x * sin + y * cos,
+            )
+        };
+        let corners = [
+            to_screen(0.0, 0.0),
+            to_screen(image_width, 0.0),
+            to_screen(0.0, image_height),
+            to_screen(image_width, image_height),
+        ];
+        let min = |f: fn(&(f64, f64)) -> f64| corners.iter().map(f).fold(f64::MAX, f64::min);
+        let max = |f: fn(&(f64, f64)) -> f64| corners.iter().map(f).fold(f64::MIN, f64::max);
+
+        let x_range = min(|c| c.0).floor().max(0.0) as u32..max(|c| c.0).ceil().max(0.0) as u32;
+        let y_range = min(|c| c.1).floor().max(0.0) as u32..max(|c| c.1).ceil().max(0.0) as u32;
+        let tint = [
+            state.colour.0 / 255.0,
+            state.colour.1 / 255.0,
+            state.colour.2 / 255.0,
+        ];
+
+        for y in y_range.start..y_range.end.min(frame.height) {
+            for x in x_range.start..x_range.end.min(frame.width) {
+                // pixel centre back in the image
+                let (dx, dy) = (x as f64 + 0.5 - position.0, y as f64 + 0.5 - position.1);
+                let image_x = (dx * cos + dy * sin) / scale_x + anchor.0;
+                let image_y = (-dx * sin + dy * cos) / scale_y + anchor.1;
+                if !(0.0..image_width).contains(&image_x) || !(0.0..image_height).contains(&image_y)
+                {
+                    continue;
+                }
+
+                let mut image_x = image_x as u32;
+                let mut image_y = image_y as u32;
+                if state.flip_horizontal {
+                    image_x = image.width - 1 - image_x;
+                }
+                if state.flip_vertical {
+                    image_y = image.height - 1 - image_y;
+                }
+
+                let source = image.pixel(image_x, image_y).unwrap();
+                let alpha = source[3] as f64 / 255.0 * state.opacity.clamp(0.0, 1.0);
+                if alpha <= 0.0 {
+                    continue;
+                }
+
+                let i = (y as usize * frame.width as usize + x as usize) * 4;
+                for (channel, tint) in tint.iter().enumerate() {
+                    let source = source[channel] as f64 * tint.clamp(0.0, 1.0);
+                    let destination = frame.pixels[i + channel] as f64;
+
+                    let blended = if state.additive {
+                        destination + source * alpha
+                    } else {
+                        source * alpha + destination * (1.0 - alpha)
+                    };
+                    frame.pixels[i + channel] = blended.round().clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+    }
+}
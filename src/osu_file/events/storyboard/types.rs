@@ -1,11 +1,14 @@
+use rust_decimal::prelude::FromPrimitive;
+
 use crate::{
-    osu_file::{Version, VersionedFromStr, VersionedToString},
+    osu_file::{Decimal, Version, VersionedFromStr, VersionedToString},
     Integer, VersionedFrom,
 };
 
 use super::error::*;
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum TriggerType {
     HitSound {
@@ -133,6 +136,7 @@ impl VersionedToString for TriggerType {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum SampleSet {
     All,
@@ -169,6 +173,7 @@ impl VersionedToString for SampleSet {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum Addition {
     Whistle,
@@ -202,6 +207,7 @@ impl VersionedToString for Addition {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum Easing {
     Linear,
@@ -242,6 +248,169 @@ pub enum Easing {
     Other(Integer),
 }
 
+const ELASTIC_CONST: f64 = 2.0 * std::f64::consts::PI / 0.3;
+const ELASTIC_CONST2: f64 = 0.3 / 4.0;
+const BACK_CONST: f64 = 1.70158;
+const BACK_CONST2: f64 = BACK_CONST * 1.525;
+const BOUNCE_CONST: f64 = 1.0 / 2.75;
+
+impl Easing {
+    /// Applies the easing to the progress `t` of a command, which goes from `0` to `1`.
+    /// - Uses the same formulas as osu! stable, including the overshoot of the `Back` and `Elastic` easings.
+    /// - `Other` easings are linear.
+    pub fn apply(&self, t: f64) -> f64 {
+        use std::f64::consts::PI;
+
+        match self {
+            Easing::Linear | Easing::Other(_) => t,
+            Easing::EasingIn | Easing::QuadIn => t * t,
+            Easing::EasingOut | Easing::QuadOut => t * (2.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    t * t * 2.0
+                } else {
+                    let t = t - 1.0;
+                    t * t * -2.0 + 1.0
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => {
+                let t = t - 1.0;
+                t * t * t + 1.0
+            }
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    t * t * t * 4.0
+                } else {
+                    let t = t - 1.0;
+                    t * t * t * 4.0 + 1.0
+                }
+            }
+            Easing::QuartIn => t * t * t * t,
+            Easing::QuartOut => {
+                let t = t - 1.0;
+                1.0 - t * t * t * t
+            }
+            Easing::QuartInOut => {
+                if t < 0.5 {
+                    t * t * t * t * 8.0
+                } else {
+                    let t = t - 1.0;
+                    t * t * t * t * -8.0 + 1.0
+                }
+            }
+            Easing::QuintIn => t * t * t * t * t,
+            Easing::QuintOut => {
+                let t = t - 1.0;
+                t * t * t * t * t + 1.0
+            }
+            Easing::QuintInOut => {
+                if t < 0.5 {
+                    t * t * t * t * t * 16.0
+                } else {
+                    let t = t - 1.0;
+                    t * t * t * t * t * 16.0 + 1.0
+                }
+            }
+            Easing::SineIn => 1.0 - (PI * 0.5 * t).cos(),
+            Easing::SineOut => (PI * 0.5 * t).sin(),
+            Easing::SineInOut => 0.5 - 0.5 * (PI * t).cos(),
+            Easing::ExpoIn => 2f64.powf(10.0 * (t - 1.0)),
+            Easing::ExpoOut => -(2f64.powf(-10.0 * t)) + 1.0,
+            Easing::ExpoInOut => {
+                if t < 0.5 {
+                    0.5 * 2f64.powf(20.0 * t - 10.0)
+                } else {
+                    1.0 - 0.5 * 2f64.powf(-20.0 * t + 10.0)
+                }
+            }
+            Easing::CircIn => 1.0 - (1.0 - t * t).sqrt(),
+            Easing::CircOut => {
+                let t = t - 1.0;
+                (1.0 - t * t).sqrt()
+            }
+            Easing::CircInOut => {
+                let t = t * 2.0;
+                if t < 1.0 {
+                    0.5 - 0.5 * (1.0 - t * t).sqrt()
+                } else {
+                    let t = t - 2.0;
+                    0.5 * (1.0 - t * t).sqrt() + 0.5
+                }
+            }
+            Easing::ElasticIn => {
+                -(2f64.powf(-10.0 + 10.0 * t)) * ((1.0 - ELASTIC_CONST2 - t) * ELASTIC_CONST).sin()
+            }
+            Easing::ElasticOut => {
+                2f64.powf(-10.0 * t) * ((t - ELASTIC_CONST2) * ELASTIC_CONST).sin() + 1.0
+            }
+            Easing::ElasticHalfOut => {
+                2f64.powf(-10.0 * t) * ((0.5 * t - ELASTIC_CONST2) * ELASTIC_CONST).sin() + 1.0
+            }
+            Easing::ElasticQuarterOut => {
+                2f64.powf(-10.0 * t) * ((0.25 * t - ELASTIC_CONST2) * ELASTIC_CONST).sin() + 1.0
+            }
+            Easing::ElasticInOut => {
+                let t = t * 2.0;
+                if t < 1.0 {
+                    -0.5 * 2f64.powf(-10.0 + 10.0 * t)
+                        * ((1.0 - ELASTIC_CONST2 * 1.5 - t) * ELASTIC_CONST / 1.5).sin()
+                } else {
+                    let t = t - 1.0;
+                    0.5 * 2f64.powf(-10.0 * t)
+                        * ((t - ELASTIC_CONST2 * 1.5) * ELASTIC_CONST / 1.5).sin()
+                        + 1.0
+                }
+            }
+            Easing::BackIn => t * t * ((BACK_CONST + 1.0) * t - BACK_CONST),
+            Easing::BackOut => {
+                let t = t - 1.0;
+                t * t * ((BACK_CONST + 1.0) * t + BACK_CONST) + 1.0
+            }
+            Easing::BackInOut => {
+                let t = t * 2.0;
+                if t < 1.0 {
+                    0.5 * t * t * ((BACK_CONST2 + 1.0) * t - BACK_CONST2)
+                } else {
+                    let t = t - 2.0;
+                    0.5 * (t * t * ((BACK_CONST2 + 1.0) * t + BACK_CONST2) + 2.0)
+                }
+            }
+            Easing::BounceIn => 1.0 - Easing::BounceOut.apply(1.0 - t),
+            Easing::BounceOut => {
+                if t < BOUNCE_CONST {
+                    7.5625 * t * t
+                } else if t < 2.0 * BOUNCE_CONST {
+                    let t = t - 1.5 * BOUNCE_CONST;
+                    7.5625 * t * t + 0.75
+                } else if t < 2.5 * BOUNCE_CONST {
+                    let t = t - 2.25 * BOUNCE_CONST;
+                    7.5625 * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 * BOUNCE_CONST;
+                    7.5625 * t * t + 0.984375
+                }
+            }
+            Easing::BounceInOut => {
+                if t < 0.5 {
+                    0.5 - 0.5 * Easing::BounceOut.apply(1.0 - t * 2.0)
+                } else {
+                    Easing::BounceOut.apply((t - 0.5) * 2.0) * 0.5 + 0.5
+                }
+            }
+        }
+    }
+
+    /// [`apply`][Self::apply] for `Decimal` values.
+    /// - The calculation is done with `f64` like osu! does.
+    /// - Returns `None` if `t` isn't a valid number.
+    pub fn apply_decimal(&self, t: &Decimal) -> Option<Decimal> {
+        let value = self.apply(t.to_f64()?);
+
+        rust_decimal::Decimal::from_f64(value).map(Decimal::from)
+    }
+}
+
 impl VersionedFrom<Integer> for Easing {
     fn from(value: Integer, _: Version) -> Option<Self> {
         match value {
@@ -335,6 +504,7 @@ impl VersionedToString for Easing {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum Parameter {
     ImageFlipHorizontal,
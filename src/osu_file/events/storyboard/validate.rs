@@ -0,0 +1,300 @@
+//! Checks for storyboards that parse but misbehave in game.
+
+use std::mem::discriminant;
+
+use either::Either;
+use thiserror::Error;
+
+use crate::osb::{Variable, VariableToken};
+use crate::osu_file::events::{Event, Events};
+use crate::osu_file::{FilePath, Integer, Version, VersionedFromStr};
+
+use super::cmds::{Command, CommandProperties};
+use super::error::ParseCommandError;
+use super::flatten::command_range;
+use super::sprites::ObjectType;
+
+/// Something wrong with a storyboard line.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Issue {
+    /// Line index of the line in the input.
+    pub line_index: usize,
+    pub kind: IssueKind,
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}, {}", self.line_index + 1, self.kind)
+    }
+}
+
+#[derive(Clone, Debug, Error, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IssueKind {
+    /// The line couldn't be parsed.
+    #[error("Invalid line: {0}")]
+    InvalidLine(String),
+    #[error("The end time {end} is before the start time {start}")]
+    EndBeforeStart { start: Integer, end: Integer },
+    #[error("The loop count is 0")]
+    LoopCountZero,
+    #[error("The loop has no commands")]
+    EmptyLoop,
+    #[error("The trigger has no commands")]
+    EmptyTrigger,
+    #[error("Unknown trigger type `{0}`")]
+    UnknownTriggerType(String),
+    /// Origins that aren't one of the named origins, which osu! treats inconsistently.
+    #[error("Undocumented origin {0}")]
+    UndocumentedOrigin(Integer),
+    #[error("The animation has no frames")]
+    NoFrames,
+    /// File paths need to be relative to the beatmap folder.
+    #[error("The file path `{0}` isn't relative")]
+    AbsolutePath(String),
+    /// A command of the same type that runs at the same time, where only one of them is used by osu!.
+    #[error("Overlaps with the command on line {}", other_line_index + 1)]
+    OverlappingCommands { other_line_index: usize },
+}
+
+/// Commands at the same indentation, which are either the commands of an object or of a loop or trigger.
+struct Scope {
+    /// Line index and kind of the loop or trigger, or `None` for the commands of an object.
+    group: Option<(usize, IssueKind)>,
+    commands: Vec<(usize, Command)>,
+}
+
+fn is_absolute(path: &str) -> bool {
+    let path = path.trim_matches('"');
+    let bytes = path.as_bytes();
+
+    std::path::Path::new(path).is_absolute()
+        || path.starts_with(['/', '\\'])
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+fn check_path(path: &FilePath, line_index: usize, issues: &mut Vec<Issue>) {
+    let path = path.get().to_string_lossy();
+
+    if is_absolute(&path) {
+        issues.push(Issue {
+            line_index,
+            kind: IssueKind::AbsolutePath(path.trim_matches('"').to_string()),
+        });
+    }
+}
+
+fn check_event(event: &Event, line_index: usize, issues: &mut Vec<Issue>) {
+    match event {
+        Event::StoryboardObject(object) => {
+            if let Either::Right(origin) = object.origin.type_ {
+                issues.push(Issue {
+                    line_index,
+                    kind: IssueKind::UndocumentedOrigin(origin),
+                });
+            }
+
+            match &object.object_type {
+                ObjectType::Sprite(sprite) => check_path(&sprite.filepath, line_index, issues),
+                ObjectType::Animation(animation) => {
+                    check_path(&animation.filepath, line_index, issues);
+
+                    if animation.frame_count == 0 {
+                        issues.push(Issue {
+                            line_index,
+                            kind: IssueKind::NoFrames,
+                        });
+                    }
+                }
+            }
+        }
+        Event::SpriteLegacy(sprite) => check_path(&sprite.file_name, line_index, issues),
+        Event::AnimationLegacy(animation) => check_path(&animation.file_name, line_index, issues),
+        Event::SampleLegacy(sample) => check_path(&sample.file_name, line_index, issues),
+        Event::AudioSample(sample) => check_path(&sample.filepath, line_index, issues),
+        _ => (),
+    }
+}
+
+fn check_command(command: &Command, line_index: usize, issues: &mut Vec<Issue>) {
+    if let (Some(start), Some(end)) = (command.start_time, command.end_time()) {
+        if end < start {
+            issues.push(Issue {
+                line_index,
+                kind: IssueKind::EndBeforeStart { start, end },
+            });
+        }
+    }
+
+    if let CommandProperties::Loop { loop_count: 0, .. } = command.properties {
+        issues.push(Issue {
+            line_index,
+            kind: IssueKind::LoopCountZero,
+        });
+    }
+}
+
+/// Checks a finished scope for overlapping commands and empty loops and triggers.
+fn close_scope(scope: Scope, issues: &mut Vec<Issue>) {
+    if let Some((line_index, kind)) = scope.group {
+        if scope.commands.is_empty() {
+            issues.push(Issue { line_index, kind });
+        }
+    }
+
+    let mut commands = scope
+        .commands
+        .iter()
+        .filter_map(|(line_index, command)| Some((*line_index, command, command_range(command)?)))
+        .collect::<Vec<_>>();
+    commands.sort_by_key(|(_, _, (start, _))| *start);
+
+    for (i, (line_index, command, (start, _))) in commands.iter().enumerate() {
+        let overlapping = commands[..i]
+            .iter()
+            .rfind(|(_, other, (other_start, other_end))| {
+                discriminant(&other.properties) == discriminant(&command.properties)
+                    && other_start < other_end
+                    && start < other_end
+            });
+
+        if let Some((other_line_index, _, _)) = overlapping {
+            issues.push(Issue {
+                line_index: *line_index,
+                kind: IssueKind::OverlappingCommands {
+                    other_line_index: *other_line_index,
+                },
+            });
+        }
+    }
+}
+
+fn close_scopes(scopes: &mut Vec<Scope>, depth: usize, issues: &mut Vec<Issue>) {
+    while scopes.len() > depth {
+        close_scope(scopes.pop().unwrap(), issues);
+    }
+}
+
+/// Checks the storyboard events of an .osb or .osu file, or of an events section on its own.
+/// - Variables from the `[Variables]` section are used for the events.
+/// - Lines are checked on their own, so lines that fail to parse are reported as [`IssueKind::InvalidLine`] instead of stopping the check.
+/// - Issues are sorted by line.
+pub fn validate(s: &str, version: Version) -> Vec<Issue> {
+    let has_sections = s.lines().any(|line| line.trim().starts_with('['));
+    let mut section = if has_sections { "" } else { "Events" };
+    let mut variables = Vec::new();
+    let mut scopes: Vec<Scope> = Vec::new();
+    let mut issues = Vec::new();
+
+    for (line_index, line) in s.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        if let Some(name) = trimmed
+            .strip_prefix('[')
+            .and_then(|name| name.strip_suffix(']'))
+        {
+            close_scopes(&mut scopes, 0, &mut issues);
+            section = match name {
+                "Variables" => "Variables",
+                "Events" => "Events",
+                _ => "",
+            };
+            continue;
+        }
+
+        match section {
+            "Variables" => {
+                if let Ok(Some(variable)) = Variable::from_str(line, version) {
+                    variables.push(variable);
+                }
+                continue;
+            }
+            "Events" => (),
+            _ => continue,
+        }
+
+        let tokens = VariableToken::tokenize(line, &variables);
+        let line = VariableToken::expand(&tokens, &variables).unwrap_or_else(|| line.to_string());
+        let invalid_line = |error: String| Issue {
+            line_index,
+            kind: IssueKind::InvalidLine(error),
+        };
+
+        let depth = line.chars().take_while(|c| *c == ' ' || *c == '_').count();
+        close_scopes(&mut scopes, depth, &mut issues);
+
+        if depth == 0 {
+            match Events::from_str(&line, version) {
+                Ok(Some(events)) => {
+                    for event in &events.0 {
+                        check_event(event, line_index, &mut issues);
+                    }
+                    scopes.push(Scope {
+                        group: None,
+                        commands: Vec::new(),
+                    });
+                }
+                Ok(None) => (),
+                Err(err) => issues.push(invalid_line(err.error().to_string())),
+            }
+            continue;
+        }
+
+        if scopes.len() < depth {
+            issues.push(invalid_line(format!(
+                "Invalid indentation, expected at most {}, got {depth}",
+                scopes.len()
+            )));
+            continue;
+        }
+
+        let group = match Command::from_str(&line, version) {
+            Ok(Some(command)) => {
+                check_command(&command, line_index, &mut issues);
+
+                let group = match command.properties {
+                    CommandProperties::Loop { .. } => Some(IssueKind::EmptyLoop),
+                    CommandProperties::Trigger { .. } => Some(IssueKind::EmptyTrigger),
+                    _ => None,
+                };
+                scopes
+                    .last_mut()
+                    .unwrap()
+                    .commands
+                    .push((line_index, command));
+
+                group
+            }
+            Ok(None) => None,
+            Err(ParseCommandError::InvalidTriggerType) => {
+                let trigger_type = line.split(',').nth(1).unwrap_or_default();
+                issues.push(Issue {
+                    line_index,
+                    kind: IssueKind::UnknownTriggerType(trigger_type.to_string()),
+                });
+
+                // still check the commands of the trigger
+                Some(IssueKind::EmptyTrigger)
+            }
+            Err(err) => {
+                issues.push(invalid_line(err.to_string()));
+                None
+            }
+        };
+
+        if let Some(kind) = group {
+            scopes.push(Scope {
+                group: Some((line_index, kind)),
+                commands: Vec::new(),
+            });
+        }
+    }
+
+    close_scopes(&mut scopes, 0, &mut issues);
+    issues.sort_by_key(|issue| issue.line_index);
+
+    issues
+}
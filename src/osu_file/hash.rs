@@ -0,0 +1,32 @@
+//! MD5 checksums and content hashes of beatmaps.
+
+use super::OsuFile;
+
+/// MD5 hash of the bytes as a lowercase hex string.
+/// - This is how replays, collection.db and the osu! API identify a difficulty, using the exact bytes of the .osu file.
+pub fn beatmap_md5<B: AsRef<[u8]>>(bytes: B) -> String {
+    format!("{:x}", md5::compute(bytes))
+}
+
+impl OsuFile {
+    /// MD5 hash of the file as it would be written by [`to_string`][ToString::to_string], not of the file it was parsed from.
+    /// - This isn't the hash osu! identifies the beatmap with unless the file was written by this crate, as the original bytes aren't kept.
+    /// - Use [`beatmap_md5`] on the original bytes to get the hash osu! uses.
+    pub fn serialized_md5(&self) -> String {
+        beatmap_md5(self.to_string())
+    }
+
+    /// Hash of the beatmap's content, which doesn't change if only the whitespace of the file changes.
+    /// - The file is serialized again, then each line is trimmed and empty lines are removed before hashing.
+    /// - Differences that are kept when parsing, such as `1.50` and `1.5`, still change the hash.
+    pub fn semantic_hash(&self) -> String {
+        let s = self.to_string();
+        let lines = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+
+        beatmap_md5(lines.join("\n"))
+    }
+}
@@ -0,0 +1,242 @@
+//! Paths of sliders, made from their curve points.
+
+use super::{CurveType, SlideParams};
+use crate::osu_file::Position;
+
+type Point = (f64, f64);
+
+/// Steps used for each bézier and catmull-rom segment.
+const CURVE_STEPS: usize = 50;
+/// Largest distance in osu! pixels between the approximated arc of a perfect circle and the actual arc.
+const CIRCLE_TOLERANCE: f64 = 0.1;
+
+/// Path the slider ball follows, approximated as a polyline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SliderPath {
+    points: Vec<Point>,
+    /// Distance from the start of the path to each point.
+    distances: Vec<f64>,
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+fn lerp(a: Point, b: Point, t: f64) -> Point {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+fn bezier(points: &[Point]) -> Vec<Point> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    (0..=CURVE_STEPS)
+        .map(|step| {
+            let t = step as f64 / CURVE_STEPS as f64;
+            let mut points = points.to_vec();
+            while points.len() > 1 {
+                points = points.windows(2).map(|w| lerp(w[0], w[1], t)).collect();
+            }
+            points[0]
+        })
+        .collect()
+}
+
+fn catmull(points: &[Point]) -> Vec<Point> {
+    let mut path = Vec::new();
+
+    for i in 0..points.len().saturating_sub(1) {
+        let v2 = points[i];
+        let v3 = points[i + 1];
+        let v1 = if i > 0 { points[i - 1] } else { v2 };
+        let v4 = points
+            .get(i + 2)
+            .copied()
+            .unwrap_or((2.0 * v3.0 - v2.0, 2.0 * v3.1 - v2.1));
+
+        for step in 0..CURVE_STEPS {
+            let t = step as f64 / CURVE_STEPS as f64;
+            let (t2, t3) = (t * t, t * t * t);
+            let axis = |v1: f64, v2: f64, v3: f64, v4: f64| {
+                0.5 * (2.0 * v2
+                    + (-v1 + v3) * t
+                    + (2.0 * v1 - 5.0 * v2 + 4.0 * v3 - v4) * t2
+                    + (-v1 + 3.0 * v2 - 3.0 * v3 + v4) * t3)
+            };
+            path.push((axis(v1.0, v2.0, v3.0, v4.0), axis(v1.1, v2.1, v3.1, v4.1)));
+        }
+    }
+    path.extend(points.last());
+
+    path
+}
+
+/// Arc through 3 points, or `None` if they are on a line.
+fn circle_arc(a: Point, b: Point, c: Point) -> Option<Vec<Point>> {
+    let d = 2.0 * (a.0 * (b.1 - c.1) + b.0 * (c.1 - a.1) + c.0 * (a.1 - b.1));
+    if d.abs() < 1e-3 {
+        return None;
+    }
+
+    let squared = |p: Point| p.0 * p.0 + p.1 * p.1;
+    let center = (
+        (squared(a) * (b.1 - c.1) + squared(b) * (c.1 - a.1) + squared(c) * (a.1 - b.1)) / d,
+        (squared(a) * (c.0 - b.0) + squared(b) * (a.0 - c.0) + squared(c) * (b.0 - a.0)) / d,
+    );
+    let radius = distance(a, center);
+
+    let theta_start = (a.1 - center.1).atan2(a.0 - center.0);
+    let mut theta_end = (c.1 - center.1).atan2(c.0 - center.0);
+    while theta_end < theta_start {
+        theta_end += std::f64::consts::TAU;
+    }
+    let mut direction = 1.0;
+    let mut theta_range = theta_end - theta_start;
+
+    // go the other way around if b is on the other side of the line from a to c
+    let ortho_a_to_c = (c.1 - a.1, -(c.0 - a.0));
+    if ortho_a_to_c.0 * (b.0 - a.0) + ortho_a_to_c.1 * (b.1 - a.1) < 0.0 {
+        direction = -1.0;
+        theta_range = std::f64::consts::TAU - theta_range;
+    }
+
+    let amount = if 2.0 * radius <= CIRCLE_TOLERANCE {
+        2
+    } else {
+        let step = 2.0 * (1.0 - CIRCLE_TOLERANCE / radius).acos();
+        ((theta_range / step).ceil() as usize).clamp(2, 1000)
+    };
+
+    Some(
+        (0..amount)
+            .map(|i| {
+                let theta = theta_start + direction * i as f64 / (amount - 1) as f64 * theta_range;
+                (
+                    center.0 + radius * theta.cos(),
+                    center.1 + radius * theta.sin(),
+                )
+            })
+            .collect(),
+    )
+}
+
+impl SliderPath {
+    /// Path of a slider that starts at `start`.
+    /// - The path is cut or extended in a straight line to the slider's `length`, unless the length isn't positive.
+    /// - Returns `None` if a position or the length isn't a valid decimal.
+    pub fn new(start: &Position, slider: &SlideParams) -> Option<Self> {
+        let point = |position: &Position| Some((position.x.to_f64()?, position.y.to_f64()?));
+        let mut control_points = vec![point(start)?];
+        for curve_point in &slider.curve_points {
+            control_points.push(point(&curve_point.0)?);
+        }
+        let length = slider.length.to_f64()?;
+
+        let curve_type = match slider.curve_type {
+            CurveType::PerfectCircle if control_points.len() != 3 => CurveType::Bezier,
+            curve_type => curve_type,
+        };
+
+        let points = match curve_type {
+            CurveType::Linear => control_points,
+            CurveType::Centripetal => catmull(&control_points),
+            CurveType::PerfectCircle => {
+                circle_arc(control_points[0], control_points[1], control_points[2])
+                    .unwrap_or_else(|| bezier(&control_points))
+            }
+            CurveType::Bezier => {
+                // repeated points split the curve into separate bézier segments
+                let mut points = Vec::new();
+                let mut segment_start = 0;
+                for i in 1..=control_points.len() {
+                    if i == control_points.len() || control_points[i] == control_points[i - 1] {
+                        points.extend(bezier(&control_points[segment_start..i]));
+                        segment_start = i;
+                    }
+                }
+                points
+            }
+        };
+
+        let mut path = SliderPath {
+            distances: Vec::with_capacity(points.len()),
+            points: Vec::with_capacity(points.len()),
+        };
+        for point in points {
+            let distance = match path.points.last() {
+                Some(last) => path.length() + distance(*last, point),
+                None => 0.0,
+            };
+            path.points.push(point);
+            path.distances.push(distance);
+        }
+
+        if length > 0.0 {
+            path.set_length(length);
+        }
+
+        Some(path)
+    }
+
+    fn set_length(&mut self, length: f64) {
+        while self.distances.len() > 1 && self.distances[self.distances.len() - 2] >= length {
+            self.points.pop();
+            self.distances.pop();
+        }
+
+        let count = self.points.len();
+        if count < 2 {
+            return;
+        }
+
+        let (before, last) = (self.points[count - 2], self.points[count - 1]);
+        let segment = distance(before, last);
+        if segment > 0.0 {
+            let t = (length - self.distances[count - 2]) / segment;
+            self.points[count - 1] = lerp(before, last, t);
+            self.distances[count - 1] = length;
+        }
+    }
+
+    /// Length of the path in osu! pixels.
+    pub fn length(&self) -> f64 {
+        self.distances.last().copied().unwrap_or_default()
+    }
+
+    /// Points of the polyline.
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Position at a distance along the path, which is clamped to the path.
+    pub fn position_at(&self, distance: f64) -> (f64, f64) {
+        let Some(first) = self.points.first() else {
+            return (0.0, 0.0);
+        };
+
+        let i = self.distances.partition_point(|d| *d < distance);
+        if i == 0 {
+            return *first;
+        }
+        if i >= self.points.len() {
+            return *self.points.last().unwrap();
+        }
+
+        let (start, end) = (self.distances[i - 1], self.distances[i]);
+        let t = if end > start {
+            (distance - start) / (end - start)
+        } else {
+            0.0
+        };
+        lerp(self.points[i - 1], self.points[i], t)
+    }
+}
+
+impl SlideParams {
+    /// Path of the slider, which starts at the hitobject's position.
+    /// - See [`SliderPath::new`].
+    pub fn path(&self, start: &Position) -> Option<SliderPath> {
+        SliderPath::new(start, self)
+    }
+}
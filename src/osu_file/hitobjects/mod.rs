@@ -1,3 +1,4 @@
+pub mod curve;
 pub mod error;
 pub mod types;
 
@@ -26,8 +27,10 @@ use super::VersionedDefault;
 use super::VersionedFromStr;
 use super::VersionedToString;
 use super::VersionedTryFrom;
+use super::LAZER_VERSION;
 
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct HitObjects(pub Vec<HitObject>);
 
 impl VersionedFromStr for HitObjects {
@@ -76,6 +79,7 @@ impl VersionedDefault for HitObjects {
 /// The `type` property is a `u8` integer with each bit flags containing some information, which are split into the functions and enums:
 /// [hitobject_type][Self::obj_params], [new_combo][Self::new_combo], [combo_skip_count][Self::combo_skip_count]
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub struct HitObject {
     /// The position of the hitobject.
@@ -94,6 +98,9 @@ pub struct HitObject {
     pub hitsound: HitSound,
     /// The [hitsample][HitSample] property of the hitobject.
     pub hitsample: Option<HitSample>,
+    /// `:` separated values after the filename of the [hitsample][HitSample], which osu!lazer can write.
+    /// - Only read and written for [`LAZER_VERSION`][super::LAZER_VERSION], and only written with a `hitsample`.
+    pub hitsample_extensions: Vec<String>,
 }
 
 impl HitObject {
@@ -126,6 +133,7 @@ impl HitObject {
             combo_skip_count: Default::default(),
             hitsound: Default::default(),
             hitsample: Default::default(),
+            hitsample_extensions: Vec::new(),
         }
     }
 
@@ -140,6 +148,7 @@ impl HitObject {
             combo_skip_count: Default::default(),
             hitsound: Default::default(),
             hitsample: Default::default(),
+            hitsample_extensions: Vec::new(),
         }
     }
 
@@ -157,6 +166,7 @@ impl HitObject {
             combo_skip_count: Default::default(),
             hitsound: Default::default(),
             hitsample: Default::default(),
+            hitsample_extensions: Vec::new(),
         }
     }
 }
@@ -167,6 +177,12 @@ impl VersionedFromStr for HitObject {
     type Err = ParseHitObjectError;
 
     fn from_str(s: &str, version: Version) -> std::result::Result<Option<Self>, Self::Err> {
+        let (s, hitsample_extensions) = if version == LAZER_VERSION {
+            split_hitsample_extensions(s)
+        } else {
+            (s, Vec::new())
+        };
+
         let hitsound = context(
             ParseHitObjectError::InvalidHitSound.into(),
             comma_field_versioned_type(version),
@@ -193,7 +209,7 @@ impl VersionedFromStr for HitObject {
                     context(ParseHitObjectError::InvalidY.into(), comma_field_type()),
                 ),
             ))
-            .map(|(x, y)| (Position { x, y })),
+            .map(|(x, y)| Position { x, y }),
             preceded(
                 context(ParseHitObjectError::MissingTime.into(), comma()),
                 context(ParseHitObjectError::InvalidTime.into(), comma_field_type()),
@@ -241,6 +257,7 @@ impl VersionedFromStr for HitObject {
                 combo_skip_count,
                 hitsound,
                 hitsample,
+                hitsample_extensions: Vec::new(),
             }
         } else if nth_bit_state_i64(obj_type as i64, 1) {
             // slider
@@ -366,6 +383,7 @@ impl VersionedFromStr for HitObject {
                 combo_skip_count,
                 hitsound,
                 hitsample,
+                hitsample_extensions: Vec::new(),
             }
         } else if nth_bit_state_i64(obj_type as i64, 3) {
             // spinner
@@ -397,6 +415,7 @@ impl VersionedFromStr for HitObject {
                 combo_skip_count,
                 hitsound,
                 hitsample,
+                hitsample_extensions: Vec::new(),
             }
         } else if nth_bit_state_i64(obj_type as i64, 7) {
             // osu!mania hold
@@ -443,17 +462,80 @@ impl VersionedFromStr for HitObject {
                 combo_skip_count,
                 hitsound,
                 hitsample,
+                hitsample_extensions: Vec::new(),
             }
         } else {
             return Err(ParseHitObjectError::UnknownObjType);
         };
 
-        Ok(Some(hitobject))
+        Ok(Some(HitObject {
+            hitsample_extensions,
+            ..hitobject
+        }))
+    }
+}
+
+/// Splits the values after the filename of the hitsample off of a hitobject line.
+/// - Lines without a hitsample are left as they are.
+fn split_hitsample_extensions(s: &str) -> (&str, Vec<String>) {
+    let obj_type = match s
+        .split(',')
+        .nth(3)
+        .and_then(|obj_type| obj_type.trim().parse::<Integer>().ok())
+    {
+        Some(obj_type) => obj_type as i64,
+        None => return (s, Vec::new()),
+    };
+
+    // comma fields before the hitsample, and `:` fields in the hitsample
+    // - osu!mania holds have the end time before the hitsample
+    let (fields_before, hitsample_fields) = if nth_bit_state_i64(obj_type, 0) {
+        (5, 5)
+    } else if nth_bit_state_i64(obj_type, 1) {
+        (10, 5)
+    } else if nth_bit_state_i64(obj_type, 3) {
+        (6, 5)
+    } else if nth_bit_state_i64(obj_type, 7) {
+        (5, 6)
+    } else {
+        return (s, Vec::new());
+    };
+
+    let hitsample_start = match s.match_indices(',').nth(fields_before - 1) {
+        Some((i, _)) => i + 1,
+        None => return (s, Vec::new()),
+    };
+    let hitsample = &s[hitsample_start..];
+
+    match hitsample.match_indices(':').nth(hitsample_fields - 1) {
+        Some((i, _)) => (
+            &s[..hitsample_start + i],
+            hitsample[i + 1..]
+                .split(':')
+                .map(|s| s.to_string())
+                .collect(),
+        ),
+        None => (s, Vec::new()),
     }
 }
 
 impl VersionedToString for HitObject {
     fn to_string(&self, version: Version) -> Option<String> {
+        let mut s = self.to_string_without_extensions(version)?;
+
+        if version == LAZER_VERSION && self.hitsample.is_some() {
+            for extension in &self.hitsample_extensions {
+                s.push(':');
+                s.push_str(extension);
+            }
+        }
+
+        Some(s)
+    }
+}
+
+impl HitObject {
+    fn to_string_without_extensions(&self, version: Version) -> Option<String> {
         let mut properties: Vec<String> = vec![
             self.position.x.to_string(),
             self.position.y.to_string(),
@@ -542,14 +624,9 @@ impl VersionedToString for HitObject {
                     end_time.to_string()
                 });
 
-                let hitsample = if let Some(hitsample) = &self.hitsample {
-                    if let Some(hitsample) = hitsample.to_string(version) {
-                        hitsample
-                    } else {
-                        String::new()
-                    }
-                } else {
-                    String::new()
+                let hitsample = match &self.hitsample {
+                    Some(hitsample) => hitsample.to_string(version).unwrap_or_default(),
+                    None => String::new(),
                 };
 
                 return Some(format!("{}:{hitsample}", properties.join(",")));
@@ -576,6 +653,7 @@ impl VersionedToString for HitObject {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum HitObjectParams {
     HitCircle,
@@ -585,6 +663,7 @@ pub enum HitObjectParams {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SlideParams {
     pub curve_type: CurveType,
     pub curve_points: Vec<CurvePoint>,
@@ -20,6 +20,7 @@ use crate::{
 use super::error::*;
 
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ComboSkipCount(u8);
 
 impl ComboSkipCount {
@@ -58,6 +59,7 @@ impl VersionedFrom<ComboSkipCount> for u8 {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// Sample sets used for the `edgeSounds`.
 pub struct EdgeSet {
     /// Sample set of the normal sound.
@@ -104,6 +106,7 @@ impl VersionedToString for EdgeSet {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// Anchor point used to construct the [`slider`][super::SlideParams].
 pub struct CurvePoint(pub Position);
 
@@ -139,6 +142,7 @@ impl VersionedToString for CurvePoint {
 
 /// Used for `normal_set` and `addition_set` for the `[hitobject]`[super::HitObject].
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum SampleSet {
     /// No custom sample set.
@@ -198,6 +202,7 @@ impl VersionedFromStr for SampleSet {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// Volume of the sample from `1` to `100`. If [volume][Self::volume] returns `None`, the timing point's volume will be used instead.
 pub struct Volume(Option<u8>);
 
@@ -278,6 +283,7 @@ impl VersionedFromStr for Volume {
 }
 
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// Flags that determine which sounds will play when the object is hit.
 /// # Possible sounds
 /// [`normal`][Self::normal] [`whistle`][Self::whistle] [`finish`][Self::finish] [`clap`][Self::clap]
@@ -385,6 +391,7 @@ impl VersionedFrom<u8> for HitSound {
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 /// Type of curve used to construct the [`Slider`][super::SlideParams].
 pub enum CurveType {
@@ -426,6 +433,7 @@ impl VersionedToString for CurveType {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum SampleIndex {
     TimingPointSampleIndex,
@@ -479,6 +487,7 @@ impl VersionedFromStr for SampleIndex {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// Information about which samples are played when the object is hit.
 /// It is closely related to [`hitSound`][HitSound].
 pub struct HitSample {
@@ -0,0 +1,391 @@
+//! Three-way merge of [`OsuFile`]s, for combining two sets of edits made from the same base file.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::colours::{Colour, Colours};
+use super::diff::{colour_key, time_sort_key, Section};
+use super::events::Events;
+use super::hitobjects::{HitObject, HitObjects};
+use super::osb::Osb;
+use super::timingpoints::{TimingPoint, TimingPoints};
+use super::{Decimal, OsuFile, Version, VersionedToString};
+
+/// Something that was changed on both sides in different ways.
+/// - The merged file keeps "ours" for every conflict.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Conflict {
+    /// The file format version was changed on both sides.
+    Version {
+        base: Version,
+        ours: Version,
+        theirs: Version,
+    },
+    /// A `key: value` field or a colour was changed on both sides.
+    /// - `None` means the field doesn't exist on that side.
+    Field {
+        section: Section,
+        key: String,
+        base: Option<String>,
+        ours: Option<String>,
+        theirs: Option<String>,
+    },
+    /// The hitobjects at the time were changed on both sides.
+    HitObjects {
+        time: Decimal,
+        base: Vec<HitObject>,
+        ours: Vec<HitObject>,
+        theirs: Vec<HitObject>,
+    },
+    /// The timing points at the time were changed on both sides.
+    /// - Uninherited and inherited timing points are merged separately.
+    TimingPoints {
+        time: Decimal,
+        uninherited: bool,
+        base: Vec<TimingPoint>,
+        ours: Vec<TimingPoint>,
+        theirs: Vec<TimingPoint>,
+    },
+    /// The `Events` section was changed on both sides.
+    Events {
+        base: Option<Events>,
+        ours: Option<Events>,
+        theirs: Option<Events>,
+    },
+    /// The storyboard was changed on both sides.
+    Osb {
+        base: Option<Osb>,
+        ours: Option<Osb>,
+        theirs: Option<Osb>,
+    },
+}
+
+/// Result of [`OsuFile::merge`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct MergeResult {
+    /// The merged file.
+    /// - Conflicting parts are taken from "ours".
+    pub merged: OsuFile,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl MergeResult {
+    /// Returns `true` if there were no conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Takes the side that changed, or `None` if both sides changed differently.
+fn merge_value<'a, T: PartialEq>(base: &'a T, ours: &'a T, theirs: &'a T) -> Option<&'a T> {
+    if ours == theirs || theirs == base {
+        Some(ours)
+    } else if ours == base {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+impl OsuFile {
+    /// Merges the changes made in `ours` and `theirs`, which are both edits of `base`.
+    /// - `key: value` sections and colours are merged by key.
+    /// - Hitobjects and timing points are merged by time.
+    /// - `Events` and the storyboard are merged as a whole.
+    pub fn merge(base: &OsuFile, ours: &OsuFile, theirs: &OsuFile) -> MergeResult {
+        let mut conflicts = Vec::new();
+
+        let version = match merge_value(&base.version, &ours.version, &theirs.version) {
+            Some(version) => *version,
+            None => {
+                conflicts.push(Conflict::Version {
+                    base: base.version,
+                    ours: ours.version,
+                    theirs: theirs.version,
+                });
+                ours.version
+            }
+        };
+
+        macro_rules! merge_fields {
+            ($field:ident, $section:expr, $section_type:ty) => {
+                match merge_value(&base.$field, &ours.$field, &theirs.$field) {
+                    Some(merged) => merged.clone(),
+                    None => {
+                        let fields = |osu_file: &OsuFile| {
+                            osu_file
+                                .$field
+                                .as_ref()
+                                .map(|s| s.fields(osu_file.version))
+                                .unwrap_or_else(|| <$section_type>::new().fields(osu_file.version))
+                        };
+                        let (base_fields, ours_fields, theirs_fields) =
+                            (fields(base), fields(ours), fields(theirs));
+                        let mut merged = ours.$field.clone().unwrap_or_default();
+
+                        for (((key, base), (_, ours)), (_, theirs)) in
+                            base_fields.into_iter().zip(ours_fields).zip(theirs_fields)
+                        {
+                            let conflict = match merge_value(&base, &ours, &theirs) {
+                                Some(value) => {
+                                    value != &ours
+                                        && merged.set_field(key, value.as_deref(), version).is_err()
+                                }
+                                None => true,
+                            };
+
+                            if conflict {
+                                conflicts.push(Conflict::Field {
+                                    section: $section,
+                                    key: key.to_string(),
+                                    base,
+                                    ours,
+                                    theirs,
+                                });
+                            }
+                        }
+
+                        if ours.$field.is_none() && merged == <$section_type>::new() {
+                            None
+                        } else {
+                            Some(merged)
+                        }
+                    }
+                }
+            };
+        }
+
+        let general = merge_fields!(general, Section::General, super::General);
+        let editor = merge_fields!(editor, Section::Editor, super::Editor);
+        let metadata = merge_fields!(metadata, Section::Metadata, super::Metadata);
+        let difficulty = merge_fields!(difficulty, Section::Difficulty, super::Difficulty);
+
+        let events = match merge_value(&base.events, &ours.events, &theirs.events) {
+            Some(events) => events.clone(),
+            None => {
+                conflicts.push(Conflict::Events {
+                    base: base.events.clone(),
+                    ours: ours.events.clone(),
+                    theirs: theirs.events.clone(),
+                });
+                ours.events.clone()
+            }
+        };
+
+        let osb = match merge_value(&base.osb, &ours.osb, &theirs.osb) {
+            Some(osb) => osb.clone(),
+            None => {
+                conflicts.push(Conflict::Osb {
+                    base: base.osb.clone(),
+                    ours: ours.osb.clone(),
+                    theirs: theirs.osb.clone(),
+                });
+                ours.osb.clone()
+            }
+        };
+
+        let timing_points = match merge_value(
+            &base.timing_points,
+            &ours.timing_points,
+            &theirs.timing_points,
+        ) {
+            Some(timing_points) => timing_points.clone(),
+            None => {
+                fn list(t: &Option<TimingPoints>) -> &[TimingPoint] {
+                    t.as_ref().map(|t| t.0.as_slice()).unwrap_or(&[])
+                }
+                let (merged, timing_conflicts) = merge_timed(
+                    list(&base.timing_points),
+                    list(&ours.timing_points),
+                    list(&theirs.timing_points),
+                    |t| (t.time().clone(), t.uninherited()),
+                    |t| t.time(),
+                );
+
+                conflicts.extend(timing_conflicts.into_iter().map(
+                    |((time, uninherited), base, ours, theirs)| Conflict::TimingPoints {
+                        time,
+                        uninherited,
+                        base,
+                        ours,
+                        theirs,
+                    },
+                ));
+
+                Some(TimingPoints(merged))
+            }
+        };
+
+        let colours =
+            match merge_value(&base.colours, &ours.colours, &theirs.colours) {
+                Some(colours) => colours.clone(),
+                None => {
+                    let list =
+                        |c: &Option<Colours>| c.as_ref().map(|c| c.0.clone()).unwrap_or_default();
+                    let (merged, colour_conflicts) = merge_colours(
+                        list(&base.colours),
+                        list(&ours.colours),
+                        list(&theirs.colours),
+                    );
+
+                    conflicts.extend(colour_conflicts.into_iter().map(
+                        |(key, base, ours, theirs)| {
+                            let to_string =
+                                |c: Option<Colour>| c.and_then(|c| c.to_string(version));
+
+                            Conflict::Field {
+                                section: Section::Colours,
+                                key,
+                                base: to_string(base),
+                                ours: to_string(ours),
+                                theirs: to_string(theirs),
+                            }
+                        },
+                    ));
+
+                    Some(Colours(merged))
+                }
+            };
+
+        let hitobjects = match merge_value(&base.hitobjects, &ours.hitobjects, &theirs.hitobjects) {
+            Some(hitobjects) => hitobjects.clone(),
+            None => {
+                fn list(h: &Option<HitObjects>) -> &[HitObject] {
+                    h.as_ref().map(|h| h.0.as_slice()).unwrap_or(&[])
+                }
+                let (merged, hitobject_conflicts) = merge_timed(
+                    list(&base.hitobjects),
+                    list(&ours.hitobjects),
+                    list(&theirs.hitobjects),
+                    |h| h.time.clone(),
+                    |h| &h.time,
+                );
+
+                conflicts.extend(hitobject_conflicts.into_iter().map(
+                    |(time, base, ours, theirs)| Conflict::HitObjects {
+                        time,
+                        base,
+                        ours,
+                        theirs,
+                    },
+                ));
+
+                Some(HitObjects(merged))
+            }
+        };
+
+        MergeResult {
+            merged: OsuFile {
+                version,
+                general,
+                editor,
+                osb,
+                metadata,
+                difficulty,
+                events,
+                timing_points,
+                colours,
+                hitobjects,
+            },
+            conflicts,
+        }
+    }
+}
+
+type TimedConflict<K, T> = (K, Vec<T>, Vec<T>, Vec<T>);
+
+/// Merges items grouped by `key`, where a group is taken from the side that changed it.
+/// - The result is sorted by time, keeping the order of "ours" for items with the same time.
+fn merge_timed<T, K, FK, FT>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+    key: FK,
+    time: FT,
+) -> (Vec<T>, Vec<TimedConflict<K, T>>)
+where
+    T: Clone + PartialEq,
+    K: Hash + Eq + Clone,
+    FK: Fn(&T) -> K,
+    FT: Fn(&T) -> &Decimal,
+{
+    // keys in the order they are first seen, "ours" first
+    let mut keys = Vec::new();
+    let mut groups: HashMap<K, [Vec<T>; 3]> = HashMap::new();
+
+    for (side, items) in [ours, theirs, base].into_iter().enumerate() {
+        for item in items {
+            let key = key(item);
+            let group = groups.entry(key.clone()).or_insert_with(|| {
+                keys.push(key);
+                Default::default()
+            });
+            group[side].push(item.clone());
+        }
+    }
+
+    let mut merged = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for key in keys {
+        let [ours, theirs, base] = groups.remove(&key).unwrap();
+
+        match merge_value(&base, &ours, &theirs) {
+            Some(group) => merged.extend(group.iter().cloned()),
+            None => {
+                merged.extend(ours.iter().cloned());
+                conflicts.push((key, base, ours, theirs));
+            }
+        }
+    }
+
+    merged.sort_by_key(|item| time_sort_key(time(item)));
+
+    (merged, conflicts)
+}
+
+type ColourConflict = (String, Option<Colour>, Option<Colour>, Option<Colour>);
+
+fn merge_colours(
+    base: Vec<Colour>,
+    ours: Vec<Colour>,
+    theirs: Vec<Colour>,
+) -> (Vec<Colour>, Vec<ColourConflict>) {
+    let to_map = |colours: &[Colour]| {
+        colours
+            .iter()
+            .map(|c| (colour_key(c), *c))
+            .collect::<HashMap<_, _>>()
+    };
+    let (base_map, ours_map, theirs_map) = (to_map(&base), to_map(&ours), to_map(&theirs));
+
+    let mut keys = ours.iter().map(colour_key).collect::<Vec<_>>();
+    for colour in theirs.iter().chain(base.iter()) {
+        let key = colour_key(colour);
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    let mut merged = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for key in keys {
+        let (base, ours, theirs) = (
+            base_map.get(&key).copied(),
+            ours_map.get(&key).copied(),
+            theirs_map.get(&key).copied(),
+        );
+
+        match merge_value(&base, &ours, &theirs) {
+            Some(colour) => merged.extend(*colour),
+            None => {
+                merged.extend(ours);
+                conflicts.push((key, base, ours, theirs));
+            }
+        }
+    }
+
+    (merged, conflicts)
+}
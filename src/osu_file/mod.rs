@@ -1,12 +1,28 @@
+pub mod assets;
+pub mod beatmapset;
+#[cfg(feature = "replay")]
+pub mod binary;
 pub mod colours;
+pub mod convert;
+#[cfg(feature = "db")]
+pub mod db;
 pub mod difficulty;
+pub mod diff;
 pub mod editor;
 pub mod events;
 pub mod general;
+pub mod hash;
 pub mod hitobjects;
+pub mod merge;
 pub mod metadata;
 pub mod osb;
+#[cfg(feature = "osz")]
+pub mod osz;
+#[cfg(feature = "replay")]
+pub mod replay;
+pub mod skin;
 pub mod timingpoints;
+pub mod transform;
 pub mod types;
 
 use std::fmt::{Debug, Display};
@@ -37,6 +53,7 @@ pub use types::*;
 
 /// An .osu file represented as a struct.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub struct OsuFile {
     /// Version of the file format.
@@ -199,7 +216,7 @@ impl FromStr for OsuFile {
             }
         };
 
-        if !(MIN_VERSION..=LATEST_VERSION).contains(&version) && version != 128 {
+        if !(MIN_VERSION..=LATEST_VERSION).contains(&version) && version != LAZER_VERSION {
             return Err(ParseError::InvalidFileVersion.into());
         }
 
@@ -325,7 +342,7 @@ impl FromStr for OsuFile {
 pub enum ParseError {
     /// File version is invalid.
     #[error(
-        "Invalid file version, expected versions from {MIN_VERSION} ~ {LATEST_VERSION} or v128"
+        "Invalid file version, expected versions from {MIN_VERSION} ~ {LATEST_VERSION} or v{LAZER_VERSION}"
     )]
     InvalidFileVersion,
     /// File version is defined wrong.
@@ -11,6 +11,7 @@ pub use error::*;
 pub use types::*;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Osb {
     pub variables: Option<Vec<Variable>>,
     pub events: Option<Events>,
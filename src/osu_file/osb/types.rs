@@ -5,10 +5,13 @@ use nom::{
     sequence::{preceded, tuple},
 };
 
+use std::fmt::Display;
+
 use super::*;
 use crate::osu_file::VersionedFromStr;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Variable {
     pub name: String,
     pub value: String,
@@ -46,3 +49,126 @@ impl VersionedToString for Variable {
         Some(format!("${}={}", self.name, self.value))
     }
 }
+
+/// Part of a storyboard line written with `[Variables]`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum VariableToken {
+    /// Text written as is.
+    Text(String),
+    /// Name of a variable used in place of its value, without the `$`.
+    Variable(String),
+}
+
+impl Display for VariableToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VariableToken::Text(text) => write!(f, "{text}"),
+            VariableToken::Variable(name) => write!(f, "${name}"),
+        }
+    }
+}
+
+impl VariableToken {
+    /// Splits `s` into text and the variables used in it.
+    /// - A `$` is matched against the longest variable name, so `$ab` isn't read as `$a` followed by `b`.
+    /// - A `$` that doesn't start any variable name is kept as text.
+    pub fn tokenize(s: &str, variables: &[Variable]) -> Vec<VariableToken> {
+        let mut tokens = Vec::new();
+        let mut text = String::new();
+        let mut rest = s;
+
+        while let Some(i) = rest.find('$') {
+            text.push_str(&rest[..i]);
+            rest = &rest[i + 1..];
+
+            let name = variables
+                .iter()
+                .map(|variable| variable.name.as_str())
+                .filter(|name| !name.is_empty() && rest.starts_with(name))
+                .max_by_key(|name| name.len());
+
+            match name {
+                Some(name) => {
+                    if !text.is_empty() {
+                        tokens.push(VariableToken::Text(std::mem::take(&mut text)));
+                    }
+                    tokens.push(VariableToken::Variable(name.to_string()));
+                    rest = &rest[name.len()..];
+                }
+                None => text.push('$'),
+            }
+        }
+
+        text.push_str(rest);
+        if !text.is_empty() {
+            tokens.push(VariableToken::Text(text));
+        }
+
+        tokens
+    }
+
+    /// Replaces the variables with their values.
+    /// - If a variable is defined more than once, the last definition is used.
+    /// - Returns `None` if a variable isn't defined.
+    pub fn expand(tokens: &[VariableToken], variables: &[Variable]) -> Option<String> {
+        tokens.iter().try_fold(String::new(), |mut s, token| {
+            match token {
+                VariableToken::Text(text) => s.push_str(text),
+                VariableToken::Variable(name) => {
+                    s.push_str(&variables.iter().rfind(|v| &v.name == name)?.value)
+                }
+            }
+
+            Some(s)
+        })
+    }
+
+    /// Replaces whole comma separated fields of `s` with variables that have the same value.
+    /// - Variables with more fields in their value are used first, so numbers like `1320` are never split.
+    /// - Variables defined later take priority over earlier ones with the same value.
+    pub fn substitute(s: &str, variables: &[Variable]) -> Vec<VariableToken> {
+        let mut candidates = variables
+            .iter()
+            .rev()
+            .filter(|v| !v.name.is_empty() && !v.value.is_empty())
+            .map(|v| (v.name.as_str(), v.value.split(',').collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|(_, value)| std::cmp::Reverse(value.len()));
+
+        let fields = s.split(',').collect::<Vec<_>>();
+        let mut tokens = Vec::new();
+        let mut text = String::new();
+        let mut i = 0;
+
+        while i < fields.len() {
+            if i > 0 {
+                text.push(',');
+            }
+
+            let variable = candidates
+                .iter()
+                .find(|(_, value)| fields[i..].starts_with(value));
+
+            match variable {
+                Some((name, value)) => {
+                    if !text.is_empty() {
+                        tokens.push(VariableToken::Text(std::mem::take(&mut text)));
+                    }
+                    tokens.push(VariableToken::Variable(name.to_string()));
+                    i += value.len();
+                }
+                None => {
+                    text.push_str(fields[i]);
+                    i += 1;
+                }
+            }
+        }
+
+        if !text.is_empty() {
+            tokens.push(VariableToken::Text(text));
+        }
+
+        tokens
+    }
+}
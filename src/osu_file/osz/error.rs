@@ -0,0 +1,26 @@
+use thiserror::Error;
+
+use crate::osu_file::{self, osb};
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum OszError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
+    #[error("The file {0} isn't valid UTF-8")]
+    InvalidUtf8(String),
+    #[error("Failed to parse the beatmap {name}")]
+    ParseOsuFile {
+        name: String,
+        #[source]
+        source: osu_file::Error<osu_file::ParseError>,
+    },
+    #[error("Failed to parse the storyboard {name}")]
+    ParseOsb {
+        name: String,
+        #[source]
+        source: osu_file::Error<osb::ParseError>,
+    },
+}
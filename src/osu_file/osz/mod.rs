@@ -0,0 +1,173 @@
+//! Reading and writing of .osz beatmap archives.
+//! - .olz archives exported by osu!lazer are the same format, with beatmaps in the [`LAZER_VERSION`][super::LAZER_VERSION] format.
+
+pub mod error;
+
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Seek, Write};
+use std::path::Path;
+
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use super::beatmapset::default_osb_name;
+use super::convert::{ConversionReport, ConvertError};
+use super::{OsuFile, Version};
+
+pub use error::*;
+
+/// A beatmap archive, which is a zip file of a mapset folder.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Osz {
+    /// Beatmaps by their file name in the archive.
+    /// - The shared storyboard is in [`OsuFile::osb`] of each beatmap.
+    pub beatmaps: BTreeMap<String, OsuFile>,
+    /// File name of the shared .osb storyboard.
+    /// - If `None` when writing, the name is made from the metadata like osu! does.
+    pub osb_name: Option<String>,
+    /// Every other file by its path in the archive, such as audio and images.
+    pub assets: BTreeMap<String, Vec<u8>>,
+}
+
+fn has_extension(name: &str, extension: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case(extension))
+}
+
+impl Osz {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// New archive of beatmaps and assets.
+    pub fn from_osu_files<B, A>(beatmaps: B, assets: A) -> Self
+    where
+        B: IntoIterator<Item = (String, OsuFile)>,
+        A: IntoIterator<Item = (String, Vec<u8>)>,
+    {
+        Self {
+            beatmaps: beatmaps.into_iter().collect(),
+            osb_name: None,
+            assets: assets.into_iter().collect(),
+        }
+    }
+
+    /// Reads an archive, parsing every .osu file and attaching the .osb storyboard to each of them.
+    pub fn from_reader<R: Read + Seek>(reader: R) -> Result<Self, OszError> {
+        let mut archive = ZipArchive::new(reader)?;
+        let mut osz = Osz::new();
+        let mut osb = None;
+
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            if file.is_dir() {
+                continue;
+            }
+
+            let name = file.name()?.to_string();
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)?;
+
+            let text = |bytes: Vec<u8>| {
+                String::from_utf8(bytes).map_err(|_| OszError::InvalidUtf8(name.clone()))
+            };
+
+            if has_extension(&name, "osu") {
+                let osu_file =
+                    text(bytes)?
+                        .parse::<OsuFile>()
+                        .map_err(|source| OszError::ParseOsuFile {
+                            name: name.clone(),
+                            source,
+                        })?;
+                osz.beatmaps.insert(name, osu_file);
+            } else if has_extension(&name, "osb") && osb.is_none() {
+                osb = Some((name.clone(), text(bytes)?));
+            } else {
+                osz.assets.insert(name, bytes);
+            }
+        }
+
+        if let Some((name, osb)) = osb {
+            for osu_file in osz.beatmaps.values_mut() {
+                osu_file
+                    .append_osb(&osb)
+                    .map_err(|source| OszError::ParseOsb {
+                        name: name.clone(),
+                        source,
+                    })?;
+            }
+            osz.osb_name = Some(name);
+        }
+
+        Ok(osz)
+    }
+
+    /// Reads an archive from its bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, OszError> {
+        Self::from_reader(Cursor::new(bytes))
+    }
+
+    /// Reads an archive from a file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, OszError> {
+        Self::from_reader(std::fs::File::open(path)?)
+    }
+
+    /// File name of the .osb storyboard, made from the metadata of a beatmap if [`Osz::osb_name`] is `None`.
+    /// - Format is `Artist - Title (Creator).osb`.
+    pub fn osb_file_name(&self) -> String {
+        match &self.osb_name {
+            Some(name) => name.clone(),
+            None => default_osb_name(self.beatmaps.values()),
+        }
+    }
+
+    /// Writes the archive.
+    /// - The storyboard is taken from the first beatmap with [`OsuFile::osb`].
+    pub fn write<W: Write + Seek>(&self, writer: W) -> Result<W, OszError> {
+        let mut zip = ZipWriter::new(writer);
+        let options = SimpleFileOptions::default();
+
+        for (name, osu_file) in &self.beatmaps {
+            zip.start_file(name.as_str(), options)?;
+            zip.write_all(osu_file.to_string().as_bytes())?;
+        }
+
+        if let Some(osb) = self.beatmaps.values().find_map(|o| o.osb_to_string()) {
+            zip.start_file(self.osb_file_name(), options)?;
+            zip.write_all(osb.as_bytes())?;
+        }
+
+        for (name, bytes) in &self.assets {
+            zip.start_file(name.as_str(), options)?;
+            zip.write_all(bytes)?;
+        }
+
+        Ok(zip.finish()?)
+    }
+
+    /// Writes the archive into bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, OszError> {
+        Ok(self.write(Cursor::new(Vec::new()))?.into_inner())
+    }
+
+    /// Writes the archive to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), OszError> {
+        self.write(std::fs::File::create(path)?)?;
+        Ok(())
+    }
+
+    /// Converts every beatmap to another file format version with [`OsuFile::convert_to`].
+    /// - Used to make a .olz archive readable by stable, by converting to [`LATEST_VERSION`][super::LATEST_VERSION].
+    /// - Returns the report of each beatmap by its file name.
+    pub fn convert_to(
+        &mut self,
+        version: Version,
+    ) -> Result<BTreeMap<String, ConversionReport>, ConvertError> {
+        self.beatmaps
+            .iter_mut()
+            .map(|(name, osu_file)| Ok((name.clone(), osu_file.convert_to(version)?)))
+            .collect()
+    }
+}
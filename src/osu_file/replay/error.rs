@@ -0,0 +1,26 @@
+use thiserror::Error;
+
+use crate::osu_file::binary::BinaryError;
+use crate::osu_file::general::Mode;
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ReplayError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Binary(#[from] BinaryError),
+    #[error("Failed to decompress the replay data")]
+    Lzma(#[from] lzma_rs::error::Error),
+    #[error("Unknown game mode {0}")]
+    UnknownMode(u8),
+    /// Only osu!standard replays can be judged.
+    #[error("Judging {0:?} replays isn't supported")]
+    UnsupportedMode(Mode),
+    #[error("The replay data isn't valid UTF-8")]
+    InvalidUtf8,
+    #[error("Invalid replay frame `{0}`")]
+    InvalidFrame(String),
+    #[error("Invalid life bar point `{0}`")]
+    InvalidLifeBar(String),
+}
@@ -0,0 +1,749 @@
+//! Recomputing the judgements of an osu!standard replay from its beatmap.
+//!
+//! This follows how osu! judges objects closely enough to find replays that don't match their beatmap, but isn't exact.
+//! - Presses can only hit the earliest object that hasn't been judged yet.
+//! - Presses too early for a 50 but less than 400ms early are a miss.
+//! - Slider ticks, repeats and ends are hit if a button is held with the cursor in the follow circle.
+//! - Stacking uses the algorithm of beatmap version 6 and later for every version.
+//! - Relax and Autopilot aren't simulated.
+
+use either::Either;
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::osu_file::general::Mode;
+use crate::osu_file::hitobjects::curve::SliderPath;
+use crate::osu_file::hitobjects::HitObjectParams;
+use crate::osu_file::transform::{active_point, slider_velocity_timing, uninherited_timing};
+use crate::osu_file::{Decimal, OsuFile};
+
+use super::{Keys, Mods, Replay, ReplayError};
+
+type Point = (f64, f64);
+
+const PLAYFIELD_HEIGHT: f64 = 384.0;
+const SPINNER_CENTRE: Point = (256.0, 192.0);
+/// Presses earlier than this before an object are ignored.
+const MISS_WINDOW: f64 = 400.0;
+/// The slider end is checked this many milliseconds before the end of the slider.
+const SLIDER_END_LENIENCY: f64 = 36.0;
+/// Radius of the follow circle compared to the circle radius.
+const FOLLOW_CIRCLE_SCALE: f64 = 2.4;
+/// Slider ticks closer than this many milliseconds to the end of a span are skipped.
+const TICK_END_DISTANCE: f64 = 10.0;
+const STACK_DISTANCE: f64 = 3.0;
+/// Fastest counted spinning speed in radians per millisecond, which is about 477 rotations per minute.
+const MAX_SPIN_RATE: f64 = 0.05;
+
+/// Result of an object.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Judgement {
+    Hit300,
+    Hit100,
+    Hit50,
+    Miss,
+}
+
+impl Judgement {
+    /// Score value used for accuracy.
+    pub fn value(&self) -> u32 {
+        match self {
+            Judgement::Hit300 => 300,
+            Judgement::Hit100 => 100,
+            Judgement::Hit50 => 50,
+            Judgement::Miss => 0,
+        }
+    }
+}
+
+/// Judgement of a single hitobject.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectJudgement {
+    /// Index of the object in [`HitObjects`][crate::osu_file::HitObjects].
+    pub index: usize,
+    pub judgement: Judgement,
+    /// Milliseconds the circle or slider head was pressed after its time, which is negative if it was pressed early.
+    /// - `None` if it wasn't pressed.
+    pub offset: Option<f64>,
+    /// Number of hit slider parts out of all parts, which are the head, ticks, repeats and the end.
+    pub slider_parts: Option<(usize, usize)>,
+    /// Number of full rotations of a spinner.
+    pub spins: Option<f64>,
+}
+
+/// A count from the replay header.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CountField {
+    Count300,
+    Count100,
+    Count50,
+    CountGeki,
+    CountKatu,
+    CountMiss,
+    MaxCombo,
+}
+
+/// A count that is different between the replay header and the simulation.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct CountMismatch {
+    pub field: CountField,
+    /// Count in the replay header.
+    pub replay: u16,
+    /// Count from [`Replay::judge`].
+    pub simulated: u16,
+}
+
+/// Judgements recomputed by [`Replay::judge`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JudgementReport {
+    /// Judgement of every hitobject, in the same order as the hitobjects.
+    pub objects: Vec<ObjectJudgement>,
+    pub count_300: u16,
+    pub count_100: u16,
+    pub count_50: u16,
+    /// Combos that were only 300s.
+    pub count_geki: u16,
+    /// Combos without 50s and misses that weren't only 300s.
+    pub count_katu: u16,
+    pub count_miss: u16,
+    pub max_combo: u16,
+    /// Highest combo possible on the beatmap.
+    pub full_combo: u16,
+}
+
+impl JudgementReport {
+    /// Accuracy from `0` to `1`, or `1` if there are no objects.
+    pub fn accuracy(&self) -> f64 {
+        let total = self.count_300 as u32
+            + self.count_100 as u32
+            + self.count_50 as u32
+            + self.count_miss as u32;
+        if total == 0 {
+            return 1.0;
+        }
+
+        let value =
+            self.count_300 as u32 * 300 + self.count_100 as u32 * 100 + self.count_50 as u32 * 50;
+        value as f64 / (total * 300) as f64
+    }
+
+    /// Compares the counts and max combo with the replay header.
+    pub fn compare(&self, replay: &Replay) -> Vec<CountMismatch> {
+        [
+            (CountField::Count300, replay.count_300, self.count_300),
+            (CountField::Count100, replay.count_100, self.count_100),
+            (CountField::Count50, replay.count_50, self.count_50),
+            (CountField::CountGeki, replay.count_geki, self.count_geki),
+            (CountField::CountKatu, replay.count_katu, self.count_katu),
+            (CountField::CountMiss, replay.count_miss, self.count_miss),
+            (CountField::MaxCombo, replay.max_combo, self.max_combo),
+        ]
+        .into_iter()
+        .filter(|(_, replay, simulated)| replay != simulated)
+        .map(|(field, replay, simulated)| CountMismatch {
+            field,
+            replay,
+            simulated,
+        })
+        .collect()
+    }
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+/// Scales a value between the values at difficulty 0, 5 and 10.
+fn difficulty_range(difficulty: f64, min: f64, mid: f64, max: f64) -> f64 {
+    if difficulty > 5.0 {
+        mid + (max - mid) * (difficulty - 5.0) / 5.0
+    } else {
+        mid - (mid - min) * (5.0 - difficulty) / 5.0
+    }
+}
+
+struct Settings {
+    radius: f64,
+    /// Hit windows of 300s, 100s and 50s.
+    windows: [f64; 3],
+    od: f64,
+    /// Time objects appear before their time.
+    preempt: f64,
+    stack_leniency: f64,
+    hard_rock: bool,
+}
+
+impl Settings {
+    fn new(osu_file: &OsuFile, mods: Mods) -> Self {
+        let difficulty = osu_file.difficulty.as_ref();
+        let value =
+            |value: Option<&Decimal>, default: f64| value.and_then(Decimal::to_f64).unwrap_or(default);
+
+        let mut cs = value(
+            difficulty
+                .and_then(|d| d.circle_size.as_ref())
+                .map(|v| &v.0),
+            5.0,
+        );
+        let mut od = value(
+            difficulty
+                .and_then(|d| d.overall_difficulty.as_ref())
+                .map(|v| &v.0),
+            5.0,
+        );
+        // old beatmaps use OD for AR
+        let mut ar = value(
+            difficulty
+                .and_then(|d| d.approach_rate.as_ref())
+                .map(|v| &v.0),
+            od,
+        );
+
+        let hard_rock = mods.contains(Mods::HARD_ROCK);
+        if hard_rock {
+            cs = (cs * 1.3).min(10.0);
+            od = (od * 1.4).min(10.0);
+            ar = (ar * 1.4).min(10.0);
+        }
+        if mods.contains(Mods::EASY) {
+            cs *= 0.5;
+            od *= 0.5;
+            ar *= 0.5;
+        }
+
+        let stack_leniency = value(
+            osu_file
+                .general
+                .as_ref()
+                .and_then(|g| g.stack_leniency.as_ref())
+                .map(|v| &v.0),
+            0.7,
+        );
+
+        Settings {
+            radius: 64.0 * (1.0 - 0.7 * (cs - 5.0) / 5.0) / 2.0,
+            windows: [
+                difficulty_range(od, 80.0, 50.0, 20.0),
+                difficulty_range(od, 140.0, 100.0, 60.0),
+                difficulty_range(od, 200.0, 150.0, 100.0),
+            ],
+            od,
+            preempt: difficulty_range(ar, 1800.0, 1200.0, 450.0),
+            stack_leniency,
+            hard_rock,
+        }
+    }
+
+    fn judge_offset(&self, offset: f64) -> Judgement {
+        match offset.abs() {
+            offset if offset <= self.windows[0] => Judgement::Hit300,
+            offset if offset <= self.windows[1] => Judgement::Hit100,
+            offset if offset <= self.windows[2] => Judgement::Hit50,
+            _ => Judgement::Miss,
+        }
+    }
+}
+
+struct Slider {
+    path: SliderPath,
+    span_duration: f64,
+    spans: u32,
+    tick_distance: f64,
+}
+
+enum Kind {
+    Circle,
+    Slider(Slider),
+    Spinner,
+}
+
+struct Target {
+    index: usize,
+    time: f64,
+    end_time: f64,
+    /// Position without stacking.
+    position: Point,
+    end_position: Point,
+    new_combo: bool,
+    stack_height: i32,
+    kind: Kind,
+}
+
+impl Slider {
+    /// Distance of the ball along the path at `time` after the start of the slider.
+    fn ball_distance(&self, time: f64) -> f64 {
+        let progress = (time / self.span_duration).clamp(0.0, self.spans as f64);
+        let span = (progress.floor() as u32).min(self.spans - 1);
+        let within = progress - span as f64;
+        let within = if span % 2 == 1 { 1.0 - within } else { within };
+
+        within * self.path.length()
+    }
+
+    /// Times after the start and path distances of the ticks and repeats.
+    fn checkpoints(&self) -> Vec<(f64, f64)> {
+        let length = self.path.length();
+        let velocity = length / self.span_duration;
+        let mut checkpoints = Vec::new();
+
+        for span in 0..self.spans {
+            let span_start = span as f64 * self.span_duration;
+            let reversed = span % 2 == 1;
+
+            if self.tick_distance > 0.0 {
+                let mut distance = self.tick_distance;
+                while distance < length - velocity * TICK_END_DISTANCE {
+                    let time = if reversed {
+                        length - distance
+                    } else {
+                        distance
+                    } / velocity;
+                    checkpoints.push((span_start + time, distance));
+                    distance += self.tick_distance;
+                }
+            }
+
+            if span + 1 < self.spans {
+                let end = if reversed { 0.0 } else { length };
+                checkpoints.push((span_start + self.span_duration, end));
+            }
+        }
+
+        checkpoints.sort_by(|a, b| a.0.total_cmp(&b.0));
+        checkpoints
+    }
+}
+
+fn targets(osu_file: &OsuFile) -> Vec<Target> {
+    let Some(hitobjects) = &osu_file.hitobjects else {
+        return Vec::new();
+    };
+
+    let uninherited = uninherited_timing(&osu_file.timing_points);
+    let velocities = slider_velocity_timing(&osu_file.timing_points);
+    let difficulty = osu_file.difficulty.as_ref();
+    let slider_multiplier = difficulty
+        .and_then(|d| d.slider_multiplier.as_ref())
+        .and_then(|m| m.0.to_f64())
+        .unwrap_or(1.4);
+    let tick_rate = difficulty
+        .and_then(|d| d.slider_tickrate.as_ref())
+        .and_then(|t| t.0.to_f64())
+        .unwrap_or(1.0);
+
+    let mut targets = Vec::new();
+    for (index, hitobject) in hitobjects.0.iter().enumerate() {
+        let (Some(time), Some(x), Some(y)) = (
+            hitobject.time.to_f64(),
+            hitobject.position.x.to_f64(),
+            hitobject.position.y.to_f64(),
+        ) else {
+            continue;
+        };
+
+        let slider = match &hitobject.obj_params {
+            HitObjectParams::Slider(slider) => {
+                let decimal_time = match hitobject.time.get() {
+                    Either::Left(time) => Some(*time),
+                    Either::Right(_) => None,
+                };
+                let beat_length = decimal_time
+                    .and_then(|t| active_point(&uninherited, t))
+                    .and_then(|(_, beat_length)| beat_length.to_f64())
+                    .filter(|beat_length| *beat_length > 0.0);
+                let multiplier = decimal_time
+                    .and_then(|t| active_point(&velocities, t))
+                    .and_then(|(_, multiplier)| multiplier.to_f64())
+                    .unwrap_or(1.0);
+                let path = slider
+                    .path(&hitobject.position)
+                    .filter(|p| p.length() > 0.0);
+
+                match (beat_length, path) {
+                    (Some(beat_length), Some(path)) => {
+                        let beat_distance = slider_multiplier * 100.0 * multiplier;
+                        // old beatmaps don't have slider velocity affect the tick distance
+                        let tick_distance = if osu_file.version < 8 {
+                            slider_multiplier * 100.0 / tick_rate
+                        } else {
+                            beat_distance / tick_rate
+                        };
+
+                        Some(Slider {
+                            span_duration: path.length() / beat_distance * beat_length,
+                            path,
+                            spans: slider.slides.max(1) as u32,
+                            tick_distance,
+                        })
+                    }
+                    // sliders without a valid path or timing are judged as circles
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        let (kind, end_time, end_position) = match (&hitobject.obj_params, slider) {
+            (HitObjectParams::Spinner { end_time }, _) => (
+                Kind::Spinner,
+                end_time.to_f64().unwrap_or(time).max(time),
+                (x, y),
+            ),
+            (_, Some(slider)) => {
+                let end_time = time + slider.span_duration * slider.spans as f64;
+                let end_position = slider
+                    .path
+                    .position_at(slider.ball_distance(end_time - time));
+                (Kind::Slider(slider), end_time, end_position)
+            }
+            _ => (Kind::Circle, time, (x, y)),
+        };
+
+        targets.push(Target {
+            index,
+            time,
+            end_time,
+            position: (x, y),
+            end_position,
+            new_combo: hitobject.new_combo,
+            stack_height: 0,
+            kind,
+        });
+    }
+
+    targets
+}
+
+/// Sets the stack heights, going backwards from the last object.
+fn apply_stacking(targets: &mut [Target], settings: &Settings) {
+    let threshold = settings.preempt * settings.stack_leniency;
+
+    for i in (1..targets.len()).rev() {
+        if targets[i].stack_height != 0 || matches!(targets[i].kind, Kind::Spinner) {
+            continue;
+        }
+
+        let mut current = i;
+        if matches!(targets[i].kind, Kind::Circle) {
+            for n in (0..i).rev() {
+                if matches!(targets[n].kind, Kind::Spinner) {
+                    continue;
+                }
+                if targets[current].time - targets[n].end_time > threshold {
+                    break;
+                }
+
+                // circles stacked on the end of a slider go the other way
+                if matches!(targets[n].kind, Kind::Slider(_))
+                    && distance(targets[n].end_position, targets[current].position) < STACK_DISTANCE
+                {
+                    let offset = targets[current].stack_height - targets[n].stack_height + 1;
+                    for j in n + 1..=i {
+                        if distance(targets[n].end_position, targets[j].position) < STACK_DISTANCE {
+                            targets[j].stack_height -= offset;
+                        }
+                    }
+                    break;
+                }
+
+                if distance(targets[n].position, targets[current].position) < STACK_DISTANCE {
+                    targets[n].stack_height = targets[current].stack_height + 1;
+                    current = n;
+                }
+            }
+        } else {
+            for n in (0..i).rev() {
+                if matches!(targets[n].kind, Kind::Spinner) {
+                    continue;
+                }
+                if targets[current].time - targets[n].time > threshold {
+                    break;
+                }
+
+                if distance(targets[n].end_position, targets[current].position) < STACK_DISTANCE {
+                    targets[n].stack_height = targets[current].stack_height + 1;
+                    current = n;
+                }
+            }
+        }
+    }
+}
+
+/// Cursor position and held buttons over time.
+struct Input {
+    /// Time, position and held mouse buttons of each frame, sorted by time.
+    frames: Vec<(f64, Point, u32)>,
+}
+
+impl Input {
+    fn new(replay: &Replay) -> Self {
+        let mut frames = replay
+            .timed_frames()
+            .map(|(time, frame)| {
+                // keyboard keys also hold their mouse button
+                let keys = frame.keys.0;
+                let buttons = (keys | keys >> 2) & (Keys::M1 | Keys::M2).0;
+                (time as f64, (frame.x as f64, frame.y as f64), buttons)
+            })
+            .collect::<Vec<_>>();
+        frames.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Input { frames }
+    }
+
+    /// Position and held buttons of the last frame at or before the time.
+    fn state_at(&self, time: f64) -> Option<(Point, u32)> {
+        let i = self.frames.partition_point(|(t, _, _)| *t <= time);
+        let (_, position, buttons) = self.frames.get(i.checked_sub(1)?)?;
+        Some((*position, *buttons))
+    }
+
+    /// Time and position of every button press.
+    fn presses(&self) -> Vec<(f64, Point)> {
+        let mut presses = Vec::new();
+        let mut held = 0;
+
+        for (time, position, buttons) in &self.frames {
+            let pressed = buttons & !held;
+            for _ in 0..pressed.count_ones() {
+                presses.push((*time, *position));
+            }
+            held = *buttons;
+        }
+
+        presses
+    }
+
+    /// Full rotations around the spinner centre while a button is held.
+    fn spins(&self, start: f64, end: f64) -> f64 {
+        let mut rotation = 0.0;
+        let mut previous: Option<(f64, f64)> = None;
+
+        for (time, position, buttons) in &self.frames {
+            if *time < start || *time > end || *buttons == 0 {
+                previous = None;
+                continue;
+            }
+
+            let angle = (position.1 - SPINNER_CENTRE.1).atan2(position.0 - SPINNER_CENTRE.0);
+            if let Some((previous_time, previous_angle)) = previous {
+                let mut delta = angle - previous_angle;
+                if delta > std::f64::consts::PI {
+                    delta -= std::f64::consts::TAU;
+                } else if delta < -std::f64::consts::PI {
+                    delta += std::f64::consts::TAU;
+                }
+                rotation += delta.abs().min(MAX_SPIN_RATE * (time - previous_time));
+            }
+            previous = Some((*time, angle));
+        }
+
+        rotation / std::f64::consts::TAU
+    }
+}
+
+impl Replay {
+    /// Recomputes the judgements of the replay on its beatmap.
+    /// - Only osu!standard replays on osu!standard beatmaps are supported.
+    /// - Hitobjects with times or positions that aren't valid decimals are skipped.
+    /// - See the [module documentation][self] for how close it is to osu!.
+    pub fn judge(&self, osu_file: &OsuFile) -> Result<JudgementReport, ReplayError> {
+        let beatmap_mode = osu_file
+            .general
+            .as_ref()
+            .and_then(|g| g.mode)
+            .unwrap_or(Mode::Osu);
+        if self.mode != Mode::Osu || beatmap_mode != Mode::Osu {
+            return Err(ReplayError::UnsupportedMode(self.mode));
+        }
+
+        let settings = Settings::new(osu_file, self.mods);
+        let mut targets = targets(osu_file);
+        targets.sort_by(|a, b| a.time.total_cmp(&b.time));
+        apply_stacking(&mut targets, &settings);
+
+        let scale = settings.radius / 64.0;
+        let place = |position: Point, stack_height: i32| {
+            let y = if settings.hard_rock {
+                PLAYFIELD_HEIGHT - position.1
+            } else {
+                position.1
+            };
+            let offset = stack_height as f64 * scale * -6.4;
+            (position.0 + offset, y + offset)
+        };
+
+        let input = Input::new(self);
+
+        // circles and slider heads, by target
+        let mut heads: Vec<Option<(Judgement, Option<f64>, f64)>> =
+            targets.iter().map(|_| None).collect();
+        let clickable = (0..targets.len())
+            .filter(|i| !matches!(targets[*i].kind, Kind::Spinner))
+            .collect::<Vec<_>>();
+        let mut next = 0;
+        let latest_hit = |target: &Target| target.time + settings.windows[2];
+
+        for (time, position) in input.presses() {
+            while next < clickable.len() && latest_hit(&targets[clickable[next]]) < time {
+                let target = &targets[clickable[next]];
+                heads[clickable[next]] = Some((Judgement::Miss, None, latest_hit(target)));
+                next += 1;
+            }
+            let Some(&i) = clickable.get(next) else {
+                break;
+            };
+
+            let target = &targets[i];
+            let offset = time - target.time;
+            let target_position = place(target.position, target.stack_height);
+            if offset < -MISS_WINDOW || distance(position, target_position) > settings.radius {
+                continue;
+            }
+
+            heads[i] = Some((settings.judge_offset(offset), Some(offset), time));
+            next += 1;
+        }
+        for &i in &clickable[next..] {
+            heads[i] = Some((Judgement::Miss, None, latest_hit(&targets[i])));
+        }
+
+        let mut report = JudgementReport::default();
+        // time and whether the combo goes up or breaks
+        let mut combo_events = Vec::new();
+        let mut full_combo = 0;
+
+        for (i, target) in targets.iter().enumerate() {
+            let mut object = ObjectJudgement {
+                index: target.index,
+                judgement: Judgement::Miss,
+                offset: None,
+                slider_parts: None,
+                spins: None,
+            };
+
+            match &target.kind {
+                Kind::Circle => {
+                    let (judgement, offset, time) = heads[i].unwrap();
+                    object.judgement = judgement;
+                    object.offset = offset;
+                    combo_events.push((time, judgement != Judgement::Miss));
+                }
+                Kind::Slider(slider) => {
+                    let (head, offset, time) = heads[i].unwrap();
+                    let head_hit = head != Judgement::Miss;
+                    object.offset = offset;
+                    combo_events.push((time, head_hit));
+
+                    let follow_radius = settings.radius * FOLLOW_CIRCLE_SCALE;
+                    let tracking = |time: f64, distance_along: f64| {
+                        let ball =
+                            place(slider.path.position_at(distance_along), target.stack_height);
+                        input.state_at(time).is_some_and(|(position, buttons)| {
+                            buttons != 0 && distance(position, ball) <= follow_radius
+                        })
+                    };
+
+                    let mut hit = head_hit as usize;
+                    let mut total = 1;
+                    for (time, distance_along) in slider.checkpoints() {
+                        let time = target.time + time;
+                        let tracked = tracking(time, distance_along);
+                        hit += tracked as usize;
+                        total += 1;
+                        combo_events.push((time, tracked));
+                    }
+
+                    let duration = target.end_time - target.time;
+                    let end_time =
+                        (target.end_time - SLIDER_END_LENIENCY).max(target.time + duration / 2.0);
+                    let end_tracked =
+                        tracking(end_time, slider.ball_distance(end_time - target.time));
+                    hit += end_tracked as usize;
+                    total += 1;
+                    if end_tracked {
+                        combo_events.push((target.end_time, true));
+                    }
+
+                    object.judgement = if hit == total {
+                        Judgement::Hit300
+                    } else if hit * 2 >= total {
+                        Judgement::Hit100
+                    } else if hit > 0 {
+                        Judgement::Hit50
+                    } else {
+                        Judgement::Miss
+                    };
+                    object.slider_parts = Some((hit, total));
+                    full_combo += total - 1;
+                }
+                Kind::Spinner => {
+                    let spins = input.spins(target.time, target.end_time);
+                    let spins_per_second = difficulty_range(settings.od, 3.0, 5.0, 7.5);
+                    let required =
+                        ((target.end_time - target.time) / 1000.0 * spins_per_second).floor();
+                    let progress = if required > 0.0 {
+                        spins / required
+                    } else {
+                        1.0
+                    };
+
+                    object.judgement = if progress >= 1.0 {
+                        Judgement::Hit300
+                    } else if progress > 0.9 {
+                        Judgement::Hit100
+                    } else if progress > 0.75 {
+                        Judgement::Hit50
+                    } else {
+                        Judgement::Miss
+                    };
+                    object.spins = Some(spins);
+                    combo_events.push((target.end_time, object.judgement != Judgement::Miss));
+                }
+            }
+
+            full_combo += 1;
+            match object.judgement {
+                Judgement::Hit300 => report.count_300 += 1,
+                Judgement::Hit100 => report.count_100 += 1,
+                Judgement::Hit50 => report.count_50 += 1,
+                Judgement::Miss => report.count_miss += 1,
+            }
+            report.objects.push(object);
+        }
+
+        // spinners and the objects after them always start a new combo
+        let mut combo_start = 0;
+        for i in 0..targets.len() {
+            let combo_ends = targets.get(i + 1).is_none_or(|next| {
+                next.new_combo
+                    || matches!(next.kind, Kind::Spinner)
+                    || matches!(targets[i].kind, Kind::Spinner)
+            });
+            if !combo_ends {
+                continue;
+            }
+
+            let combo = &report.objects[combo_start..=i];
+            if combo.iter().all(|o| o.judgement == Judgement::Hit300) {
+                report.count_geki += 1;
+            } else if combo
+                .iter()
+                .all(|o| matches!(o.judgement, Judgement::Hit300 | Judgement::Hit100))
+            {
+                report.count_katu += 1;
+            }
+            combo_start = i + 1;
+        }
+
+        combo_events.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut combo = 0u16;
+        for (_, hit) in &combo_events {
+            combo = if *hit { combo.saturating_add(1) } else { 0 };
+            report.max_combo = report.max_combo.max(combo);
+        }
+        report.full_combo = full_combo.min(u16::MAX as usize) as u16;
+        report.objects.sort_by_key(|o| o.index);
+        Ok(report)
+    }
+}
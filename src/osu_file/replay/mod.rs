@@ -0,0 +1,330 @@
+//! Reading and writing of .osr replay files.
+
+pub mod error;
+pub mod judge;
+pub mod types;
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+use lzma_rs::compress::{Options, UnpackedSize};
+
+use super::binary::{BinaryReader, BinaryWriter};
+use super::general::Mode;
+
+pub use error::*;
+pub use types::*;
+
+/// Time delta of the frame holding the RNG seed, which is the last frame of newer replays.
+const SEED_FRAME_TIME_DELTA: i64 = -12345;
+
+/// A replay of a score.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub mode: Mode,
+    /// Version of the game that made the replay, such as `20150414`.
+    pub version: i32,
+    /// MD5 hash of the .osu file the replay was played on.
+    pub beatmap_md5: Option<String>,
+    pub player_name: Option<String>,
+    /// MD5 hash of the replay, which osu! uses to tell replays apart.
+    pub replay_md5: Option<String>,
+    pub count_300: u16,
+    pub count_100: u16,
+    pub count_50: u16,
+    /// Number of gekis, or max 300s in osu!mania.
+    pub count_geki: u16,
+    /// Number of katus, or 200s in osu!mania.
+    pub count_katu: u16,
+    pub count_miss: u16,
+    pub score: i32,
+    pub max_combo: u16,
+    /// Full combo.
+    pub perfect: bool,
+    pub mods: Mods,
+    /// Life over time, which is `None` if there is no graph.
+    pub life_bar: Option<Vec<LifeBarPoint>>,
+    /// Time the replay was made, in Windows ticks.
+    /// - Ticks are 100 nanoseconds since 0001-01-01 UTC.
+    pub timestamp: i64,
+    /// Input frames, without the seed frame.
+    pub frames: Vec<ReplayFrame>,
+    /// RNG seed from the last frame, which only exists in replays since `20130319`.
+    pub seed: Option<i32>,
+    /// Score ID on the osu! servers, or `0` if the score wasn't submitted.
+    pub online_score_id: i64,
+    /// Accuracy of the score, which only exists for [`Mods::TARGET_PRACTICE`].
+    pub target_practice_accuracy: Option<f64>,
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self {
+            mode: Mode::Osu,
+            version: 0,
+            beatmap_md5: None,
+            player_name: None,
+            replay_md5: None,
+            count_300: 0,
+            count_100: 0,
+            count_50: 0,
+            count_geki: 0,
+            count_katu: 0,
+            count_miss: 0,
+            score: 0,
+            max_combo: 0,
+            perfect: false,
+            mods: Mods::NONE,
+            life_bar: None,
+            timestamp: 0,
+            frames: Vec::new(),
+            seed: None,
+            online_score_id: 0,
+            target_practice_accuracy: None,
+        }
+    }
+}
+
+pub(crate) fn mode_from_u8(mode: u8) -> Result<Mode, ReplayError> {
+    match mode {
+        0 => Ok(Mode::Osu),
+        1 => Ok(Mode::Taiko),
+        2 => Ok(Mode::Catch),
+        3 => Ok(Mode::Mania),
+        _ => Err(ReplayError::UnknownMode(mode)),
+    }
+}
+
+pub(crate) fn mode_to_u8(mode: Mode) -> u8 {
+    match mode {
+        Mode::Osu => 0,
+        Mode::Taiko => 1,
+        Mode::Catch => 2,
+        Mode::Mania => 3,
+    }
+}
+
+fn parse_life_bar(s: &str) -> Result<Vec<LifeBarPoint>, ReplayError> {
+    s.split(',')
+        .filter(|point| !point.trim().is_empty())
+        .map(|point| {
+            let invalid = || ReplayError::InvalidLifeBar(point.to_string());
+            let (time, life) = point.split_once('|').ok_or_else(invalid)?;
+
+            Ok(LifeBarPoint {
+                time: time.trim().parse().map_err(|_| invalid())?,
+                life: life.trim().parse().map_err(|_| invalid())?,
+            })
+        })
+        .collect()
+}
+
+/// Parses the decompressed frames, returning the seed separately.
+fn parse_frames(s: &str) -> Result<(Vec<ReplayFrame>, Option<i32>), ReplayError> {
+    let mut frames = Vec::new();
+    let mut seed = None;
+
+    for frame in s.split(',').filter(|frame| !frame.trim().is_empty()) {
+        let invalid = || ReplayError::InvalidFrame(frame.to_string());
+        let fields = frame.split('|').map(str::trim).collect::<Vec<_>>();
+        let [time_delta, x, y, keys] = fields[..] else {
+            return Err(invalid());
+        };
+
+        let time_delta = time_delta.parse::<i64>().map_err(|_| invalid())?;
+        let keys = keys.parse::<i64>().map_err(|_| invalid())?;
+
+        if time_delta == SEED_FRAME_TIME_DELTA {
+            seed = Some(keys as i32);
+            continue;
+        }
+
+        frames.push(ReplayFrame {
+            time_delta,
+            x: x.parse().map_err(|_| invalid())?,
+            y: y.parse().map_err(|_| invalid())?,
+            keys: Keys(keys as u32),
+        });
+    }
+
+    Ok((frames, seed))
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a replay from its bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        Self::read(&mut BinaryReader::new(bytes))
+    }
+
+    /// Reads a replay, which can be without frames as it is in scores.db.
+    pub(crate) fn read(reader: &mut BinaryReader) -> Result<Self, ReplayError> {
+        let mut replay = Replay {
+            mode: mode_from_u8(reader.u8()?)?,
+            version: reader.i32()?,
+            beatmap_md5: reader.string()?,
+            player_name: reader.string()?,
+            replay_md5: reader.string()?,
+            count_300: reader.u16()?,
+            count_100: reader.u16()?,
+            count_50: reader.u16()?,
+            count_geki: reader.u16()?,
+            count_katu: reader.u16()?,
+            count_miss: reader.u16()?,
+            score: reader.i32()?,
+            max_combo: reader.u16()?,
+            perfect: reader.bool()?,
+            mods: Mods(reader.u32()?),
+            life_bar: reader
+                .string()?
+                .map(|life_bar| parse_life_bar(&life_bar))
+                .transpose()?,
+            timestamp: reader.i64()?,
+            ..Default::default()
+        };
+
+        let data_len = reader.i32()?;
+        if data_len > 0 {
+            let mut data = Vec::new();
+            lzma_rs::lzma_decompress(&mut reader.bytes(data_len as usize)?, &mut data)?;
+            let data = String::from_utf8(data).map_err(|_| ReplayError::InvalidUtf8)?;
+
+            (replay.frames, replay.seed) = parse_frames(&data)?;
+        }
+
+        replay.online_score_id = if replay.version >= 20140721 {
+            reader.i64()?
+        } else if replay.version >= 20121008 {
+            reader.i32()? as i64
+        } else {
+            0
+        };
+
+        if replay.mods.contains(Mods::TARGET_PRACTICE) {
+            replay.target_practice_accuracy = Some(reader.f64()?);
+        }
+
+        Ok(replay)
+    }
+
+    /// Reads a replay.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, ReplayError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Reads a replay from a file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ReplayError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// The frames as written in the replay data, before compression.
+    pub fn frames_to_string(&self) -> String {
+        let mut s = String::new();
+
+        for frame in &self.frames {
+            s.push_str(&format!(
+                "{}|{}|{}|{},",
+                frame.time_delta, frame.x, frame.y, frame.keys.0
+            ));
+        }
+        if let Some(seed) = self.seed {
+            s.push_str(&format!("{SEED_FRAME_TIME_DELTA}|0|0|{seed},"));
+        }
+
+        s
+    }
+
+    /// Writes the replay into bytes.
+    /// - [`Replay::online_score_id`] and [`Replay::target_practice_accuracy`] are only written if the version and mods have them.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ReplayError> {
+        let mut writer = BinaryWriter::new();
+        self.write_into(&mut writer, true)?;
+
+        Ok(writer.bytes)
+    }
+
+    /// Writes the replay, with the frames replaced by a length of `-1` if `with_frames` is `false`.
+    pub(crate) fn write_into(
+        &self,
+        writer: &mut BinaryWriter,
+        with_frames: bool,
+    ) -> Result<(), ReplayError> {
+        writer.u8(mode_to_u8(self.mode));
+        writer.i32(self.version);
+        writer.string(self.beatmap_md5.as_deref());
+        writer.string(self.player_name.as_deref());
+        writer.string(self.replay_md5.as_deref());
+        writer.u16(self.count_300);
+        writer.u16(self.count_100);
+        writer.u16(self.count_50);
+        writer.u16(self.count_geki);
+        writer.u16(self.count_katu);
+        writer.u16(self.count_miss);
+        writer.i32(self.score);
+        writer.u16(self.max_combo);
+        writer.bool(self.perfect);
+        writer.u32(self.mods.0);
+
+        let life_bar = self.life_bar.as_ref().map(|life_bar| {
+            life_bar
+                .iter()
+                .map(|point| format!("{}|{},", point.time, point.life))
+                .collect::<String>()
+        });
+        writer.string(life_bar.as_deref());
+        writer.i64(self.timestamp);
+
+        if with_frames {
+            let frames = self.frames_to_string();
+            let mut data = Vec::new();
+            lzma_rs::lzma_compress_with_options(
+                &mut frames.as_bytes(),
+                &mut data,
+                &Options {
+                    unpacked_size: UnpackedSize::WriteToHeader(Some(frames.len() as u64)),
+                },
+            )?;
+            writer.i32(data.len() as i32);
+            writer.bytes.extend(data);
+        } else {
+            writer.i32(-1);
+        }
+
+        if self.version >= 20140721 {
+            writer.i64(self.online_score_id);
+        } else if self.version >= 20121008 {
+            writer.i32(self.online_score_id as i32);
+        }
+
+        if self.mods.contains(Mods::TARGET_PRACTICE) {
+            writer.f64(self.target_practice_accuracy.unwrap_or_default());
+        }
+
+        Ok(())
+    }
+
+    /// Writes the replay.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), ReplayError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Writes the replay to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ReplayError> {
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Frames with their time in milliseconds, which is the sum of the time deltas up to the frame.
+    pub fn timed_frames(&self) -> impl Iterator<Item = (i64, &ReplayFrame)> {
+        self.frames.iter().scan(0, |time, frame| {
+            *time += frame.time_delta;
+            Some((*time, frame))
+        })
+    }
+}
@@ -0,0 +1,124 @@
+use std::ops::{BitOr, BitOrAssign};
+
+/// Mods a score was played with, as bit flags.
+/// - Unknown bits are kept as they are.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Mods(pub u32);
+
+impl Mods {
+    pub const NONE: Mods = Mods(0);
+    pub const NO_FAIL: Mods = Mods(1 << 0);
+    pub const EASY: Mods = Mods(1 << 1);
+    pub const TOUCH_DEVICE: Mods = Mods(1 << 2);
+    pub const HIDDEN: Mods = Mods(1 << 3);
+    pub const HARD_ROCK: Mods = Mods(1 << 4);
+    pub const SUDDEN_DEATH: Mods = Mods(1 << 5);
+    pub const DOUBLE_TIME: Mods = Mods(1 << 6);
+    pub const RELAX: Mods = Mods(1 << 7);
+    pub const HALF_TIME: Mods = Mods(1 << 8);
+    /// Always set together with [`Mods::DOUBLE_TIME`].
+    pub const NIGHTCORE: Mods = Mods(1 << 9);
+    pub const FLASHLIGHT: Mods = Mods(1 << 10);
+    pub const AUTOPLAY: Mods = Mods(1 << 11);
+    pub const SPUN_OUT: Mods = Mods(1 << 12);
+    pub const AUTOPILOT: Mods = Mods(1 << 13);
+    /// Always set together with [`Mods::SUDDEN_DEATH`].
+    pub const PERFECT: Mods = Mods(1 << 14);
+    pub const KEY4: Mods = Mods(1 << 15);
+    pub const KEY5: Mods = Mods(1 << 16);
+    pub const KEY6: Mods = Mods(1 << 17);
+    pub const KEY7: Mods = Mods(1 << 18);
+    pub const KEY8: Mods = Mods(1 << 19);
+    pub const FADE_IN: Mods = Mods(1 << 20);
+    pub const RANDOM: Mods = Mods(1 << 21);
+    pub const CINEMA: Mods = Mods(1 << 22);
+    pub const TARGET_PRACTICE: Mods = Mods(1 << 23);
+    pub const KEY9: Mods = Mods(1 << 24);
+    pub const KEY_COOP: Mods = Mods(1 << 25);
+    pub const KEY1: Mods = Mods(1 << 26);
+    pub const KEY3: Mods = Mods(1 << 27);
+    pub const KEY2: Mods = Mods(1 << 28);
+    pub const SCORE_V2: Mods = Mods(1 << 29);
+    pub const MIRROR: Mods = Mods(1 << 30);
+
+    /// Returns `true` if every mod in `other` is set.
+    pub fn contains(&self, other: Mods) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: Mods) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Mods) {
+        self.0 &= !other.0;
+    }
+}
+
+impl BitOr for Mods {
+    type Output = Mods;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Mods(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Mods {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
+/// Buttons held in a [`ReplayFrame`], as bit flags.
+/// - For osu!mania, this is unused and the held columns are in [`ReplayFrame::x`] instead.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Keys(pub u32);
+
+impl Keys {
+    pub const NONE: Keys = Keys(0);
+    /// Left mouse button, also set with [`Keys::K1`].
+    pub const M1: Keys = Keys(1 << 0);
+    /// Right mouse button, also set with [`Keys::K2`].
+    pub const M2: Keys = Keys(1 << 1);
+    pub const K1: Keys = Keys(1 << 2);
+    pub const K2: Keys = Keys(1 << 3);
+    pub const SMOKE: Keys = Keys(1 << 4);
+
+    /// Returns `true` if every key in `other` is held.
+    pub fn contains(&self, other: Keys) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true` if any of the keys in `other` is held.
+    pub fn intersects(&self, other: Keys) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for Keys {
+    type Output = Keys;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Keys(self.0 | rhs.0)
+    }
+}
+
+/// A single frame of replay input.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ReplayFrame {
+    /// Milliseconds since the previous frame.
+    pub time_delta: i64,
+    /// Cursor position in osu! pixels.
+    pub x: f32,
+    pub y: f32,
+    pub keys: Keys,
+}
+
+/// A point of the life bar graph.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LifeBarPoint {
+    /// Time in milliseconds.
+    pub time: i32,
+    /// Amount of life from `0` to `1`.
+    pub life: f64,
+}
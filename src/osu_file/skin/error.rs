@@ -0,0 +1,44 @@
+use std::num::ParseIntError;
+
+use thiserror::Error;
+
+use crate::helper::macros::unreachable_err_impl;
+use crate::helper::ParseZeroOneBoolError;
+use crate::osu_file::colours::ParseRgbError;
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+/// Error used when there was a problem parsing a skin.ini file.
+pub enum ParseError {
+    /// Unexpected line before any section.
+    #[error("Unexpected line before any section")]
+    UnexpectedLine,
+    /// Duplicate section names defined.
+    /// - Only `Mania` can be defined more than once.
+    #[error("There are multiple sections defined as the same name")]
+    DuplicateSections,
+    /// A `Mania` section without the `Keys` field.
+    #[error("The `Mania` section is missing the `Keys` field")]
+    MissingManiaKeys,
+    /// There is a duplicate field in the section.
+    /// - Only returned when parsing a single section, as [`Skin`][super::Skin] keeps the last value.
+    #[error("There is a duplicate field in the section")]
+    DuplicateField,
+    /// When the line isn't in a `key: value` format.
+    #[error("Invalid colon set, expected format of `key: value`")]
+    InvalidColonSet,
+    /// Invalid key name was used.
+    #[error("The key doesn't exist in the section")]
+    InvalidKey,
+    /// A field failed to parse as an `Integer`.
+    #[error(transparent)]
+    ParseIntError(#[from] ParseIntError),
+    /// A field failed to parse as a `0` or `1` boolean.
+    #[error(transparent)]
+    ParseZeroOneBoolError(#[from] ParseZeroOneBoolError),
+    /// A field failed to parse as a colour.
+    #[error(transparent)]
+    ParseRgbError(#[from] ParseRgbError),
+}
+
+unreachable_err_impl!(ParseError);
@@ -0,0 +1,573 @@
+//! Parsing and writing of skin.ini files.
+
+pub mod error;
+pub mod types;
+
+use std::fmt::Display;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use crate::helper;
+use crate::helper::macros::*;
+
+use super::colours::{ParseRgbError, Rgb};
+use super::{Decimal, Error, Integer, VersionedFromStr, VersionedToString, LATEST_VERSION};
+
+pub use error::*;
+pub use types::*;
+
+fn parse_rgb(s: &str) -> Result<Rgb, ParseRgbError> {
+    Rgb::from_str(s, LATEST_VERSION).map(|rgb| rgb.unwrap())
+}
+
+/// Writes the colour with its alpha, which skins can use unlike beatmaps.
+fn rgb_to_string(rgb: &Rgb) -> String {
+    let rgb_string = rgb.to_string(LATEST_VERSION).unwrap();
+
+    match rgb.alpha {
+        Some(alpha) => format!("{rgb_string},{alpha}"),
+        None => rgb_string,
+    }
+}
+
+/// Parses the `key: value` lines of a section, returning the fields that `set_field` doesn't know in the order they are written.
+fn parse_fields<F>(
+    lines: &[String],
+    mut set_field: F,
+) -> Result<Vec<(String, String)>, Error<ParseError>>
+where
+    F: FnMut(&str, &str) -> Result<(), ParseError>,
+{
+    let mut unknown_fields: Vec<(String, String)> = Vec::new();
+
+    for (line_index, line) in lines.iter().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            return Err(Error::new(ParseError::InvalidColonSet, line_index));
+        };
+        let value = value.trim_start();
+
+        // like osu!, a field defined again replaces the earlier value
+        match set_field(key, value) {
+            Ok(()) => (),
+            Err(ParseError::InvalidKey) => {
+                match unknown_fields.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, v)) => *v = value.to_string(),
+                    None => unknown_fields.push((key.to_string(), value.to_string())),
+                }
+            }
+            Err(err) => return Err(Error::new(err, line_index)),
+        }
+    }
+
+    Ok(unknown_fields)
+}
+
+/// Writes the known fields of a section followed by the unknown ones.
+fn fields_to_string(known: Option<String>, unknown: &[(String, String)]) -> String {
+    known
+        .filter(|known| !known.is_empty())
+        .into_iter()
+        .chain(unknown.iter().map(|(key, value)| format!("{key}: {value}")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_comma_list<T: FromStr>(s: &str) -> Result<Vec<T>, T::Err> {
+    s.split(',').map(|s| s.trim().parse()).collect()
+}
+
+fn comma_list_to_string<T: Display>(v: &[T]) -> String {
+    v.iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+versioned_field!(Name, String, no_versions, |s| { Ok(s.to_string()) } -> (),,);
+versioned_field!(Author, String, no_versions, |s| { Ok(s.to_string()) } -> (),,);
+versioned_field!(Version, SkinVersion, no_versions, |s| { s.parse() } -> (),, SkinVersion::default());
+versioned_field!(AnimationFramerate, Integer, no_versions, |s| { s.parse() } -> ParseIntError,, -1);
+versioned_field!(AllowSliderBallTint, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, false);
+versioned_field!(ComboBurstRandom, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, false);
+versioned_field!(CursorCentre, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, true);
+versioned_field!(CursorExpand, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, true);
+versioned_field!(CursorRotate, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, true);
+versioned_field!(CursorTrailRotate, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, true);
+versioned_field!(CustomComboBurstSounds, Vec<Integer>, no_versions, |s| { parse_comma_list(s) } -> ParseIntError, |v| { comma_list_to_string(v) },);
+versioned_field!(HitCircleOverlayAboveNumber, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, true);
+versioned_field!(LayeredHitSounds, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, true);
+versioned_field!(SliderBallFlip, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, true);
+versioned_field!(SliderStyle, Integer, no_versions, |s| { s.parse() } -> ParseIntError,, 2);
+versioned_field!(SpinnerFadePlayfield, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, false);
+versioned_field!(SpinnerFrequencyModulate, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, true);
+versioned_field!(SpinnerNoBlink, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, false);
+
+general_section!(
+    /// A struct representing the general section of a skin.ini file.
+    pub struct General {
+        /// Name of the skin.
+        pub name: Name,
+        /// Creator of the skin.
+        pub author: Author,
+        /// Version of the skin.
+        pub version: Version,
+        /// Frame rate of animations, or `-1` for the default of each animation.
+        pub animation_framerate: AnimationFramerate,
+        /// Whether the slider ball is tinted with the combo colour.
+        pub allow_slider_ball_tint: AllowSliderBallTint,
+        /// Whether combo bursts are shown in a random order.
+        pub combo_burst_random: ComboBurstRandom,
+        /// Whether the cursor is centred.
+        pub cursor_centre: CursorCentre,
+        /// Whether the cursor expands when clicking.
+        pub cursor_expand: CursorExpand,
+        /// Whether the cursor rotates.
+        pub cursor_rotate: CursorRotate,
+        /// Whether the cursor trail rotates.
+        pub cursor_trail_rotate: CursorTrailRotate,
+        /// Combo counts that play the combo burst sounds.
+        pub custom_combo_burst_sounds: CustomComboBurstSounds,
+        /// Whether the hit circle overlay is drawn above the combo number.
+        pub hit_circle_overlay_above_number: HitCircleOverlayAboveNumber,
+        /// Whether the normal hit sound is always played.
+        pub layered_hit_sounds: LayeredHitSounds,
+        /// Whether the slider ball is flipped when going backwards.
+        pub slider_ball_flip: SliderBallFlip,
+        /// Deprecated.
+        pub slider_style: SliderStyle,
+        /// Whether the playfield fades out during spinners.
+        pub spinner_fade_playfield: SpinnerFadePlayfield,
+        /// Whether the spinner sound pitch goes up with the spin speed.
+        pub spinner_frequency_modulate: SpinnerFrequencyModulate,
+        /// Whether the spinner's highest bar stays lit.
+        pub spinner_no_blink: SpinnerNoBlink,
+    },
+    ParseError,
+    " ",
+);
+
+versioned_field!(Combo1, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(Combo2, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(Combo3, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(Combo4, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(Combo5, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(Combo6, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(Combo7, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(Combo8, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(InputOverlayText, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(MenuGlow, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(SliderBall, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(SliderBorder, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(SliderTrackOverride, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(SongSelectActiveText, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(SongSelectInactiveText, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(SpinnerBackground, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(StarBreakAdditive, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+
+general_section!(
+    /// A struct representing the colours section of a skin.ini file.
+    pub struct Colours {
+        /// Combo colour 1.
+        /// - Beatmap combo colours take priority unless the player disables them.
+        pub combo1: Combo1,
+        /// Combo colour 2.
+        pub combo2: Combo2,
+        /// Combo colour 3.
+        pub combo3: Combo3,
+        /// Combo colour 4.
+        pub combo4: Combo4,
+        /// Combo colour 5.
+        pub combo5: Combo5,
+        /// Combo colour 6.
+        pub combo6: Combo6,
+        /// Combo colour 7.
+        pub combo7: Combo7,
+        /// Combo colour 8.
+        pub combo8: Combo8,
+        /// Colour of the key counters in the input overlay.
+        pub input_overlay_text: InputOverlayText,
+        /// Glow of the main menu visualiser.
+        pub menu_glow: MenuGlow,
+        /// Slider ball tint.
+        pub slider_ball: SliderBall,
+        /// Slider border colour.
+        pub slider_border: SliderBorder,
+        /// Slider track colour, used instead of the combo colour.
+        pub slider_track_override: SliderTrackOverride,
+        /// Text colour of the selected beatmap in song select.
+        pub song_select_active_text: SongSelectActiveText,
+        /// Text colour of the other beatmaps in song select.
+        pub song_select_inactive_text: SongSelectInactiveText,
+        /// Spinner background tint.
+        pub spinner_background: SpinnerBackground,
+        /// Tint of the breaking star effect in the main menu.
+        pub star_break_additive: StarBreakAdditive,
+    },
+    ParseError,
+    " ",
+);
+
+impl Colours {
+    /// Combo colours that are set, from `Combo1` to `Combo8`.
+    pub fn combo_colours(&self) -> Vec<Rgb> {
+        [
+            self.combo1.as_ref().map(|c| c.0),
+            self.combo2.as_ref().map(|c| c.0),
+            self.combo3.as_ref().map(|c| c.0),
+            self.combo4.as_ref().map(|c| c.0),
+            self.combo5.as_ref().map(|c| c.0),
+            self.combo6.as_ref().map(|c| c.0),
+            self.combo7.as_ref().map(|c| c.0),
+            self.combo8.as_ref().map(|c| c.0),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+versioned_field!(HitCirclePrefix, String, no_versions, |s| { Ok(s.to_string()) } -> (),, "default".to_string());
+versioned_field!(HitCircleOverlap, Integer, no_versions, |s| { s.parse() } -> ParseIntError,, -2);
+versioned_field!(ScorePrefix, String, no_versions, |s| { Ok(s.to_string()) } -> (),, "score".to_string());
+versioned_field!(ScoreOverlap, Integer, no_versions, |s| { s.parse() } -> ParseIntError,, 0);
+versioned_field!(ComboPrefix, String, no_versions, |s| { Ok(s.to_string()) } -> (),, "score".to_string());
+versioned_field!(ComboOverlap, Integer, no_versions, |s| { s.parse() } -> ParseIntError,, 0);
+
+general_section!(
+    /// A struct representing the fonts section of a skin.ini file.
+    pub struct Fonts {
+        /// Prefix of the hit circle number images.
+        pub hit_circle_prefix: HitCirclePrefix,
+        /// Overlap of the hit circle number images in pixels.
+        pub hit_circle_overlap: HitCircleOverlap,
+        /// Prefix of the score number images.
+        pub score_prefix: ScorePrefix,
+        /// Overlap of the score number images in pixels.
+        pub score_overlap: ScoreOverlap,
+        /// Prefix of the combo number images.
+        pub combo_prefix: ComboPrefix,
+        /// Overlap of the combo number images in pixels.
+        pub combo_overlap: ComboOverlap,
+    },
+    ParseError,
+    " ",
+);
+
+versioned_field!(HyperDash, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(HyperDashFruit, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+versioned_field!(HyperDashAfterImage, Rgb, no_versions, |s| { parse_rgb(s) } -> ParseRgbError, |v| { rgb_to_string(v) },);
+
+general_section!(
+    /// A struct representing the osu!catch section of a skin.ini file.
+    pub struct CatchTheBeat {
+        /// Catcher tint while hyper dashing.
+        pub hyper_dash: HyperDash,
+        /// Tint of fruits that start a hyper dash.
+        pub hyper_dash_fruit: HyperDashFruit,
+        /// Tint of the catcher's after images while hyper dashing.
+        pub hyper_dash_after_image: HyperDashAfterImage,
+    },
+    ParseError,
+    " ",
+);
+
+versioned_field!(Keys, Integer, no_versions, |s| { s.parse() } -> ParseIntError,,);
+versioned_field!(ColumnStart, Decimal, no_versions, |s| { s.parse() } -> (),, Decimal::from(136));
+versioned_field!(ColumnRight, Decimal, no_versions, |s| { s.parse() } -> (),, Decimal::from(19));
+versioned_field!(ColumnSpacing, Vec<Decimal>, no_versions, |s| { parse_comma_list(s) } -> (), |v| { comma_list_to_string(v) },);
+versioned_field!(ColumnWidth, Vec<Decimal>, no_versions, |s| { parse_comma_list(s) } -> (), |v| { comma_list_to_string(v) },);
+versioned_field!(ColumnLineWidth, Vec<Decimal>, no_versions, |s| { parse_comma_list(s) } -> (), |v| { comma_list_to_string(v) },);
+versioned_field!(BarlineHeight, Decimal, no_versions, |s| { s.parse() } -> (),, Decimal::from(2));
+versioned_field!(HitPosition, Integer, no_versions, |s| { s.parse() } -> ParseIntError,, 402);
+versioned_field!(LightPosition, Integer, no_versions, |s| { s.parse() } -> ParseIntError,, 413);
+versioned_field!(ScorePosition, Integer, no_versions, |s| { s.parse() } -> ParseIntError,,);
+versioned_field!(ComboPosition, Integer, no_versions, |s| { s.parse() } -> ParseIntError,,);
+versioned_field!(LightFramePerSecond, Integer, no_versions, |s| { s.parse() } -> ParseIntError,,);
+versioned_field!(JudgementLine, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, false);
+versioned_field!(SpecialStyle, Integer, no_versions, |s| { s.parse() } -> ParseIntError,, 0);
+versioned_field!(ComboBurstStyle, Integer, no_versions, |s| { s.parse() } -> ParseIntError,, 1);
+versioned_field!(SplitStages, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, false);
+versioned_field!(StageSeparation, Decimal, no_versions, |s| { s.parse() } -> (),, Decimal::from(40));
+versioned_field!(SeparateScore, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, true);
+versioned_field!(KeysUnderNotes, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, false);
+versioned_field!(UpsideDown, bool, no_versions, |s| { helper::parse_zero_one_bool(s) } -> helper::ParseZeroOneBoolError, boolean, false);
+
+general_section!(
+    /// Settings of a `Mania` section that apply to the whole stage.
+    pub struct ManiaSettings {
+        /// Key count the section is for.
+        pub keys: Keys,
+        /// Left position of the stage.
+        pub column_start: ColumnStart,
+        /// Right position of the stage when `column_start` isn't used.
+        pub column_right: ColumnRight,
+        /// Gap between each column, separated by commas.
+        pub column_spacing: ColumnSpacing,
+        /// Width of each column, separated by commas.
+        pub column_width: ColumnWidth,
+        /// Width of each column line, separated by commas.
+        pub column_line_width: ColumnLineWidth,
+        /// Height of the bar lines.
+        pub barline_height: BarlineHeight,
+        /// Position of the judgement line.
+        pub hit_position: HitPosition,
+        /// Position of the lighting effects.
+        pub light_position: LightPosition,
+        /// Position of the judgement display.
+        pub score_position: ScorePosition,
+        /// Position of the combo counter.
+        pub combo_position: ComboPosition,
+        /// Frame rate of the lighting animations.
+        pub light_frame_per_second: LightFramePerSecond,
+        /// Whether the judgement line is drawn.
+        pub judgement_line: JudgementLine,
+        /// Layout of the special column.
+        pub special_style: SpecialStyle,
+        /// Side the combo burst is shown on.
+        pub combo_burst_style: ComboBurstStyle,
+        /// Whether the stage is split into two when there are many columns.
+        pub split_stages: SplitStages,
+        /// Gap between split stages.
+        pub stage_separation: StageSeparation,
+        /// Whether the score is shown for each stage.
+        pub separate_score: SeparateScore,
+        /// Whether the key images are drawn under the notes.
+        pub keys_under_notes: KeysUnderNotes,
+        /// Whether the stage is flipped upside down.
+        pub upside_down: UpsideDown,
+    },
+    ParseError,
+    " ",
+);
+
+/// A `Mania` section, which holds the settings for a single key count.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Mania {
+    pub settings: ManiaSettings,
+    /// Fields that aren't part of [`ManiaSettings`], in the order they are written.
+    /// - These are mostly numbered per column, such as `KeyImage0` or `Colour1`.
+    pub column_fields: Vec<(String, String)>,
+}
+
+impl Mania {
+    /// Parses a `Mania` section from its trimmed lines.
+    fn from_lines(lines: &[String]) -> Result<Self, Error<ParseError>> {
+        let mut settings = ManiaSettings::new();
+        let column_fields = parse_fields(lines, |key, value| {
+            settings.set_field(key, Some(value), LATEST_VERSION)
+        })?;
+
+        Ok(Mania {
+            settings,
+            column_fields,
+        })
+    }
+}
+
+impl Display for Mania {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            fields_to_string(self.settings.to_string(LATEST_VERSION), &self.column_fields)
+        )
+    }
+}
+
+/// A skin.ini file represented as a struct.
+/// - `//` starts a comment, which isn't kept, both at the start of a line and after a value.
+/// - A field defined more than once in a section has the last value, as in osu!.
+/// - Fields and sections that aren't known are kept, so they are written back.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Skin {
+    pub general: Option<General>,
+    pub colours: Option<Colours>,
+    pub fonts: Option<Fonts>,
+    pub catch_the_beat: Option<CatchTheBeat>,
+    /// `Mania` sections, one for each key count.
+    pub mania: Vec<Mania>,
+    /// Fields of the `General`, `Colours`, `Fonts` and `CatchTheBeat` sections that aren't known, by the section name.
+    /// - These are written after the known fields of the section, such as misspelled keys or keys only osu!lazer reads.
+    pub unknown_fields: Vec<(String, Vec<(String, String)>)>,
+    /// Sections that aren't known, with their lines in the order they are written.
+    pub unknown_sections: Vec<(String, Vec<String>)>,
+}
+
+/// Trims the line, removing comments and whitespace before the colon of a `key: value` line.
+/// - Comments can also be after a value, such as `CursorRotate: 1 // yes`.
+fn clean_line(line: &str) -> String {
+    let line = match line.find("//") {
+        Some(i) => &line[..i],
+        None => line,
+    }
+    .trim();
+
+    match line.split_once(':') {
+        Some((key, value)) => format!("{}:{value}", key.trim_end()),
+        None => line.to_string(),
+    }
+}
+
+impl Skin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Version of the skin, which is `1.0` if it isn't defined.
+    pub fn version(&self) -> SkinVersion {
+        self.general
+            .as_ref()
+            .and_then(|general| general.version.clone())
+            .map(|version| version.0)
+            .unwrap_or_default()
+    }
+
+    /// Fields of the section that aren't known.
+    pub fn unknown_fields(&self, section: &str) -> &[(String, String)] {
+        self.unknown_fields
+            .iter()
+            .find(|(name, _)| name == section)
+            .map(|(_, fields)| fields.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The `Mania` section for the key count.
+    pub fn mania(&self, keys: Integer) -> Option<&Mania> {
+        self.mania
+            .iter()
+            .find(|mania| mania.settings.keys.as_ref().map(|k| k.0) == Some(keys))
+    }
+}
+
+impl FromStr for Skin {
+    type Err = Error<ParseError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix('\u{feff}').unwrap_or(s);
+
+        // (line index of the section name, section name, lines of the section)
+        let mut sections: Vec<(usize, &str, Vec<String>)> = Vec::new();
+
+        for (line_index, line) in s.lines().enumerate() {
+            let trimmed = line.trim();
+
+            if let Some(name) = trimmed
+                .strip_prefix('[')
+                .and_then(|name| name.strip_suffix(']'))
+            {
+                sections.push((line_index, name, Vec::new()));
+                continue;
+            }
+
+            match sections.last_mut() {
+                Some((_, _, lines)) => lines.push(clean_line(line)),
+                None if trimmed.is_empty() || trimmed.starts_with("//") => (),
+                None => return Err(Error::new(ParseError::UnexpectedLine, line_index)),
+            }
+        }
+
+        let mut skin = Skin::new();
+        let mut section_parsed = Vec::new();
+
+        for (section_line, name, lines) in sections {
+            if name != "Mania" && section_parsed.contains(&name) {
+                return Err(Error::new(ParseError::DuplicateSections, section_line));
+            }
+            section_parsed.push(name);
+
+            let body_line = section_line + 1;
+
+            if name == "Mania" {
+                let mania = Error::processing_line(Mania::from_lines(&lines), body_line)?;
+                if mania.settings.keys.is_none() {
+                    return Err(Error::new(ParseError::MissingManiaKeys, section_line));
+                }
+                skin.mania.push(mania);
+                continue;
+            }
+
+            macro_rules! parse_section {
+                ($field:ident, $section_type:ty) => {{
+                    let mut section = <$section_type>::new();
+                    let unknown_fields = Error::processing_line(
+                        parse_fields(&lines, |key, value| {
+                            section.set_field(key, Some(value), LATEST_VERSION)
+                        }),
+                        body_line,
+                    )?;
+
+                    skin.$field = Some(section);
+                    if !unknown_fields.is_empty() {
+                        skin.unknown_fields.push((name.to_string(), unknown_fields));
+                    }
+                }};
+            }
+
+            match name {
+                "General" => parse_section!(general, General),
+                "Colours" => parse_section!(colours, Colours),
+                "Fonts" => parse_section!(fonts, Fonts),
+                "CatchTheBeat" => parse_section!(catch_the_beat, CatchTheBeat),
+                _ => skin.unknown_sections.push((
+                    name.to_string(),
+                    lines.into_iter().filter(|line| !line.is_empty()).collect(),
+                )),
+            }
+        }
+
+        Ok(skin)
+    }
+}
+
+impl Display for Skin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut sections = Vec::new();
+
+        let known = [
+            (
+                "General",
+                self.general.as_ref().map(|s| s.to_string(LATEST_VERSION)),
+            ),
+            (
+                "Colours",
+                self.colours.as_ref().map(|s| s.to_string(LATEST_VERSION)),
+            ),
+            (
+                "Fonts",
+                self.fonts.as_ref().map(|s| s.to_string(LATEST_VERSION)),
+            ),
+            (
+                "CatchTheBeat",
+                self.catch_the_beat
+                    .as_ref()
+                    .map(|s| s.to_string(LATEST_VERSION)),
+            ),
+        ];
+        for (name, fields) in known {
+            let unknown_fields = self.unknown_fields(name);
+            if fields.is_some() || !unknown_fields.is_empty() {
+                sections.push((name, fields_to_string(fields.flatten(), unknown_fields)));
+            }
+        }
+        for mania in &self.mania {
+            sections.push(("Mania", mania.to_string()));
+        }
+        for (name, lines) in &self.unknown_sections {
+            sections.push((name, lines.join("\n")));
+        }
+
+        write!(
+            f,
+            "{}",
+            sections
+                .iter()
+                .map(|(name, content)| format!("[{name}]\n{content}"))
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        )
+    }
+}
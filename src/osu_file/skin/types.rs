@@ -0,0 +1,42 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::osu_file::Decimal;
+
+/// Version of the skin, which changes how some elements are drawn.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SkinVersion {
+    /// Always the newest version, written as `latest`.
+    Latest,
+    /// A version number such as `2.5`.
+    Number(Decimal),
+}
+
+impl Default for SkinVersion {
+    /// The version osu! uses for skins that don't define one.
+    fn default() -> Self {
+        SkinVersion::Number(Decimal::from("1.0"))
+    }
+}
+
+impl FromStr for SkinVersion {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("latest") {
+            Ok(SkinVersion::Latest)
+        } else {
+            Ok(SkinVersion::Number(s.parse()?))
+        }
+    }
+}
+
+impl Display for SkinVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkinVersion::Latest => write!(f, "latest"),
+            SkinVersion::Number(version) => write!(f, "{version}"),
+        }
+    }
+}
@@ -22,6 +22,7 @@ pub use error::*;
 pub use types::*;
 
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TimingPoints(pub Vec<TimingPoint>);
 
 impl VersionedFromStr for TimingPoints {
@@ -41,7 +42,7 @@ impl VersionedFromStr for TimingPoints {
             )?);
         }
 
-        if let Some(s) = timing_points.get(0) {
+        if let Some(s) = timing_points.first() {
             if s.is_some() {
                 Ok(Some(TimingPoints(
                     timing_points
@@ -80,6 +81,7 @@ impl VersionedDefault for TimingPoints {
 /// Each timing point influences a specified portion of the map, commonly called a `timing section`.
 /// The .osu file format requires these to be sorted in chronological order.
 #[derive(Clone, Hash, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TimingPoint {
     // for some reason decimal is parsed anyway in the beatmap???
     time: Decimal,
@@ -194,10 +196,20 @@ impl TimingPoint {
         &self.time
     }
 
+    /// Get a mutable reference to the timing point's start time.
+    pub fn time_mut(&mut self) -> &mut Decimal {
+        &mut self.time
+    }
+
     pub fn beat_length(&self) -> &Decimal {
         &self.beat_length
     }
 
+    /// Get a mutable reference to the timing point's beat length.
+    pub fn beat_length_mut(&mut self) -> &mut Decimal {
+        &mut self.beat_length
+    }
+
     /// Set the timing point's start time.
     pub fn set_time(&mut self, time: Integer) {
         self.time = time.into();
@@ -5,6 +5,7 @@ use crate::osu_file::VersionedFromStr;
 
 /// Default sample set for hitobjects.
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum SampleSet {
     /// Beatmap's default.
@@ -73,6 +74,7 @@ impl VersionedToString for SampleSet {
 /// - The unused bits will come in effect when to_string is called.
 /// - You can clear the unused bits by calling [`Effects::clear_unused_bits`].
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Effects(u32);
 
 impl VersionedFromStr for Effects {
@@ -146,6 +148,7 @@ impl Effects {
 
 /// Custom sample index for hitobjects.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum SampleIndex {
     /// Osu!'s default hitsounds.
@@ -202,6 +205,7 @@ impl VersionedDefault for SampleIndex {
 
 /// The volume percentage in the range of 0 ~ 100.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Volume(Integer);
 
 impl VersionedFromStr for Volume {
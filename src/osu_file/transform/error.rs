@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+/// Error used when a transform can't be applied to an [`OsuFile`][crate::OsuFile].
+pub enum TransformError {
+    /// The rate factor is zero or negative.
+    #[error("The rate factor must be greater than zero")]
+    InvalidRate,
+    /// Sliders can't be transformed with a non-uniform scale or a skew, as their length can't be kept.
+    #[error("Sliders can only be transformed with a uniform scale")]
+    NonUniformScale,
+}
@@ -0,0 +1,285 @@
+use std::ops::RangeBounds;
+
+use either::Either;
+use rust_decimal::prelude::FromPrimitive;
+
+use crate::osu_file::hitobjects::{HitObject, HitObjectParams, HitObjects, SlideParams};
+use crate::osu_file::{Decimal, Position};
+
+use super::TransformError;
+
+/// Width of the playfield in `osu!pixels`.
+pub const PLAYFIELD_WIDTH: u32 = 512;
+/// Height of the playfield in `osu!pixels`.
+pub const PLAYFIELD_HEIGHT: u32 = 384;
+
+const CENTER_X: f64 = PLAYFIELD_WIDTH as f64 / 2.0;
+const CENTER_Y: f64 = PLAYFIELD_HEIGHT as f64 / 2.0;
+
+/// Relative tolerance used to tell if a transform scales every direction the same.
+const SCALE_EPSILON: f64 = 1e-9;
+
+/// An affine transform of positions in `osu!pixels`.
+/// - Transforms can be chained with [`then`][Self::then].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Affine {
+    /// `[[a, b], [c, d]]` where `x' = a * x + b * y + tx` and `y' = c * x + d * y + ty`.
+    pub matrix: [[f64; 2]; 2],
+    /// `(tx, ty)`.
+    pub translation: (f64, f64),
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Affine {
+    /// Transform that leaves positions as is.
+    pub const IDENTITY: Affine = Affine {
+        matrix: [[1.0, 0.0], [0.0, 1.0]],
+        translation: (0.0, 0.0),
+    };
+
+    /// Mirrors positions on the vertical center line of the playfield.
+    pub fn flip_horizontal() -> Self {
+        Self {
+            matrix: [[-1.0, 0.0], [0.0, 1.0]],
+            translation: (PLAYFIELD_WIDTH as f64, 0.0),
+        }
+    }
+
+    /// Mirrors positions on the horizontal center line of the playfield, the same as the `Hard Rock` mod.
+    pub fn flip_vertical() -> Self {
+        Self {
+            matrix: [[1.0, 0.0], [0.0, -1.0]],
+            translation: (0.0, PLAYFIELD_HEIGHT as f64),
+        }
+    }
+
+    /// Rotates positions clockwise around the playfield center by `degrees`.
+    pub fn rotate(degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+
+        Self {
+            matrix: [[cos, -sin], [sin, cos]],
+            translation: (
+                CENTER_X - cos * CENTER_X + sin * CENTER_Y,
+                CENTER_Y - sin * CENTER_X - cos * CENTER_Y,
+            ),
+        }
+    }
+
+    /// Scales positions from the playfield center.
+    pub fn scale(x: f64, y: f64) -> Self {
+        Self {
+            matrix: [[x, 0.0], [0.0, y]],
+            translation: (CENTER_X - x * CENTER_X, CENTER_Y - y * CENTER_Y),
+        }
+    }
+
+    /// Moves positions by `x` and `y`.
+    pub fn translate(x: f64, y: f64) -> Self {
+        Self {
+            matrix: Self::IDENTITY.matrix,
+            translation: (x, y),
+        }
+    }
+
+    /// Returns a transform that applies `self` and then `other`.
+    pub fn then(self, other: Affine) -> Self {
+        let [[a, b], [c, d]] = self.matrix;
+        let [[oa, ob], [oc, od]] = other.matrix;
+        let (tx, ty) = self.translation;
+        let (otx, oty) = other.translation;
+
+        Self {
+            matrix: [
+                [oa * a + ob * c, oa * b + ob * d],
+                [oc * a + od * c, oc * b + od * d],
+            ],
+            translation: (oa * tx + ob * ty + otx, oc * tx + od * ty + oty),
+        }
+    }
+
+    /// Factor that lengths are scaled by, if the transform scales every direction the same.
+    /// - Returns `None` for non-uniform scales and skews, which don't keep the shape of slider curves.
+    pub fn uniform_scale(&self) -> Option<f64> {
+        let [[a, b], [c, d]] = self.matrix;
+        let x = (a * a + c * c).sqrt();
+        let y = (b * b + d * d).sqrt();
+        let tolerance = SCALE_EPSILON * x.max(y);
+
+        ((x - y).abs() <= tolerance && (a * b + c * d).abs() <= tolerance * x.max(y)).then_some(x)
+    }
+
+    /// Transforms the point.
+    pub fn apply_point(&self, x: f64, y: f64) -> (f64, f64) {
+        let [[a, b], [c, d]] = self.matrix;
+        let (tx, ty) = self.translation;
+
+        (a * x + b * y + tx, c * x + d * y + ty)
+    }
+
+    /// Transforms the position, rounding it to whole `osu!pixels`.
+    /// - If `clamp` is `true`, the position is kept inside of the playfield.
+    /// - Positions that aren't valid numbers are left as is.
+    pub fn apply(&self, position: &mut Position, clamp: bool) {
+        let (Some(x), Some(y)) = (position.x.to_f64(), position.y.to_f64()) else {
+            return;
+        };
+        let (mut x, mut y) = self.apply_point(x, y);
+
+        if clamp {
+            x = x.clamp(0.0, PLAYFIELD_WIDTH as f64);
+            y = y.clamp(0.0, PLAYFIELD_HEIGHT as f64);
+        }
+
+        position.x = f64_to_decimal(x);
+        position.y = f64_to_decimal(y);
+    }
+}
+
+fn f64_to_decimal(value: f64) -> Decimal {
+    // rounding also gets rid of `-0`
+    rust_decimal::Decimal::from_f64(value.round() + 0.0)
+        .unwrap_or_default()
+        .into()
+}
+
+fn in_range<R: RangeBounds<rust_decimal::Decimal>>(hitobject: &HitObject, range: &R) -> bool {
+    match hitobject.time.get() {
+        Either::Left(time) => range.contains(time),
+        Either::Right(_) => false,
+    }
+}
+
+/// Moves the slider by whole `osu!pixels` so its path is inside of the playfield.
+fn clamp_slider(position: &mut Position, slider: &mut SlideParams) {
+    let points = match slider.path(position) {
+        Some(path) => path.points().to_vec(),
+        None => std::iter::once(&*position)
+            .chain(slider.curve_points.iter().map(|curve_point| &curve_point.0))
+            .filter_map(|position| Some((position.x.to_f64()?, position.y.to_f64()?)))
+            .collect(),
+    };
+
+    // how much to move so `min..=max` is inside of `0..=size`, keeping `min` inside if it doesn't fit
+    let shift = |min: f64, max: f64, size: f64| {
+        if max > size {
+            (size - max).floor().max(-min.floor())
+        } else if min < 0.0 {
+            (-min).ceil()
+        } else {
+            0.0
+        }
+    };
+    if points.is_empty() {
+        return;
+    }
+    let (min_x, max_x, min_y, max_y) = points.iter().fold(
+        (
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(min_x, max_x, min_y, max_y), &(x, y)| {
+            (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y))
+        },
+    );
+
+    let translate = Affine::translate(
+        shift(min_x, max_x, PLAYFIELD_WIDTH as f64),
+        shift(min_y, max_y, PLAYFIELD_HEIGHT as f64),
+    );
+    if translate == Affine::IDENTITY {
+        return;
+    }
+
+    translate.apply(position, false);
+    for curve_point in slider.curve_points.iter_mut() {
+        translate.apply(&mut curve_point.0, false);
+    }
+}
+
+impl HitObjects {
+    /// Applies the transform to the position and slider curve points of every hitobject with a time inside of `range`.
+    /// - Use `..` as the range to transform every hitobject.
+    /// - If `clamp` is `true`, positions are kept inside of the 512x384 playfield.
+    ///   Sliders are moved as a whole until their path is inside of the playfield, or starts at its left or top edge if it's bigger than the playfield.
+    /// - Slider lengths are scaled by the [`uniform_scale`][Affine::uniform_scale] of the transform, so sliders keep their shape and end position.
+    /// - Returns an error without changing anything if a slider is in `range` and the transform doesn't have a uniform scale.
+    pub fn transform<R>(
+        &mut self,
+        transform: &Affine,
+        range: R,
+        clamp: bool,
+    ) -> Result<(), TransformError>
+    where
+        R: RangeBounds<rust_decimal::Decimal>,
+    {
+        let scale = transform.uniform_scale();
+        let has_slider = self
+            .0
+            .iter()
+            .any(|h| in_range(h, &range) && matches!(h.obj_params, HitObjectParams::Slider(_)));
+        if has_slider && scale.is_none() {
+            return Err(TransformError::NonUniformScale);
+        }
+
+        for hitobject in self.0.iter_mut().filter(|h| in_range(h, &range)) {
+            let HitObjectParams::Slider(slider) = &mut hitobject.obj_params else {
+                transform.apply(&mut hitobject.position, clamp);
+                continue;
+            };
+
+            // sliders are clamped after the transform, so their shape is kept
+            transform.apply(&mut hitobject.position, false);
+            for curve_point in slider.curve_points.iter_mut() {
+                transform.apply(&mut curve_point.0, false);
+            }
+
+            if let (Some(scale), Some(length)) = (scale, slider.length.to_f64()) {
+                if (scale - 1.0).abs() > SCALE_EPSILON {
+                    slider.length = rust_decimal::Decimal::from_f64(length * scale)
+                        .map(|length| length.round_dp(6).normalize().into())
+                        .unwrap_or_else(|| slider.length.clone());
+                }
+            }
+
+            if clamp {
+                clamp_slider(&mut hitobject.position, slider);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mirrors the columns of osu!mania hitobjects with a time inside of `range`.
+    /// - `keys` is the key count of the map, which is the `CircleSize` in `Difficulty`.
+    /// - Hitobjects are moved to the center of the mirrored column.
+    pub fn mirror_mania_columns<R>(&mut self, keys: u8, range: R)
+    where
+        R: RangeBounds<rust_decimal::Decimal>,
+    {
+        if keys == 0 {
+            return;
+        }
+
+        let keys = keys as f64;
+        let column_width = PLAYFIELD_WIDTH as f64 / keys;
+
+        for hitobject in self.0.iter_mut().filter(|h| in_range(h, &range)) {
+            let Some(x) = hitobject.position.x.to_f64() else {
+                continue;
+            };
+
+            let column = (x / column_width).floor().clamp(0.0, keys - 1.0);
+            let mirrored = keys - 1.0 - column;
+
+            hitobject.position.x = f64_to_decimal(((mirrored + 0.5) * column_width).floor());
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Transforms that edit an [`OsuFile`][super::OsuFile] as a whole, or its hitobjects.
+
+pub mod error;
+mod geometry;
+mod resnap;
+mod time;
+
+pub use error::*;
+pub use geometry::*;
+pub use resnap::*;
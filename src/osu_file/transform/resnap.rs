@@ -0,0 +1,279 @@
+use either::Either;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::osu_file::hitobjects::HitObjectParams;
+use crate::osu_file::timingpoints::TimingPoints;
+use crate::osu_file::OsuFile;
+
+use super::time::round_ms;
+
+/// Beat divisor used for resnapping when the `Editor` section doesn't have one.
+pub const DEFAULT_BEAT_DIVISOR: u32 = 4;
+
+/// Decimal places kept for changed slider lengths.
+const LENGTH_DECIMAL_PLACES: u32 = 8;
+
+/// What was moved by [`OsuFile::resnap`].
+/// - The indexes are of the hitobjects and timing points in the file.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SnapTarget {
+    /// Start time of a hitobject.
+    HitObject(usize),
+    /// End time of a slider, spinner or osu!mania hold.
+    /// - Sliders have their `length` changed to end at the new time.
+    HitObjectEnd(usize),
+    /// Time of an inherited timing point.
+    TimingPoint(usize),
+}
+
+/// A single time that was moved by [`OsuFile::resnap`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct SnapMove {
+    pub target: SnapTarget,
+    pub old: Decimal,
+    pub new: Decimal,
+}
+
+impl SnapMove {
+    /// How far the time was moved in milliseconds, which is negative if it was moved back.
+    pub fn distance(&self) -> Decimal {
+        self.new - self.old
+    }
+}
+
+/// Every move made by [`OsuFile::resnap`], in the order they were made.
+/// - Inherited timing points are moved first, then hitobjects in order.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct ResnapReport {
+    pub moves: Vec<SnapMove>,
+}
+
+impl ResnapReport {
+    fn push(&mut self, target: SnapTarget, old: Decimal, new: Decimal) {
+        if old != new {
+            self.moves.push(SnapMove { target, old, new });
+        }
+    }
+
+    /// Returns `true` if nothing was moved.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// The largest distance of a move in milliseconds.
+    pub fn max_distance(&self) -> Decimal {
+        self.moves
+            .iter()
+            .map(|m| m.distance().abs())
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// Snaps the time to the closest `1 / divisor` beat of the uninherited timing point that is active at the time.
+/// - `uninherited` is a list of `(time, beat_length)` sorted by time.
+/// - The result is rounded to whole milliseconds.
+pub(crate) fn snap_time(
+    uninherited: &[(Decimal, Decimal)],
+    time: Decimal,
+    divisor: u32,
+) -> Decimal {
+    let Some((point_time, beat_length)) = active_point(uninherited, time) else {
+        return time;
+    };
+
+    if beat_length.is_sign_negative() || beat_length.is_zero() || divisor == 0 {
+        return time;
+    }
+
+    let tick = beat_length / Decimal::from(divisor);
+    let ticks = ((time - point_time) / tick).round();
+
+    round_ms(point_time + ticks * tick)
+}
+
+/// Snaps the time to the closest snap out of all the divisors.
+fn snap_time_divisors(
+    uninherited: &[(Decimal, Decimal)],
+    time: Decimal,
+    divisors: &[u32],
+) -> Decimal {
+    divisors
+        .iter()
+        .map(|divisor| snap_time(uninherited, time, *divisor))
+        .min_by_key(|snapped| (snapped - time).abs())
+        .unwrap_or(time)
+}
+
+/// The point that is active at the time, or the first one if the time is before every point.
+pub(crate) fn active_point<T: Copy>(points: &[(Decimal, T)], time: Decimal) -> Option<(Decimal, T)> {
+    points
+        .iter()
+        .rev()
+        .find(|(point_time, _)| *point_time <= time)
+        .or_else(|| points.first())
+        .copied()
+}
+
+/// Returns `(time, beat_length)` of every uninherited timing point with valid values, sorted by time.
+pub(crate) fn uninherited_timing(timing_points: &Option<TimingPoints>) -> Vec<(Decimal, Decimal)> {
+    let mut uninherited = timing_points
+        .iter()
+        .flat_map(|t| t.0.iter())
+        .filter(|t| t.uninherited())
+        .filter_map(|t| match (t.time().get(), t.beat_length().get()) {
+            (Either::Left(time), Either::Left(beat_length)) => Some((*time, *beat_length)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    uninherited.sort_by_key(|(time, _)| *time);
+    uninherited
+}
+
+/// Returns `(time, slider_velocity_multiplier)` of every timing point with valid values, sorted by time.
+/// - Uninherited timing points reset the multiplier to 1.
+pub(crate) fn slider_velocity_timing(timing_points: &Option<TimingPoints>) -> Vec<(Decimal, Decimal)> {
+    let mut velocities = timing_points
+        .iter()
+        .flat_map(|t| t.0.iter())
+        .filter_map(|t| {
+            let time = match t.time().get() {
+                Either::Left(time) => *time,
+                Either::Right(_) => return None,
+            };
+            let multiplier = if t.uninherited() {
+                Decimal::ONE
+            } else {
+                t.calc_slider_velocity_multiplier()?
+                    .clamp(dec!(0.1), dec!(10))
+            };
+
+            Some((time, multiplier))
+        })
+        .collect::<Vec<_>>();
+
+    // stable sort so inherited points after an uninherited point at the same time stay in effect
+    velocities.sort_by_key(|(time, _)| *time);
+    velocities
+}
+
+impl OsuFile {
+    /// The `Editor` beat divisor as a whole number, or [`DEFAULT_BEAT_DIVISOR`].
+    pub fn beat_divisor(&self) -> u32 {
+        self.editor
+            .as_ref()
+            .and_then(|e| e.beat_divisor.as_ref())
+            .and_then(|d| match d.0.get() {
+                Either::Left(d) => d.to_u32(),
+                Either::Right(_) => None,
+            })
+            .filter(|d| *d > 0)
+            .unwrap_or(DEFAULT_BEAT_DIVISOR)
+    }
+
+    /// Moves hitobjects, their end times and inherited timing points to the closest snap of the uninherited timing points.
+    /// - Each time is snapped to the closest `1 / divisor` beat out of all `divisors`.
+    /// - If `divisors` is empty, the `Editor` beat divisor is used.
+    /// - Slider ends are snapped by changing the slider `length`, which needs `SliderMultiplier` in `Difficulty`.
+    /// - Times that failed to parse as a decimal are left as is.
+    pub fn resnap(&mut self, divisors: &[u32]) -> ResnapReport {
+        let divisors = if divisors.is_empty() {
+            vec![self.beat_divisor()]
+        } else {
+            divisors.to_vec()
+        };
+        let uninherited = uninherited_timing(&self.timing_points);
+        let mut report = ResnapReport::default();
+
+        let snap = |report: &mut ResnapReport, time: &mut crate::osu_file::Decimal, target| {
+            if let Either::Left(old) = time.get_mut() {
+                let new = snap_time_divisors(&uninherited, *old, &divisors);
+                report.push(target, *old, new);
+                *old = new;
+            }
+        };
+
+        if let Some(timing_points) = &mut self.timing_points {
+            for (i, timing_point) in timing_points.0.iter_mut().enumerate() {
+                if !timing_point.uninherited() {
+                    snap(
+                        &mut report,
+                        timing_point.time_mut(),
+                        SnapTarget::TimingPoint(i),
+                    );
+                }
+            }
+        }
+
+        let velocities = slider_velocity_timing(&self.timing_points);
+        let slider_multiplier = self
+            .difficulty
+            .as_ref()
+            .and_then(|d| d.slider_multiplier.as_ref())
+            .and_then(|m| match m.0.get() {
+                Either::Left(m) => Some(*m),
+                Either::Right(_) => None,
+            })
+            .filter(|m| m.is_sign_positive() && !m.is_zero());
+
+        if let Some(hitobjects) = &mut self.hitobjects {
+            for (i, hitobject) in hitobjects.0.iter_mut().enumerate() {
+                let old_start = match hitobject.time.get() {
+                    Either::Left(time) => *time,
+                    Either::Right(_) => continue,
+                };
+
+                snap(&mut report, &mut hitobject.time, SnapTarget::HitObject(i));
+
+                match &mut hitobject.obj_params {
+                    HitObjectParams::Spinner { end_time }
+                    | HitObjectParams::OsuManiaHold { end_time } => {
+                        snap(&mut report, end_time, SnapTarget::HitObjectEnd(i))
+                    }
+                    HitObjectParams::Slider(slider) => {
+                        let (Some(slider_multiplier), Either::Left(length)) =
+                            (slider_multiplier, slider.length.get_mut())
+                        else {
+                            continue;
+                        };
+                        let (Some((_, beat_length)), Some((_, multiplier))) = (
+                            active_point(&uninherited, old_start),
+                            active_point(&velocities, old_start),
+                        ) else {
+                            continue;
+                        };
+                        if beat_length.is_sign_negative() || beat_length.is_zero() {
+                            continue;
+                        }
+
+                        // distance travelled in a single beat
+                        let beat_distance = slider_multiplier * dec!(100) * multiplier;
+                        let slides = Decimal::from(slider.slides.max(1));
+                        let old_end = old_start + *length * slides / beat_distance * beat_length;
+                        let new_start = match hitobject.time.get() {
+                            Either::Left(time) => *time,
+                            Either::Right(_) => continue,
+                        };
+                        let old_end = round_ms(old_end);
+                        let new_end = snap_time_divisors(&uninherited, old_end, &divisors);
+
+                        if new_end > new_start && (new_end != old_end || new_start != old_start) {
+                            *length = ((new_end - new_start) / slides / beat_length
+                                * beat_distance)
+                                .round_dp(LENGTH_DECIMAL_PLACES)
+                                .normalize();
+                            report.push(SnapTarget::HitObjectEnd(i), old_end, new_end);
+                        }
+                    }
+                    HitObjectParams::HitCircle => (),
+                }
+            }
+        }
+
+        report
+    }
+}
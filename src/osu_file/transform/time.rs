@@ -0,0 +1,238 @@
+use either::Either;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::RoundingStrategy;
+
+use crate::osu_file::events::storyboard::cmds::{Command, CommandProperties};
+use crate::osu_file::events::storyboard::sprites::ObjectType;
+use crate::osu_file::events::{Event, Events};
+use crate::osu_file::hitobjects::HitObjectParams;
+use crate::osu_file::{Decimal, Integer, OsuFile};
+
+use super::TransformError;
+
+/// Decimal places kept for scaled beat lengths, so they don't get written with the full precision of `rust_decimal`.
+const BEAT_LENGTH_DECIMAL_PLACES: u32 = 12;
+
+/// Maps absolute times with `(time + shift) / rate`, and durations with `duration / rate`.
+struct TimeMap {
+    shift: rust_decimal::Decimal,
+    rate: rust_decimal::Decimal,
+}
+
+impl TimeMap {
+    fn time(&self, time: rust_decimal::Decimal) -> rust_decimal::Decimal {
+        round_ms((time + self.shift) / self.rate)
+    }
+
+    fn duration(&self, duration: rust_decimal::Decimal) -> rust_decimal::Decimal {
+        round_ms(duration / self.rate)
+    }
+
+    fn time_integer(&self, time: Integer) -> Integer {
+        to_integer(self.time(time.into()), time)
+    }
+
+    fn duration_integer(&self, duration: Integer) -> Integer {
+        to_integer(self.duration(duration.into()), duration)
+    }
+
+    fn time_decimal(&self, time: &mut Decimal) {
+        if let Either::Left(value) = time.get_mut() {
+            *value = self.time(*value);
+        }
+    }
+}
+
+pub(super) fn round_ms(time: rust_decimal::Decimal) -> rust_decimal::Decimal {
+    time.round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+}
+
+fn to_integer(value: rust_decimal::Decimal, fallback: Integer) -> Integer {
+    value.to_i32().unwrap_or(fallback)
+}
+
+impl OsuFile {
+    /// Moves every time in the file by `ms` milliseconds.
+    /// - This includes hitobjects, timing points, the preview time, bookmarks, events and storyboard commands.
+    /// - Times inside of storyboard loops and triggers are relative and are left as is.
+    /// - Times that failed to parse as a decimal are left as is.
+    pub fn shift_time(&mut self, ms: Integer) {
+        self.map_times(&TimeMap {
+            shift: ms.into(),
+            rate: rust_decimal::Decimal::ONE,
+        });
+    }
+
+    /// Changes the speed of the map by `factor`, where `1.5` makes the map 1.5x faster.
+    /// - Every time and duration in the file is divided by `factor` and rounded to whole milliseconds.
+    /// - Uninherited timing points have their beat length scaled, so slider lengths stay valid as the slider velocity is unchanged.
+    /// - If `resnap` is `true`, [`resnap`][Self::resnap] is called with the `Editor` beat divisor afterwards.
+    pub fn scale_rate(
+        &mut self,
+        factor: rust_decimal::Decimal,
+        resnap: bool,
+    ) -> Result<(), TransformError> {
+        if factor <= rust_decimal::Decimal::ZERO {
+            return Err(TransformError::InvalidRate);
+        }
+
+        let map = TimeMap {
+            shift: rust_decimal::Decimal::ZERO,
+            rate: factor,
+        };
+        self.map_times(&map);
+
+        if let Some(timing_points) = &mut self.timing_points {
+            for timing_point in timing_points.0.iter_mut() {
+                if !timing_point.uninherited() {
+                    continue;
+                }
+                if let Either::Left(beat_length) = timing_point.beat_length_mut().get_mut() {
+                    *beat_length = (*beat_length / factor)
+                        .round_dp(BEAT_LENGTH_DECIMAL_PLACES)
+                        .normalize();
+                }
+            }
+        }
+
+        if resnap {
+            self.resnap(&[]);
+        }
+
+        Ok(())
+    }
+
+    fn map_times(&mut self, map: &TimeMap) {
+        if let Some(general) = &mut self.general {
+            if let Some(preview_time) = &mut general.preview_time {
+                // -1 means there is no preview time
+                if preview_time.0 != -1 {
+                    preview_time.0 = map.time_integer(preview_time.0);
+                }
+            }
+            if let Some(bookmarks) = &mut general.editor_bookmarks {
+                for bookmark in bookmarks.0.iter_mut() {
+                    *bookmark = map.time_integer(*bookmark);
+                }
+            }
+        }
+
+        if let Some(bookmarks) = self.editor.as_mut().and_then(|e| e.bookmarks.as_mut()) {
+            for bookmark in bookmarks.0.iter_mut() {
+                *bookmark = map.time_integer(*bookmark);
+            }
+        }
+
+        if let Some(events) = &mut self.events {
+            map_events(events, map);
+        }
+        if let Some(events) = self.osb.as_mut().and_then(|osb| osb.events.as_mut()) {
+            map_events(events, map);
+        }
+
+        if let Some(timing_points) = &mut self.timing_points {
+            for timing_point in timing_points.0.iter_mut() {
+                map.time_decimal(timing_point.time_mut());
+            }
+        }
+
+        if let Some(hitobjects) = &mut self.hitobjects {
+            for hitobject in hitobjects.0.iter_mut() {
+                map.time_decimal(&mut hitobject.time);
+
+                if let HitObjectParams::Spinner { end_time }
+                | HitObjectParams::OsuManiaHold { end_time } = &mut hitobject.obj_params
+                {
+                    map.time_decimal(end_time);
+                }
+            }
+        }
+    }
+}
+
+fn map_events(events: &mut Events, map: &TimeMap) {
+    for event in events.0.iter_mut() {
+        let commands = match event {
+            Event::Comment(_) => None,
+            Event::Background(background) => {
+                background.start_time = map.time_integer(background.start_time);
+                Some(&mut background.commands)
+            }
+            Event::Video(video) => {
+                video.start_time = map.time_integer(video.start_time);
+                Some(&mut video.commands)
+            }
+            Event::Break(break_) => {
+                break_.start_time = map.time_integer(break_.start_time);
+                break_.end_time = map.time_integer(break_.end_time);
+                None
+            }
+            Event::ColourTransformation(colour_trans) => {
+                colour_trans.start_time = map.time_integer(colour_trans.start_time);
+                None
+            }
+            Event::SpriteLegacy(sprite) => Some(&mut sprite.commands),
+            Event::AnimationLegacy(animation) => Some(&mut animation.commands),
+            Event::SampleLegacy(sample) => {
+                map.time_decimal(&mut sample.time);
+                Some(&mut sample.commands)
+            }
+            Event::StoryboardObject(object) => {
+                if let ObjectType::Animation(animation) = &mut object.object_type {
+                    animation.frame_delay /= map.rate;
+                }
+                Some(&mut object.commands)
+            }
+            Event::AudioSample(audio_sample) => {
+                audio_sample.time = map.time_integer(audio_sample.time);
+                None
+            }
+        };
+
+        if let Some(commands) = commands {
+            map_commands(commands, map, false);
+        }
+    }
+}
+
+/// Maps the command times, where commands inside of loops and triggers are relative to their parent.
+fn map_commands(commands: &mut [Command], map: &TimeMap, relative: bool) {
+    let map_time = |time: Integer| {
+        if relative {
+            map.duration_integer(time)
+        } else {
+            map.time_integer(time)
+        }
+    };
+
+    for command in commands {
+        if let Some(start_time) = &mut command.start_time {
+            *start_time = map_time(*start_time);
+        }
+
+        match &mut command.properties {
+            CommandProperties::Loop { commands, .. } => map_commands(commands, map, true),
+            CommandProperties::Trigger {
+                end_time, commands, ..
+            } => {
+                if let Some(end_time) = end_time {
+                    *end_time = map_time(*end_time);
+                }
+                map_commands(commands, map, true);
+            }
+            CommandProperties::Fade { end_time, .. }
+            | CommandProperties::Move { end_time, .. }
+            | CommandProperties::MoveX { end_time, .. }
+            | CommandProperties::MoveY { end_time, .. }
+            | CommandProperties::Scale { end_time, .. }
+            | CommandProperties::VectorScale { end_time, .. }
+            | CommandProperties::Rotate { end_time, .. }
+            | CommandProperties::Colour { end_time, .. }
+            | CommandProperties::Parameter { end_time, .. } => {
+                if let Some(end_time) = end_time {
+                    *end_time = map_time(*end_time);
+                }
+            }
+        }
+    }
+}
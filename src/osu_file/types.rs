@@ -13,10 +13,13 @@ pub type Integer = i32;
 
 pub const LATEST_VERSION: Version = 14;
 pub const MIN_VERSION: Version = 3;
+/// Version osu!lazer writes, which has additions that stable doesn't read.
+pub const LAZER_VERSION: Version = 128;
 
 pub type Version = u8;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// The position of something in `osu!pixels` with the `x` `y` form.
 pub struct Position {
     /// x coordinate.
@@ -199,6 +202,7 @@ pub trait VersionedDefault: Sized {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// File path type that is used in most of the crate.
 pub struct FilePath(PathBuf);
 
@@ -304,6 +308,15 @@ impl Decimal {
     pub fn get_mut(&mut self) -> &mut Either<rust_decimal::Decimal, String> {
         &mut self.0
     }
+
+    /// Converts the value to a `f64`.
+    /// - Returns `None` if the value is a `String` that isn't a valid number.
+    pub fn to_f64(&self) -> Option<f64> {
+        match &self.0 {
+            Either::Left(value) => rust_decimal::prelude::ToPrimitive::to_f64(value),
+            Either::Right(value) => value.parse().ok(),
+        }
+    }
 }
 
 impl FromStr for Decimal {
@@ -320,6 +333,14 @@ impl Display for Decimal {
     }
 }
 
+/// Serialized as the string it's written as.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Decimal {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 impl From<rust_decimal::Decimal> for Decimal {
     fn from(value: rust_decimal::Decimal) -> Self {
         Self(Either::Left(value))
@@ -133,8 +133,10 @@ where
     preceded(space0, eof)
 }
 
+type SquareSection<'a> = (&'a str, &'a str, &'a str, &'a str);
+
 pub fn square_section<'a>(
-) -> impl FnMut(&'a str) -> IResult<&'a str, (&str, &str, &str, &str), nom::error::Error<&str>> {
+) -> impl FnMut(&'a str) -> IResult<&'a str, SquareSection<'a>, nom::error::Error<&'a str>> {
     let section_open = tag("[");
     let section_close = tag("]");
     let section_name_inner = take_till(|c: char| c == ']' || c == '\n');
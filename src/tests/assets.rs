@@ -0,0 +1,100 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::assets::{normalize_path, AssetKind, AssetReport};
+use crate::osu_file::OsuFile;
+
+const OSU: &str = r#"osu file format v14
+
+[General]
+AudioFilename: Audio.mp3
+
+[Events]
+0,0,"BG.jpg",0,0
+Video,500,"video.mp4"
+Sprite,Foreground,Centre,"SB\Star.png",320,240
+ F,0,0,1000,1,0
+Animation,Foreground,Centre,sb/anim/frame.png,320,240,2,100,LoopForever
+ F,0,0,1000,1,0
+Sample,1000,0,"sb\hit.ogg",70
+
+[TimingPoints]
+0,500,4,2,2,60,1,0
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:Clap.wav
+"#;
+
+#[test]
+fn normalize() {
+    assert_eq!(
+        normalize_path("\"SB\\Folder//Star.PNG\""),
+        "sb/folder/star.png"
+    );
+    assert_eq!(normalize_path("./bg.jpg"), "bg.jpg");
+}
+
+#[test]
+fn inventory() {
+    let osu_file = OSU.parse::<OsuFile>().unwrap();
+    let inventory = osu_file.assets();
+
+    let assets = inventory
+        .assets()
+        .map(|asset| (asset.path.as_str(), asset.kinds.iter().copied().collect()))
+        .collect::<Vec<(_, Vec<_>)>>();
+    assert_eq!(
+        assets,
+        vec![
+            ("Audio.mp3", vec![AssetKind::Audio]),
+            ("BG.jpg", vec![AssetKind::Background]),
+            ("Clap.wav", vec![AssetKind::HitSample]),
+            ("sb/anim/frame0.png", vec![AssetKind::AnimationFrame]),
+            ("sb/anim/frame1.png", vec![AssetKind::AnimationFrame]),
+            ("sb\\hit.ogg", vec![AssetKind::StoryboardSample]),
+            ("SB\\Star.png", vec![AssetKind::Sprite]),
+            ("video.mp4", vec![AssetKind::Video]),
+        ]
+    );
+    assert!(inventory.get("sb/star.PNG").is_some());
+
+    let report = inventory.report([
+        "audio.mp3",
+        "bg.JPG",
+        "clap.wav",
+        "sb/anim/frame0.png",
+        "SB\\hit.ogg",
+        "sb/star.png",
+        "sb/unused.png",
+        "soft-hitclap2.wav",
+        "soft-hitclap3.wav",
+        "map [Hard].osu",
+    ]);
+    assert_eq!(
+        report,
+        AssetReport {
+            missing: vec!["sb/anim/frame1.png".to_string(), "video.mp4".to_string()],
+            unused: vec!["sb/unused.png".to_string(), "soft-hitclap3.wav".to_string()],
+        }
+    );
+}
+
+#[test]
+fn check_folder() {
+    let folder =
+        std::env::temp_dir().join(format!("osu-file-parser-assets-{}", std::process::id()));
+    std::fs::create_dir_all(folder.join("SB")).unwrap();
+    for file in ["audio.mp3", "bg.jpg", "SB/star.png", "SB/extra.png"] {
+        std::fs::write(folder.join(file), []).unwrap();
+    }
+
+    let report = OSU
+        .parse::<OsuFile>()
+        .unwrap()
+        .assets()
+        .check_folder(&folder);
+    std::fs::remove_dir_all(&folder).unwrap();
+
+    let report = report.unwrap();
+    assert_eq!(report.unused, vec!["SB/extra.png".to_string()]);
+    assert_eq!(report.missing.len(), 5);
+}
@@ -0,0 +1,158 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::beatmapset::{BeatmapSet, BeatmapSetError, Inconsistency, SetField};
+use crate::osu_file::diff::Section;
+
+fn osu(version: &str, preview_time: i32, background: &str) -> String {
+    format!(
+        "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: {preview_time}
+
+[Metadata]
+Title:foo
+Artist:bar
+Creator:baz
+Version:{version}
+BeatmapSetID:1
+
+[Events]
+//Background and Video events
+0,0,\"{background}\",0,0
+//Break Periods
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:"
+    )
+}
+
+const OSB: &str = "[Events]
+//Background and Video events
+//Storyboard Layer 0 (Background)
+Sprite,Background,Centre,\"sb/star.png\",320,240
+ F,0,0,1000,0,1
+//Storyboard Layer 1 (Fail)
+//Storyboard Layer 2 (Pass)
+//Storyboard Layer 3 (Foreground)
+//Storyboard Layer 4 (Overlay)
+//Storyboard Sound Samples";
+
+#[test]
+fn open_and_check() {
+    let folder =
+        std::env::temp_dir().join(format!("osu-file-parser-beatmapset-{}", std::process::id()));
+    std::fs::create_dir_all(&folder).unwrap();
+    for (name, content) in [
+        ("bar - foo (baz) [Easy].osu", osu("Easy", 100, "bg.jpg")),
+        ("bar - foo (baz) [Hard].osu", osu("Hard", 200, "bg.jpg")),
+        (
+            "bar - foo (baz) [Insane].osu",
+            osu("Insane", 100, "bg2.jpg"),
+        ),
+        ("bar - foo (baz).osb", OSB.to_string()),
+        ("audio.mp3", String::new()),
+    ] {
+        std::fs::write(folder.join(name), content).unwrap();
+    }
+
+    let set = BeatmapSet::open(&folder).unwrap();
+    std::fs::remove_dir_all(&folder).unwrap();
+
+    assert_eq!(set.difficulties.len(), 3);
+    assert_eq!(set.osb_name.as_deref(), Some("bar - foo (baz).osb"));
+    assert!(set
+        .difficulties
+        .values()
+        .all(|o| o.osb == set.osb().cloned()));
+
+    assert_eq!(
+        set.check(),
+        vec![
+            Inconsistency {
+                field: SetField::Field {
+                    section: Section::General,
+                    key: "PreviewTime"
+                },
+                values: vec![
+                    (
+                        Some("100".to_string()),
+                        vec![
+                            "bar - foo (baz) [Easy].osu".to_string(),
+                            "bar - foo (baz) [Insane].osu".to_string()
+                        ]
+                    ),
+                    (
+                        Some("200".to_string()),
+                        vec!["bar - foo (baz) [Hard].osu".to_string()]
+                    ),
+                ],
+            },
+            Inconsistency {
+                field: SetField::Background,
+                values: vec![
+                    (
+                        Some("bg.jpg".to_string()),
+                        vec![
+                            "bar - foo (baz) [Easy].osu".to_string(),
+                            "bar - foo (baz) [Hard].osu".to_string()
+                        ]
+                    ),
+                    (
+                        Some("bg2.jpg".to_string()),
+                        vec!["bar - foo (baz) [Insane].osu".to_string()]
+                    ),
+                ],
+            },
+        ]
+    );
+}
+
+#[test]
+fn edits() {
+    let mut set = BeatmapSet::new();
+    for (name, preview_time, background) in [("Easy", 100, "bg.jpg"), ("Hard", 200, "bg2.jpg")] {
+        set.difficulties.insert(
+            format!("{name}.osu"),
+            osu(name, preview_time, background).parse().unwrap(),
+        );
+    }
+    set.difficulties
+        .get_mut("Easy.osu")
+        .unwrap()
+        .append_osb(OSB)
+        .unwrap();
+
+    set.sync_from("Easy.osu").unwrap();
+    assert_eq!(set.check(), Vec::new());
+
+    set.set_field(Section::Metadata, "Tags", Some("a b"))
+        .unwrap();
+    set.set_background("new bg.png");
+    for osu_file in set.difficulties.values() {
+        assert_eq!(
+            SetField::Field {
+                section: Section::Metadata,
+                key: "Tags"
+            }
+            .value(osu_file)
+            .as_deref(),
+            Some("a b")
+        );
+        assert!(osu_file.to_string().contains("0,0,\"new bg.png\",0,0"));
+    }
+
+    let err = set
+        .set_field(Section::General, "PreviewTime", Some("foo"))
+        .unwrap_err();
+    assert!(matches!(err, BeatmapSetError::InvalidField { .. }));
+    assert!(matches!(
+        set.set_field(Section::HitObjects, "foo", None),
+        Err(BeatmapSetError::NotKeyValueSection(Section::HitObjects))
+    ));
+    assert!(matches!(
+        set.sync_from("Normal.osu"),
+        Err(BeatmapSetError::UnknownDifficulty(_))
+    ));
+}
@@ -0,0 +1,121 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{
+    convert::{ConversionLoss, ConvertError},
+    diff::Section,
+    OsuFile,
+};
+
+const SMALL: &str = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: 1000
+OverlayPosition: Above
+
+[Events]
+2,5000,6000
+
+[TimingPoints]
+0,500,4,2,1,60,1,0
+
+[Colours]
+Combo1 : 255,0,0
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:";
+
+#[test]
+fn downgrade_reports_losses() {
+    let mut osu_file = SMALL.parse::<OsuFile>().unwrap();
+    let report = osu_file.convert_to(12).unwrap();
+
+    assert_eq!(
+        report.losses,
+        vec![ConversionLoss::Lost {
+            section: Section::General,
+            value: "OverlayPosition: Above".to_string(),
+        }]
+    );
+    assert_eq!(osu_file.version, 12);
+    assert_eq!(osu_file.general.unwrap().overlay_position, None);
+
+    let mut osu_file = SMALL.parse::<OsuFile>().unwrap();
+    let report = osu_file.convert_to(4).unwrap();
+
+    assert!(report.losses.contains(&ConversionLoss::Lost {
+        section: Section::Colours,
+        value: "Combo1 : 255,0,0".to_string(),
+    }));
+    // v4 timing points and hitobjects don't have the newer fields
+    assert!(report.losses.contains(&ConversionLoss::Approximated {
+        section: Section::HitObjects,
+        old: "256,192,1000,1,0,0:0:0:0:".to_string(),
+        new: "256,192,976,1,0".to_string(),
+    }));
+    assert_eq!(osu_file.colours, None);
+}
+
+#[test]
+fn time_offset_is_kept() {
+    let mut osu_file = SMALL.parse::<OsuFile>().unwrap();
+    let times = |osu_file: &OsuFile| {
+        (
+            osu_file.hitobjects.as_ref().unwrap().0[0].time.clone(),
+            osu_file.timing_points.as_ref().unwrap().0[0].time().clone(),
+        )
+    };
+    let original_times = times(&osu_file);
+
+    let report = osu_file.convert_to(3).unwrap();
+    assert!(!report.to_string().contains("PreviewTime"));
+    assert_eq!(times(&osu_file), original_times);
+    // every time is written with the offset, so the preview time stays on the hitobject
+    let written = osu_file.to_string();
+    assert!(written.contains("PreviewTime: 976"));
+    assert!(written.contains("\n-24,500"));
+    assert!(written.contains("256,192,976,1,0"));
+
+    assert!(osu_file.convert_to(14).unwrap().is_lossless());
+    assert_eq!(times(&osu_file), original_times);
+    assert!(osu_file.to_string().contains("PreviewTime: 1000"));
+}
+
+#[test]
+fn upgrade() {
+    let v4 = include_str!("./osu_files/files/v4.osu");
+    let mut osu_file = v4.parse::<OsuFile>().unwrap();
+    let report = osu_file.convert_to(14).unwrap();
+
+    assert_eq!(
+        report.losses,
+        vec![
+            ConversionLoss::Lost {
+                section: Section::General,
+                value: "AudioHash: 8784fe3d6c810facbdd3a95680cfa419".to_string(),
+            },
+            ConversionLoss::Lost {
+                section: Section::Events,
+                value: "3,0,255,128,64".to_string(),
+            },
+        ]
+    );
+
+    assert!(osu_file.to_string().contains("PreviewTime: 33550"));
+
+    let converted = osu_file.clone();
+    assert!(osu_file.convert_to(4).unwrap().is_lossless());
+    assert!(osu_file.convert_to(14).unwrap().is_lossless());
+    assert_eq!(osu_file, converted);
+}
+
+#[test]
+fn invalid_version() {
+    let mut osu_file = SMALL.parse::<OsuFile>().unwrap();
+
+    assert!(matches!(
+        osu_file.convert_to(15),
+        Err(ConvertError::InvalidVersion(15))
+    ));
+    assert_eq!(osu_file.version, 14);
+}
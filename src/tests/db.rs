@@ -0,0 +1,359 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::db::*;
+use crate::osu_file::general::Mode;
+use crate::osu_file::replay::{Mods, Replay};
+
+fn beatmap() -> DbBeatmap {
+    DbBeatmap {
+        artist: Some("Artist".to_string()),
+        title: Some("Title".to_string()),
+        creator: Some("Creator".to_string()),
+        version: Some("Insane".to_string()),
+        md5: Some("d41d8cd98f00b204e9800998ecf8427e".to_string()),
+        ranked_status: RankedStatus::Ranked,
+        hitcircle_count: 300,
+        approach_rate: 9.0,
+        circle_size: 4.0,
+        star_ratings: StarRatings {
+            osu: vec![(Mods::NONE, 5.25), (Mods::DOUBLE_TIME, 7.5)],
+            ..Default::default()
+        },
+        timing_points: vec![
+            DbTimingPoint {
+                beat_length: 500.0,
+                time: 1000.5,
+                uninherited: true,
+            },
+            DbTimingPoint {
+                beat_length: -50.0,
+                time: 2000.0,
+                uninherited: false,
+            },
+        ],
+        beatmap_id: 75,
+        beatmap_set_id: 1,
+        tags: Some("foo bar".to_string()),
+        mode: Mode::Mania,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn osu_db_round_trip() {
+    for version in [20130815, 20150203, 20250107] {
+        let db = OsuDb {
+            version,
+            folder_count: 1,
+            account_unlocked: true,
+            player_name: Some("player".to_string()),
+            beatmaps: vec![beatmap(), DbBeatmap::default()],
+            ..OsuDb::new(version)
+        };
+        let mut expected = db.clone();
+        if version < 20140609 {
+            expected.beatmaps[0].star_ratings = StarRatings::default();
+        }
+
+        let read = OsuDb::from_bytes(&db.to_bytes().unwrap()).unwrap();
+        assert_eq!(read, expected);
+    }
+}
+
+#[test]
+fn osu_db_entry_size() {
+    let db = OsuDb {
+        beatmaps: vec![beatmap()],
+        ..OsuDb::new(20150203)
+    };
+    let bytes = db.to_bytes().unwrap();
+
+    // version, folder count, account unlocked, unlock date, no player name, beatmap count
+    let entry_start = 4 + 4 + 1 + 8 + 1 + 4;
+    let entry_size = i32::from_le_bytes(bytes[entry_start..entry_start + 4].try_into().unwrap());
+    // entry and the user permissions
+    assert_eq!(entry_size as usize, bytes.len() - entry_start - 4 - 4);
+}
+
+#[test]
+fn db_beatmap_conversions() {
+    let beatmap = beatmap();
+
+    let metadata = beatmap.metadata();
+    assert_eq!(metadata.title, Some("Title".to_string().into()));
+    assert_eq!(
+        metadata.tags,
+        Some(vec!["foo".to_string(), "bar".to_string()].into())
+    );
+    assert_eq!(metadata.beatmap_id, Some(75.into()));
+
+    let timing_points = beatmap.timing_points();
+    assert_eq!(timing_points[0].calc_bpm(), Some(120.into()));
+    assert_eq!(
+        timing_points[1].calc_slider_velocity_multiplier(),
+        Some(2.into())
+    );
+    assert_eq!(
+        DbTimingPoint::from_timing_point(&timing_points[0]),
+        Some(beatmap.timing_points[0])
+    );
+    assert_eq!(beatmap.star_ratings.nomod(Mode::Osu), Some(5.25));
+}
+
+#[test]
+fn collection_db_round_trip() {
+    let db = CollectionDb {
+        version: 20250107,
+        collections: vec![
+            Collection {
+                name: Some("favourites".to_string()),
+                beatmap_md5s: vec![Some("a".repeat(32)), Some("b".repeat(32))],
+            },
+            Collection::default(),
+        ],
+    };
+    let bytes = db.to_bytes().unwrap();
+
+    assert_eq!(CollectionDb::from_bytes(&bytes).unwrap(), db);
+    assert_eq!(db.collection("favourites").unwrap().beatmap_md5s.len(), 2);
+}
+
+#[test]
+fn scores_db_round_trip() {
+    let score = Replay {
+        version: 20250107,
+        beatmap_md5: Some("a".repeat(32)),
+        player_name: Some("player".to_string()),
+        count_300: 100,
+        score: 1000000,
+        mods: Mods::HIDDEN,
+        online_score_id: 123,
+        ..Replay::new()
+    };
+    let db = ScoresDb {
+        version: 20250107,
+        beatmaps: vec![BeatmapScores {
+            beatmap_md5: Some("a".repeat(32)),
+            scores: vec![score.clone()],
+        }],
+    };
+    let bytes = db.to_bytes().unwrap();
+
+    // replay data length and the online score id are at the end of the score
+    assert_eq!(
+        bytes[bytes.len() - 12..bytes.len() - 8],
+        (-1i32).to_le_bytes()
+    );
+    assert_eq!(ScoresDb::from_bytes(&bytes).unwrap(), db);
+    assert_eq!(db.scores(&"a".repeat(32)), &[score]);
+}
+
+/// Little endian bytes written by hand, to check the layout of the databases without the crate's writer.
+#[derive(Default)]
+struct Fixture(Vec<u8>);
+
+impl Fixture {
+    fn u8(mut self, v: u8) -> Self {
+        self.0.push(v);
+        self
+    }
+
+    fn bytes(mut self, v: &[u8]) -> Self {
+        self.0.extend_from_slice(v);
+        self
+    }
+
+    /// A string of less than 128 bytes, or no string if `None`.
+    fn string(self, s: Option<&str>) -> Self {
+        match s {
+            Some(s) => self.u8(0x0b).u8(s.len() as u8).bytes(s.as_bytes()),
+            None => self.u8(0x00),
+        }
+    }
+}
+
+/// Beatmap entry fields up to the slider multiplier, without the difficulty values.
+fn entry_start() -> Fixture {
+    Fixture::default()
+        .string(Some("Artist"))
+        .string(None)
+        .string(Some("Title"))
+        .string(None)
+        .string(Some("Creator"))
+        .string(Some("Insane"))
+        .string(None)
+        .string(None)
+        .string(None)
+        // ranked, 3 circles, 2 sliders, 1 spinner
+        .u8(4)
+        .bytes(&3u16.to_le_bytes())
+        .bytes(&2u16.to_le_bytes())
+        .bytes(&1u16.to_le_bytes())
+        .bytes(&0i64.to_le_bytes())
+}
+
+/// Beatmap entry fields from the drain time to the visual override.
+fn entry_end() -> Fixture {
+    Fixture::default()
+        // drain time, total time, preview time, no timing points
+        .bytes(&60i32.to_le_bytes())
+        .bytes(&61000i32.to_le_bytes())
+        .bytes(&(-1i32).to_le_bytes())
+        .bytes(&0i32.to_le_bytes())
+        // beatmap id, set id, thread id, grades, local offset
+        .bytes(&75i32.to_le_bytes())
+        .bytes(&1i32.to_le_bytes())
+        .bytes(&0i32.to_le_bytes())
+        .bytes(&[9, 9, 9, 9])
+        .bytes(&0i16.to_le_bytes())
+        // stack leniency, osu!mania
+        .bytes(&0.7f32.to_le_bytes())
+        .u8(3)
+        .string(None)
+        .string(Some("foo bar"))
+        .bytes(&0i16.to_le_bytes())
+        .string(None)
+        // unplayed, last played, osz2, folder, last checked, the ignore and disable flags
+        .u8(1)
+        .bytes(&0i64.to_le_bytes())
+        .u8(0)
+        .string(Some("folder"))
+        .bytes(&0i64.to_le_bytes())
+        .bytes(&[0, 0, 0, 0, 0])
+}
+
+/// osu!.db with a single beatmap entry.
+fn osu_db_fixture(version: i32, entry: Vec<u8>) -> Vec<u8> {
+    Fixture::default()
+        .bytes(&version.to_le_bytes())
+        .bytes(&1i32.to_le_bytes())
+        .u8(1)
+        .bytes(&0i64.to_le_bytes())
+        .string(Some("player"))
+        .bytes(&1i32.to_le_bytes())
+        .bytes(&entry)
+        .bytes(&0i32.to_le_bytes())
+        .0
+}
+
+fn check_fixture_beatmap(beatmap: &DbBeatmap) {
+    assert_eq!(beatmap.artist.as_deref(), Some("Artist"));
+    assert_eq!(beatmap.version.as_deref(), Some("Insane"));
+    assert_eq!(beatmap.ranked_status, RankedStatus::Ranked);
+    assert_eq!(
+        (
+            beatmap.hitcircle_count,
+            beatmap.slider_count,
+            beatmap.spinner_count
+        ),
+        (3, 2, 1)
+    );
+    assert_eq!(beatmap.slider_multiplier, 1.4);
+    assert_eq!(beatmap.total_time, 61000);
+    assert_eq!(beatmap.beatmap_id, 75);
+    assert_eq!(beatmap.stack_leniency, 0.7);
+    assert_eq!(beatmap.mode, Mode::Mania);
+    assert_eq!(beatmap.tags.as_deref(), Some("foo bar"));
+    assert_eq!(beatmap.folder_name.as_deref(), Some("folder"));
+}
+
+#[test]
+fn osu_db_old_version_fixture() {
+    // before 20140609 the difficulty values are bytes, there are no star ratings and there's an extra i16
+    let entry = entry_start()
+        .bytes(&[9, 4, 6, 8])
+        .bytes(&1.4f64.to_le_bytes())
+        .bytes(&entry_end().0)
+        .bytes(&7i16.to_le_bytes())
+        .bytes(&0i32.to_le_bytes())
+        .u8(0)
+        .0;
+    // before 20191106 every entry starts with its size
+    let sized_entry = Fixture::default()
+        .bytes(&(entry.len() as i32).to_le_bytes())
+        .bytes(&entry)
+        .0;
+    let bytes = osu_db_fixture(20130815, sized_entry);
+
+    let db = OsuDb::from_bytes(&bytes).unwrap();
+    assert_eq!(db.version, 20130815);
+    assert_eq!(db.player_name.as_deref(), Some("player"));
+
+    let beatmap = &db.beatmaps[0];
+    check_fixture_beatmap(beatmap);
+    assert_eq!(
+        (
+            beatmap.approach_rate,
+            beatmap.circle_size,
+            beatmap.hp_drain_rate,
+            beatmap.overall_difficulty
+        ),
+        (9.0, 4.0, 6.0, 8.0)
+    );
+    assert_eq!(beatmap.star_ratings, StarRatings::default());
+    assert_eq!(beatmap.unknown, 7);
+
+    assert_eq!(db.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn osu_db_current_version_fixture() {
+    let star_ratings = |ratings: &[(u32, u8, &[u8])]| {
+        let mut fixture = Fixture::default().bytes(&(ratings.len() as i32).to_le_bytes());
+        for (mods, tag, rating) in ratings {
+            fixture = fixture
+                .u8(0x08)
+                .bytes(&mods.to_le_bytes())
+                .u8(*tag)
+                .bytes(rating);
+        }
+        fixture.0
+    };
+
+    // since 20250107 star ratings can be floats, marked with 0x0c instead of 0x0d
+    let entry = entry_start()
+        .bytes(&9.5f32.to_le_bytes())
+        .bytes(&4.2f32.to_le_bytes())
+        .bytes(&6.0f32.to_le_bytes())
+        .bytes(&8.5f32.to_le_bytes())
+        .bytes(&1.4f64.to_le_bytes())
+        .bytes(&star_ratings(&[
+            (0, 0x0c, &5.25f32.to_le_bytes()),
+            (64, 0x0c, &7.5f32.to_le_bytes()),
+        ]))
+        .bytes(&star_ratings(&[(0, 0x0d, &2.5f64.to_le_bytes())]))
+        .bytes(&star_ratings(&[]))
+        .bytes(&star_ratings(&[]))
+        .bytes(&entry_end().0)
+        .bytes(&0i32.to_le_bytes())
+        .u8(0)
+        .0;
+    let bytes = osu_db_fixture(20250107, entry);
+
+    let db = OsuDb::from_bytes(&bytes).unwrap();
+    let beatmap = &db.beatmaps[0];
+    check_fixture_beatmap(beatmap);
+    assert_eq!(
+        (
+            beatmap.approach_rate,
+            beatmap.circle_size,
+            beatmap.hp_drain_rate,
+            beatmap.overall_difficulty
+        ),
+        (9.5, 4.2, 6.0, 8.5)
+    );
+    assert_eq!(
+        beatmap.star_ratings.osu,
+        vec![(Mods::NONE, 5.25), (Mods::DOUBLE_TIME, 7.5)]
+    );
+    assert_eq!(beatmap.star_ratings.taiko, vec![(Mods::NONE, 2.5)]);
+    assert_eq!(beatmap.unknown, 0);
+
+    // floats are only read in versions that have them
+    let mut old_bytes = bytes.clone();
+    old_bytes[..4].copy_from_slice(&20191106i32.to_le_bytes());
+    assert!(matches!(
+        OsuDb::from_bytes(&old_bytes),
+        Err(DbError::InvalidStarRating)
+    ));
+}
@@ -0,0 +1,133 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{
+    diff::{Change, FieldChange, ObjectChange, Section},
+    OsuFile,
+};
+
+const SMALL: &str = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: 1000
+
+[Metadata]
+Title:foo
+Creator:bar
+
+[Events]
+0,0,\"bg.jpg\",0,0
+2,5000,6000
+
+[TimingPoints]
+0,500,4,2,1,60,1,0
+1000,-100,4,2,1,60,0,0
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:
+100,100,500,1,0,0:0:0:0:
+200,200,1000,1,0,0:0:0:0:";
+
+#[test]
+fn diff_identical() {
+    let osu_file = include_str!("./osu_files/files/v14.osu")
+        .parse::<OsuFile>()
+        .unwrap();
+
+    let diff = osu_file.diff(&osu_file);
+
+    assert!(diff.is_empty());
+    assert_eq!(diff.report(), "");
+}
+
+#[test]
+fn diff_fields() {
+    let old = SMALL.parse::<OsuFile>().unwrap();
+    let new = SMALL
+        .replace("PreviewTime: 1000", "PreviewTime: 2000\nCountdown: 0")
+        .replace("Creator:bar\n", "")
+        .parse::<OsuFile>()
+        .unwrap();
+
+    let diff = old.diff(&new);
+    let changes = diff.changes;
+
+    assert_eq!(
+        changes,
+        vec![
+            Change::Field(FieldChange {
+                section: Section::General,
+                key: "PreviewTime".to_string(),
+                old: Some("1000".to_string()),
+                new: Some("2000".to_string()),
+            }),
+            Change::Field(FieldChange {
+                section: Section::General,
+                key: "Countdown".to_string(),
+                old: None,
+                new: Some("0".to_string()),
+            }),
+            Change::Field(FieldChange {
+                section: Section::Metadata,
+                key: "Creator".to_string(),
+                old: Some("bar".to_string()),
+                new: None,
+            }),
+        ]
+    );
+}
+
+#[test]
+fn diff_hitobjects() {
+    let old = SMALL.parse::<OsuFile>().unwrap();
+    let new = SMALL
+        .replace("100,100,500,1,0", "100,100,750,1,0")
+        .replace("200,200,1000,1,0", "300,200,1000,1,0")
+        .replace(
+            "256,192,0,1,0,0:0:0:0:",
+            "256,192,0,1,0,0:0:0:0:\n1,1,1500,1,0,0:0:0:0:",
+        )
+        .parse::<OsuFile>()
+        .unwrap();
+
+    let diff = old.diff(&new);
+    let changes = diff
+        .section(Section::HitObjects)
+        .map(|c| match c {
+            Change::HitObject(c) => c,
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(changes.len(), 3);
+    assert!(matches!(changes[0], ObjectChange::Moved { .. }));
+    assert!(matches!(changes[1], ObjectChange::Changed { .. }));
+    assert!(matches!(changes[2], ObjectChange::Added(_)));
+    assert_eq!(
+        diff.report(),
+        "[HitObjects]
+> 100,100,500,1,0,0:0:0:0: -> 100,100,750,1,0,0:0:0:0:
+~ 200,200,1000,1,0,0:0:0:0: -> 300,200,1000,1,0,0:0:0:0:
++ 1,1,1500,1,0,0:0:0:0:"
+    );
+}
+
+#[test]
+fn diff_timing_points_and_events() {
+    let old = SMALL.parse::<OsuFile>().unwrap();
+    let new = SMALL
+        .replace("1000,-100,4,2,1,60,0,0", "1000,-50,4,2,1,60,0,0")
+        .replace("2,5000,6000", "2,5000,7000")
+        .parse::<OsuFile>()
+        .unwrap();
+
+    let diff = old.diff(&new);
+
+    assert_eq!(
+        diff.report(),
+        "[Events]
+~ 2,5000,6000 -> 2,5000,7000
+[TimingPoints]
+~ 1000,-100,4,2,1,60,0,0 -> 1000,-50,4,2,1,60,0,0"
+    );
+}
@@ -0,0 +1,48 @@
+use pretty_assertions::{assert_eq, assert_ne};
+
+use crate::osu_file::hash::beatmap_md5;
+use crate::osu_file::OsuFile;
+
+const ACID_RAIN: &str = include_str!("./osu_files/files/acid_rain.osu");
+
+#[test]
+fn md5() {
+    assert_eq!(beatmap_md5(""), "d41d8cd98f00b204e9800998ecf8427e");
+    assert_eq!(beatmap_md5(b"abc"), "900150983cd24fb0d6963f7d28e17f72");
+
+    // v14.osu is written back byte for byte, so it has the digest of the file from `md5sum`
+    let v14 = include_str!("./osu_files/files/v14.osu");
+    assert_eq!(beatmap_md5(v14), "44a8fff7c33e2a4923f92f5fd45c3108");
+    assert_eq!(
+        v14.parse::<OsuFile>().unwrap().serialized_md5(),
+        "44a8fff7c33e2a4923f92f5fd45c3108"
+    );
+
+    // the original bytes aren't kept, so files written differently hash differently
+    let reformatted = ACID_RAIN.replace("Countdown: 0", "Countdown:0");
+    assert_ne!(
+        reformatted.parse::<OsuFile>().unwrap().serialized_md5(),
+        beatmap_md5(&reformatted)
+    );
+}
+
+#[test]
+fn semantic_hash_ignores_whitespace() {
+    let osu_file = ACID_RAIN.parse::<OsuFile>().unwrap();
+
+    let reformatted = ACID_RAIN
+        .replace("\r\n", "\n")
+        .replace("[Editor]", "\n\n[Editor]")
+        .replace("Countdown: 0", "Countdown:0")
+        .replace("BeatDivisor: 8", "BeatDivisor:   8");
+    assert_ne!(beatmap_md5(&reformatted), beatmap_md5(ACID_RAIN));
+
+    let reformatted = reformatted.parse::<OsuFile>().unwrap();
+    assert_eq!(reformatted.semantic_hash(), osu_file.semantic_hash());
+
+    let changed = ACID_RAIN
+        .replace("Title:Acid Rain", "Title:Acid Rain (Cut)")
+        .parse::<OsuFile>()
+        .unwrap();
+    assert_ne!(changed.semantic_hash(), osu_file.semantic_hash());
+}
@@ -0,0 +1,39 @@
+use crate::osu_file::hitobjects::{HitObject, HitObjectParams};
+use crate::osu_file::VersionedFromStr;
+
+fn path(s: &str) -> Vec<(f64, f64)> {
+    let hitobject = HitObject::from_str(s, 14).unwrap().unwrap();
+    let HitObjectParams::Slider(slider) = &hitobject.obj_params else {
+        panic!("not a slider");
+    };
+    let path = slider.path(&hitobject.position).unwrap();
+
+    [0.0, path.length() / 2.0, path.length()]
+        .into_iter()
+        .map(|distance| {
+            let (x, y) = path.position_at(distance);
+            // the path is a polyline, so it's slightly off from the actual curve
+            (x.round(), y.round())
+        })
+        .collect()
+}
+
+#[test]
+fn linear_cut_and_extended() {
+    assert_eq!(
+        path("0,0,0,2,0,L|100:0|100:100,1,150"),
+        vec![(0.0, 0.0), (75.0, 0.0), (100.0, 50.0)]
+    );
+    assert_eq!(
+        path("0,0,0,2,0,L|100:0,1,200"),
+        vec![(0.0, 0.0), (100.0, 0.0), (200.0, 0.0)]
+    );
+}
+
+#[test]
+fn perfect_circle() {
+    let length = std::f64::consts::PI * 100.0;
+    let points = path(&format!("0,100,0,2,0,P|100:0|200:100,1,{length}"));
+
+    assert_eq!(points, vec![(0.0, 100.0), (100.0, 0.0), (200.0, 100.0)]);
+}
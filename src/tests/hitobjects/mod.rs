@@ -1,2 +1,3 @@
+mod curve;
 mod error;
 mod parse;
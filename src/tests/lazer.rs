@@ -0,0 +1,107 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{
+    convert::ConversionLoss, diff::Section, general::ParseError as GeneralParseError, OsuFile,
+    ParseError, LATEST_VERSION, LAZER_VERSION,
+};
+
+const LAZER: &str = "osu file format v128
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: 1000
+
+[HitObjects]
+256,192,1000,1,0,1:2:0:80::custom-bank";
+
+#[test]
+fn lazer_round_trip() {
+    let osu_file = LAZER.parse::<OsuFile>().unwrap();
+
+    assert_eq!(
+        osu_file.hitobjects.as_ref().unwrap().0[0].hitsample_extensions,
+        vec!["custom-bank".to_string()]
+    );
+    assert_eq!(osu_file.to_string(), LAZER);
+}
+
+#[test]
+fn lazer_unknown_keys() {
+    // no `[General]` keys only osu!lazer writes are known
+    let unknown = LAZER.replace("PreviewTime", "EditorTimelineZoom");
+    let err = unknown.parse::<OsuFile>().unwrap_err();
+
+    assert_eq!(err.line_index(), 4);
+    assert!(matches!(
+        err.error(),
+        ParseError::ParseGeneralError {
+            source: GeneralParseError::InvalidKey
+        }
+    ));
+}
+
+#[test]
+fn lazer_hold_hitsample_extensions() {
+    let hold = "osu file format v128
+
+[HitObjects]
+64,192,1000,128,0,1500:1:2:0:80:hold.wav:custom-bank:1";
+    let osu_file = hold.parse::<OsuFile>().unwrap();
+    let hitobject = &osu_file.hitobjects.as_ref().unwrap().0[0];
+
+    assert_eq!(hitobject.hitsample.as_ref().unwrap().filename, "hold.wav");
+    assert_eq!(
+        hitobject.hitsample_extensions,
+        vec!["custom-bank".to_string(), "1".to_string()]
+    );
+    assert_eq!(osu_file.to_string(), hold);
+}
+
+#[test]
+fn lazer_slider_without_hitsample() {
+    let slider = "100,100,1000,2,0,B|200:200|300:100,5,100,0|0|0|0|0|0,0:0|0:0|0:0|0:0|0:0|0:0";
+    let lazer = format!("osu file format v128\n\n[HitObjects]\n{slider}");
+    let osu_file = lazer.parse::<OsuFile>().unwrap();
+    let hitobject = &osu_file.hitobjects.as_ref().unwrap().0[0];
+
+    assert!(hitobject.hitsample.is_none());
+    assert!(hitobject.hitsample_extensions.is_empty());
+    assert_eq!(osu_file.to_string(), lazer);
+    assert_eq!(
+        osu_file.hitobjects,
+        lazer
+            .replace("v128", "v14")
+            .parse::<OsuFile>()
+            .unwrap()
+            .hitobjects
+    );
+
+    let with_hitsample = format!("{lazer},1:2:0:80:slide.wav:custom-bank");
+    let osu_file = with_hitsample.parse::<OsuFile>().unwrap();
+    let hitobject = &osu_file.hitobjects.as_ref().unwrap().0[0];
+
+    assert_eq!(hitobject.hitsample.as_ref().unwrap().filename, "slide.wav");
+    assert_eq!(
+        hitobject.hitsample_extensions,
+        vec!["custom-bank".to_string()]
+    );
+    assert_eq!(osu_file.to_string(), with_hitsample);
+}
+
+#[test]
+fn lazer_convert_to_stable() {
+    let mut osu_file = LAZER.parse::<OsuFile>().unwrap();
+    let report = osu_file.convert_to(LATEST_VERSION).unwrap();
+
+    assert_eq!(
+        report.losses,
+        vec![ConversionLoss::Approximated {
+            section: Section::HitObjects,
+            old: "256,192,1000,1,0,1:2:0:80::custom-bank".to_string(),
+            new: "256,192,1000,1,0,1:2:0:80:".to_string(),
+        }]
+    );
+    assert!(osu_file.to_string().parse::<OsuFile>().is_ok());
+
+    assert!(osu_file.convert_to(LAZER_VERSION).unwrap().is_lossless());
+}
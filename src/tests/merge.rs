@@ -0,0 +1,86 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{diff::Section, merge::Conflict, OsuFile};
+
+const BASE: &str = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: 1000
+
+[Metadata]
+Title:foo
+Creator:bar
+
+[TimingPoints]
+0,500,4,2,1,60,1,0
+1000,-100,4,2,1,60,0,0
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:
+100,100,500,1,0,0:0:0:0:
+200,200,1000,1,0,0:0:0:0:";
+
+#[test]
+fn merge_clean() {
+    let base = BASE.parse::<OsuFile>().unwrap();
+    let ours = BASE
+        .replace("PreviewTime: 1000", "PreviewTime: 2000")
+        .replace("100,100,500,1,0", "150,100,500,1,0")
+        .parse::<OsuFile>()
+        .unwrap();
+    let theirs = BASE
+        .replace("Creator:bar", "Creator:baz")
+        .replace("1000,-100", "1000,-50")
+        .replace(
+            "200,200,1000,1,0,0:0:0:0:",
+            "200,200,1000,1,0,0:0:0:0:\n300,300,750,1,0,0:0:0:0:",
+        )
+        .parse::<OsuFile>()
+        .unwrap();
+
+    let result = OsuFile::merge(&base, &ours, &theirs);
+
+    assert!(result.is_clean());
+    assert_eq!(
+        result.merged.to_string(),
+        BASE.replace("PreviewTime: 1000", "PreviewTime: 2000")
+            .replace("Creator:bar", "Creator:baz")
+            .replace("1000,-100", "1000,-50")
+            .replace(
+                "100,100,500,1,0,0:0:0:0:",
+                "150,100,500,1,0,0:0:0:0:\n300,300,750,1,0,0:0:0:0:"
+            )
+    );
+}
+
+#[test]
+fn merge_conflicts() {
+    let base = BASE.parse::<OsuFile>().unwrap();
+    let ours = BASE
+        .replace("Title:foo", "Title:ours")
+        .replace("256,192,0,1,0", "1,1,0,1,0")
+        .parse::<OsuFile>()
+        .unwrap();
+    let theirs = BASE
+        .replace("Title:foo", "Title:theirs")
+        .replace("256,192,0,1,0", "2,2,0,1,0")
+        .parse::<OsuFile>()
+        .unwrap();
+
+    let result = OsuFile::merge(&base, &ours, &theirs);
+
+    assert_eq!(result.merged, ours);
+    assert_eq!(result.conflicts.len(), 2);
+    assert_eq!(
+        result.conflicts[0],
+        Conflict::Field {
+            section: Section::Metadata,
+            key: "Title".to_string(),
+            base: Some("foo".to_string()),
+            ours: Some("ours".to_string()),
+            theirs: Some("theirs".to_string()),
+        }
+    );
+    assert!(matches!(result.conflicts[1], Conflict::HitObjects { .. }));
+}
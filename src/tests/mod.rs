@@ -1,8 +1,25 @@
+mod assets;
+mod beatmapset;
+#[cfg(feature = "db")]
+mod db;
+mod convert;
+mod diff;
+mod merge;
 mod error_line_index;
+mod hash;
 mod hitobjects;
+mod lazer;
 mod osu_files;
+#[cfg(feature = "osz")]
+mod osz;
 mod parsers;
+#[cfg(feature = "replay")]
+mod replay;
+#[cfg(feature = "replay")]
+mod replay_judge;
+mod skin;
 mod storyboard;
+mod transform;
 
 use crate::osu_file::types::Decimal;
 use pretty_assertions::assert_eq;
@@ -179,17 +196,20 @@ SliderBorder : 120,130,140";
                 red: 255,
                 green: 128,
                 blue: 255,
+                alpha: None,
             },
         ),
         Colour::SliderTrackOverride(Rgb {
             red: 100,
             green: 99,
             blue: 70,
+            alpha: None,
         }),
         Colour::SliderBorder(Rgb {
             red: 120,
             green: 130,
             blue: 140,
+            alpha: None,
         }),
     ];
 
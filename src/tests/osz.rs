@@ -0,0 +1,109 @@
+use std::io::{Cursor, Write};
+
+use pretty_assertions::assert_eq;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::osu_file::osz::Osz;
+use crate::osu_file::OsuFile;
+
+const OSU: &str = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+
+[Metadata]
+Title:foo
+Artist:bar
+Creator:baz
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:";
+
+const OSB: &str = "[Events]
+//Background and Video events
+//Storyboard Layer 0 (Background)
+Sprite,Background,Centre,\"sb/star.png\",320,240
+ F,0,0,1000,0,1
+//Storyboard Layer 1 (Fail)
+//Storyboard Layer 2 (Pass)
+//Storyboard Layer 3 (Foreground)
+//Storyboard Layer 4 (Overlay)
+//Storyboard Sound Samples";
+
+#[test]
+fn read_osz() {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, content) in [
+        ("bar - foo (baz) [Easy].osu", OSU.as_bytes()),
+        ("bar - foo (baz) [Hard].OSU", OSU.as_bytes()),
+        ("bar - foo (baz).osb", OSB.as_bytes()),
+        ("audio.mp3", &[1, 2, 3]),
+    ] {
+        zip.start_file(name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(content).unwrap();
+    }
+    zip.add_directory("sb/", SimpleFileOptions::default())
+        .unwrap();
+    zip.start_file("sb/star.png", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(&[4, 5]).unwrap();
+    let bytes = zip.finish().unwrap().into_inner();
+
+    let osz = Osz::from_bytes(&bytes).unwrap();
+
+    assert_eq!(
+        osz.beatmaps.keys().collect::<Vec<_>>(),
+        vec!["bar - foo (baz) [Easy].osu", "bar - foo (baz) [Hard].OSU"]
+    );
+    for osu_file in osz.beatmaps.values() {
+        assert_eq!(osu_file.osb_to_string().unwrap(), OSB);
+    }
+    assert_eq!(osz.osb_name.as_deref(), Some("bar - foo (baz).osb"));
+    assert_eq!(
+        osz.assets.into_iter().collect::<Vec<_>>(),
+        vec![
+            ("audio.mp3".to_string(), vec![1, 2, 3]),
+            ("sb/star.png".to_string(), vec![4, 5]),
+        ]
+    );
+}
+
+#[test]
+fn write_osz() {
+    let mut osu_file = OSU.parse::<OsuFile>().unwrap();
+    osu_file.append_osb(OSB).unwrap();
+
+    let osz = Osz::from_osu_files(
+        [("map.osu".to_string(), osu_file)],
+        [("audio.mp3".to_string(), vec![1, 2, 3])],
+    );
+    assert_eq!(osz.osb_file_name(), "bar - foo (baz).osb");
+
+    let read = Osz::from_bytes(&osz.to_bytes().unwrap()).unwrap();
+    assert_eq!(read.beatmaps, osz.beatmaps);
+    assert_eq!(read.assets, osz.assets);
+    assert_eq!(read.osb_name.as_deref(), Some("bar - foo (baz).osb"));
+}
+
+#[test]
+fn convert_olz() {
+    let lazer = OSU
+        .replace("v14", "v128")
+        .replace("0:0:0:0:", "0:0:0:0::custom-bank");
+    let mut olz = Osz::from_osu_files(
+        [(
+            "bar - foo (baz) [Easy].osu".to_string(),
+            lazer.parse::<OsuFile>().unwrap(),
+        )],
+        [],
+    );
+
+    let reports = olz.convert_to(14).unwrap();
+
+    assert_eq!(reports["bar - foo (baz) [Easy].osu"].losses.len(), 1);
+    assert_eq!(
+        olz.beatmaps["bar - foo (baz) [Easy].osu"].to_string(),
+        OSU.parse::<OsuFile>().unwrap().to_string()
+    );
+}
@@ -0,0 +1,105 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::general::Mode;
+use crate::osu_file::replay::{Keys, LifeBarPoint, Mods, Replay, ReplayError, ReplayFrame};
+
+fn replay() -> Replay {
+    Replay {
+        mode: Mode::Osu,
+        version: 20220216,
+        beatmap_md5: Some("d41d8cd98f00b204e9800998ecf8427e".to_string()),
+        player_name: Some("player".to_string()),
+        replay_md5: Some(String::new()),
+        count_300: 100,
+        count_100: 2,
+        count_miss: 1,
+        score: 123456,
+        max_combo: 300,
+        mods: Mods::HIDDEN | Mods::DOUBLE_TIME,
+        life_bar: Some(vec![
+            LifeBarPoint {
+                time: 100,
+                life: 1.0,
+            },
+            LifeBarPoint {
+                time: 2000,
+                life: 0.75,
+            },
+        ]),
+        timestamp: 637_800_000_000_000_000,
+        frames: vec![
+            ReplayFrame {
+                time_delta: 0,
+                x: 256.0,
+                y: -500.0,
+                keys: Keys::NONE,
+            },
+            ReplayFrame {
+                time_delta: 16,
+                x: 100.5,
+                y: 200.25,
+                keys: Keys::M1 | Keys::K1,
+            },
+        ],
+        seed: Some(7),
+        online_score_id: 4_000_000_000,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn round_trip() {
+    let replay = replay();
+    let bytes = replay.to_bytes().unwrap();
+
+    assert_eq!(bytes[0], 0);
+    assert_eq!(&bytes[1..5], &20220216i32.to_le_bytes());
+    assert_eq!(&bytes[5..7], &[0x0b, 32]);
+    assert_eq!(
+        replay.frames_to_string(),
+        "0|256|-500|0,16|100.5|200.25|5,-12345|0|0|7,"
+    );
+
+    let read = Replay::from_bytes(&bytes).unwrap();
+    assert_eq!(read, replay);
+    assert_eq!(
+        read.timed_frames()
+            .map(|(time, _)| time)
+            .collect::<Vec<_>>(),
+        vec![0, 16]
+    );
+    assert!(read.frames[1].keys.contains(Keys::K1));
+    assert!(read.mods.contains(Mods::DOUBLE_TIME));
+}
+
+#[test]
+fn old_versions() {
+    let mut replay = Replay {
+        version: 20130101,
+        online_score_id: 5,
+        seed: None,
+        mods: Mods::TARGET_PRACTICE,
+        target_practice_accuracy: Some(0.5),
+        life_bar: None,
+        ..replay()
+    };
+    assert_eq!(
+        Replay::from_bytes(&replay.to_bytes().unwrap()).unwrap(),
+        replay
+    );
+
+    replay.version = 20120101;
+    let bytes = replay.to_bytes().unwrap();
+    replay.online_score_id = 0;
+    assert_eq!(Replay::from_bytes(&bytes).unwrap(), replay);
+}
+
+#[test]
+fn truncated() {
+    let bytes = replay().to_bytes().unwrap();
+
+    assert!(matches!(
+        Replay::from_bytes(&bytes[..20]),
+        Err(ReplayError::Binary(_))
+    ));
+}
@@ -0,0 +1,132 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::replay::judge::{CountField, CountMismatch, Judgement};
+use crate::osu_file::replay::{Keys, Replay, ReplayFrame};
+use crate::osu_file::OsuFile;
+
+const OSU: &str = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+StackLeniency: 0.7
+Mode: 0
+
+[Difficulty]
+HPDrainRate:5
+CircleSize:4
+OverallDifficulty:8
+ApproachRate:9
+SliderMultiplier:1
+SliderTickRate:1
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+100,100,1000,5,0,0:0:0:0:
+200,100,1500,1,0,0:0:0:0:
+100,200,2000,2,0,L|300:200,1,200
+256,192,4000,12,0,5000,0:0:0:0:";
+
+/// Frames from absolute times.
+fn replay(frames: &[(i64, f32, f32, Keys)]) -> Replay {
+    let mut time = 0;
+    let frames = frames
+        .iter()
+        .map(|(frame_time, x, y, keys)| {
+            let time_delta = frame_time - time;
+            time = *frame_time;
+            ReplayFrame {
+                time_delta,
+                x: *x,
+                y: *y,
+                keys: *keys,
+            }
+        })
+        .collect();
+
+    Replay {
+        frames,
+        ..Default::default()
+    }
+}
+
+fn frames(hit_second_circle: bool) -> Vec<(i64, f32, f32, Keys)> {
+    let mut frames = vec![
+        (0, 100.0, 100.0, Keys::NONE),
+        (1000, 100.0, 100.0, Keys::M1),
+        (1050, 200.0, 100.0, Keys::NONE),
+    ];
+    if hit_second_circle {
+        frames.push((1505, 200.0, 100.0, Keys::M2 | Keys::K2));
+    }
+    frames.extend([
+        (1600, 100.0, 200.0, Keys::NONE),
+        (2000, 100.0, 200.0, Keys::M1 | Keys::K1),
+        (2500, 200.0, 200.0, Keys::M1 | Keys::K1),
+        (2964, 296.4, 200.0, Keys::M1 | Keys::K1),
+        (3000, 300.0, 200.0, Keys::M1 | Keys::K1),
+        (3010, 300.0, 200.0, Keys::NONE),
+    ]);
+
+    // spinning at the fastest counted speed
+    for i in 0..=100 {
+        let angle = i as f64 * 0.45;
+        frames.push((
+            4000 + i * 10,
+            (256.0 + 50.0 * angle.cos()) as f32,
+            (192.0 + 50.0 * angle.sin()) as f32,
+            Keys::M1,
+        ));
+    }
+
+    frames
+}
+
+#[test]
+fn full_combo() {
+    let osu_file = OSU.parse::<OsuFile>().unwrap();
+    let report = replay(&frames(true)).judge(&osu_file).unwrap();
+
+    assert_eq!(
+        report
+            .objects
+            .iter()
+            .map(|o| o.judgement)
+            .collect::<Vec<_>>(),
+        vec![Judgement::Hit300; 4]
+    );
+    assert_eq!(report.objects[1].offset, Some(5.0));
+    assert_eq!(report.objects[2].slider_parts, Some((3, 3)));
+    assert!(report.objects[3].spins.unwrap() >= 6.0);
+    assert_eq!(
+        (report.count_300, report.count_geki, report.count_katu),
+        (4, 2, 0)
+    );
+    assert_eq!((report.max_combo, report.full_combo), (6, 6));
+    assert_eq!(report.accuracy(), 1.0);
+}
+
+#[test]
+fn miss_and_compare() {
+    let osu_file = OSU.parse::<OsuFile>().unwrap();
+    let mut replay = replay(&frames(false));
+    let report = replay.judge(&osu_file).unwrap();
+
+    assert_eq!(report.objects[1].judgement, Judgement::Miss);
+    assert_eq!((report.count_300, report.count_miss), (3, 1));
+    assert_eq!(report.max_combo, 4);
+
+    replay.count_300 = 4;
+    replay.count_miss = 1;
+    replay.count_geki = 1;
+    replay.max_combo = 4;
+    assert_eq!(
+        report.compare(&replay),
+        vec![CountMismatch {
+            field: CountField::Count300,
+            replay: 4,
+            simulated: 3,
+        }]
+    );
+}
@@ -0,0 +1,193 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::colours::Rgb;
+use crate::osu_file::skin::*;
+use crate::osu_file::{Decimal, VersionedToString, LATEST_VERSION};
+
+const SKIN: &str = "[General]
+Name: test skin
+Author: someone
+Version: 2.5
+CursorRotate: 0
+CustomComboBurstSounds: 50,100,200
+
+[Colours]
+Combo1: 255,192,0
+Combo2: 0,202,0
+SliderBorder: 255,255,255,128
+
+[Fonts]
+HitCirclePrefix: fonts/default
+HitCircleOverlap: 3
+
+[CatchTheBeat]
+HyperDash: 255,0,0
+
+[Mania]
+Keys: 4
+ColumnWidth: 30,30,30,30
+HitPosition: 420
+KeyImage0: mania-key1
+Colour1: 0,0,0,255
+
+[Mania]
+Keys: 7
+UpsideDown: 1";
+
+#[test]
+fn skin_round_trip() {
+    let skin = SKIN.parse::<Skin>().unwrap();
+
+    let general = skin.general.as_ref().unwrap();
+    assert_eq!(general.name, Some("test skin".to_string().into()));
+    assert_eq!(skin.version(), SkinVersion::Number(Decimal::from("2.5")));
+    assert_eq!(general.cursor_rotate, Some(false.into()));
+    assert_eq!(
+        skin.colours.as_ref().unwrap().combo_colours(),
+        vec![
+            Rgb {
+                red: 255,
+                green: 192,
+                blue: 0,
+                alpha: None
+            },
+            Rgb {
+                red: 0,
+                green: 202,
+                blue: 0,
+                alpha: None
+            },
+        ]
+    );
+
+    let mania = skin.mania(4).unwrap();
+    assert_eq!(mania.settings.hit_position, Some(420.into()));
+    assert_eq!(
+        mania.column_fields,
+        vec![
+            ("KeyImage0".to_string(), "mania-key1".to_string()),
+            ("Colour1".to_string(), "0,0,0,255".to_string()),
+        ]
+    );
+    assert_eq!(
+        skin.mania(7).unwrap().settings.upside_down,
+        Some(true.into())
+    );
+
+    assert_eq!(skin.to_string(), SKIN);
+}
+
+#[test]
+fn skin_comments_and_spacing() {
+    let i = "\u{feff}// comment before sections
+
+[General]
+  // a comment
+Name : spaced
+Version: latest
+";
+    let skin = i.parse::<Skin>().unwrap();
+
+    assert_eq!(skin.version(), SkinVersion::Latest);
+    assert_eq!(skin.to_string(), "[General]\nName: spaced\nVersion: latest");
+}
+
+#[test]
+fn skin_inline_comments_and_repeated_keys() {
+    let i = "[General]
+Name: first
+CursorRotate: 1 // yes
+Name: second // the name used
+Unknown: 1
+Unknown: 2
+
+[Mania]
+Keys: 4
+KeyImage0: a
+KeyImage0: b";
+    let skin = i.parse::<Skin>().unwrap();
+
+    let general = skin.general.as_ref().unwrap();
+    assert_eq!(general.name.as_ref().unwrap().0, "second");
+    assert!(general.cursor_rotate.as_ref().unwrap().0);
+    assert_eq!(
+        skin.unknown_fields("General"),
+        [("Unknown".to_string(), "2".to_string())]
+    );
+    assert_eq!(
+        skin.mania[0].column_fields,
+        vec![("KeyImage0".to_string(), "b".to_string())]
+    );
+}
+
+#[test]
+fn skin_version_default() {
+    assert_eq!(
+        Skin::new().version(),
+        SkinVersion::Number(Decimal::from("1.0"))
+    );
+}
+
+#[test]
+fn skin_error_line_index() {
+    let i = "// comment\n[General]\n\n// comment\nName: foo\nCursorRotate: 2";
+    let err = i.parse::<Skin>().unwrap_err();
+    assert_eq!(err.line_index(), 5);
+
+    let i = "[Colours]\nCombo1: 255,0,0\n\nCombo2: 255,foo,0";
+    let err = i.parse::<Skin>().unwrap_err();
+    assert_eq!(err.line_index(), 3);
+    assert_eq!(err.error().to_string(), "Invalid green value");
+
+    let i = "[Mania]\nKeys: 4\nfoo\n";
+    let err = i.parse::<Skin>().unwrap_err();
+    assert_eq!(err.line_index(), 2);
+
+    let i = "[General]\nName: foo\n\n[Mania]\nHitPosition: 400";
+    let err = i.parse::<Skin>().unwrap_err();
+    assert_eq!(err.line_index(), 3);
+    assert!(matches!(err.error(), ParseError::MissingManiaKeys));
+
+    let i = "[General]\n[Fonts]\n[General]";
+    let err = i.parse::<Skin>().unwrap_err();
+    assert_eq!(err.line_index(), 2);
+}
+
+#[test]
+fn skin_unknown_fields() {
+    let i = "[General]
+Name: typo
+HitCircleOverlayAboveNumer: 0
+LazerOnly: 1
+
+[Colours]
+SliderBorder: 255,255,255,128
+
+[Mania]
+Keys: 4
+
+[Future]
+Foo: bar";
+    let skin = i.parse::<Skin>().unwrap();
+
+    assert_eq!(
+        skin.unknown_fields("General"),
+        [
+            ("HitCircleOverlayAboveNumer".to_string(), "0".to_string()),
+            ("LazerOnly".to_string(), "1".to_string()),
+        ]
+    );
+    assert_eq!(
+        skin.unknown_sections,
+        vec![("Future".to_string(), vec!["Foo: bar".to_string()])]
+    );
+    assert_eq!(skin.to_string(), i);
+
+    // only skins write the alpha of colours
+    let slider_border = skin.colours.unwrap().slider_border.unwrap().0;
+    assert_eq!(slider_border.alpha, Some(128));
+    assert_eq!(
+        slider_border.to_string(LATEST_VERSION).unwrap(),
+        "255,255,255"
+    );
+}
@@ -0,0 +1,111 @@
+use pretty_assertions::assert_eq;
+
+use crate::osb::Osb;
+use crate::osu_file::events::storyboard::builder::{
+    animation, sprite, CommandBuilder, StoryboardBuilder,
+};
+use crate::osu_file::events::storyboard::error::BuildError;
+use crate::osu_file::events::storyboard::sprites::{Layer, LoopType, OriginType};
+use crate::osu_file::events::storyboard::types::{Easing, Parameter, TriggerType};
+use crate::osu_file::{VersionedFromStr, VersionedToString};
+
+#[test]
+fn build_osb() {
+    let osb = StoryboardBuilder::new()
+        .object(
+            sprite("sb/bg.png")
+                .layer(Layer::Background)
+                .origin(OriginType::TopLeft)
+                .position(0.0, 0.0)
+                .fade(0, 1000, 0.0, 1.0, Easing::QuadOut)
+                .move_(0, 1000, (0.0, 0.0), (10.5, -20.0), Easing::Linear)
+                .colour(0, 0, (255, 0, 0), (0, 0, 255), Easing::Linear)
+                .parameter(500, 500, Parameter::UseAdditiveColourBlending)
+                .r#loop(4000, 3, |l| {
+                    l.scale(0, 500, 1.0, 1.25, Easing::Linear).rotate(
+                        500,
+                        1000,
+                        0.0,
+                        1.5,
+                        Easing::SineInOut,
+                    )
+                })
+                .trigger(TriggerType::Passing, 0, 10000, |t| {
+                    t.vector_scale(0, 100, (1.0, 1.0), (2.0, 0.5), Easing::Linear)
+                }),
+        )
+        .object(animation("sb/anim.png", 4, 100.0, LoopType::LoopOnce))
+        .build_osb()
+        .unwrap();
+
+    let s = "[Events]
+Sprite,Background,TopLeft,sb/bg.png,0,0
+ F,4,0,1000,0,1
+ M,0,0,1000,0,0,10.5,-20
+ C,0,0,0,255,0,0,0,0,255
+ P,0,500,500,A
+ L,4000,3
+  S,0,0,500,1,1.25
+  R,17,500,1000,0,1.5
+ T,Passing,0,10000
+  V,0,0,100,1,1,2,0.5
+Animation,Foreground,Centre,sb/anim.png,320,240,4,100,LoopOnce";
+
+    assert_eq!(osb.to_string(14).unwrap(), s);
+    assert_eq!(Osb::from_str(s, 14).unwrap().unwrap(), osb);
+}
+
+#[test]
+fn build_errors() {
+    let error = |builder: StoryboardBuilder| builder.build_events().unwrap_err();
+
+    assert!(matches!(
+        error(StoryboardBuilder::new().object(sprite("a.png").fade(
+            1000,
+            0,
+            0.0,
+            1.0,
+            Easing::Linear
+        ))),
+        BuildError::EndBeforeStart {
+            start: 1000,
+            end: 0
+        }
+    ));
+    assert!(matches!(
+        error(StoryboardBuilder::new().object(sprite("a.png").scale(
+            0,
+            0,
+            f64::NAN,
+            1.0,
+            Easing::Linear
+        ))),
+        BuildError::InvalidValue(_)
+    ));
+    assert!(matches!(
+        error(
+            StoryboardBuilder::new().object(sprite("a.png").r#loop(0, 2, |l| l.r#loop(
+                0,
+                2,
+                |l| l.fade(0, 1, 0.0, 1.0, Easing::Linear)
+            )))
+        ),
+        BuildError::NestedGroup
+    ));
+    assert!(matches!(
+        error(StoryboardBuilder::new().object(sprite("a.png").r#loop(0, 2, |l| l))),
+        BuildError::EmptyGroup
+    ));
+    assert!(matches!(
+        error(
+            StoryboardBuilder::new().object(sprite("a.png").r#loop(0, 0, |l| l.fade(
+                0,
+                1,
+                0.0,
+                1.0,
+                Easing::Linear
+            )))
+        ),
+        BuildError::InvalidLoopCount
+    ));
+}
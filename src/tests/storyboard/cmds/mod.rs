@@ -68,6 +68,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
             commands: vec![
                 Command {
                     start_time: Some(-28),
+                    variables: None,
                     properties: CommandProperties::Fade {
                         easing: <Easing as VersionedFrom<Integer>>::from(0, 14).unwrap(),
                         end_time: None,
@@ -77,6 +78,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(100),
+                    variables: None,
                     properties: CommandProperties::Move {
                         easing: <Easing as VersionedFrom<Integer>>::from(3, 14).unwrap(),
                         end_time: Some(120),
@@ -89,6 +91,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(100),
+                    variables: None,
                     properties: CommandProperties::MoveX {
                         easing: <Easing as VersionedFrom<Integer>>::from(3, 14).unwrap(),
                         end_time: Some(120),
@@ -98,6 +101,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(100),
+                    variables: None,
                     properties: CommandProperties::MoveY {
                         easing: <Easing as VersionedFrom<Integer>>::from(3, 14).unwrap(),
                         end_time: Some(120),
@@ -107,6 +111,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(-28),
+                    variables: None,
                     properties: CommandProperties::Scale {
                         easing: <Easing as VersionedFrom<Integer>>::from(0, 14).unwrap(),
                         end_time: None,
@@ -116,6 +121,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(5000),
+                    variables: None,
                     properties: CommandProperties::VectorScale {
                         easing: <Easing as VersionedFrom<Integer>>::from(8, 14).unwrap(),
                         end_time: Some(5500),
@@ -128,6 +134,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(5000),
+                    variables: None,
                     properties: CommandProperties::Rotate {
                         easing: <Easing as VersionedFrom<Integer>>::from(7, 14).unwrap(),
                         end_time: Some(5500),
@@ -137,6 +144,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(50000),
+                    variables: None,
                     properties: CommandProperties::Colour {
                         easing: <Easing as VersionedFrom<Integer>>::from(6, 14).unwrap(),
                         end_time: Some(50001),
@@ -146,6 +154,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(300),
+                    variables: None,
                     properties: CommandProperties::Parameter {
                         easing: <Easing as VersionedFrom<Integer>>::from(5, 14).unwrap(),
                         end_time: Some(350),
@@ -155,6 +164,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(300),
+                    variables: None,
                     properties: CommandProperties::Parameter {
                         easing: <Easing as VersionedFrom<Integer>>::from(5, 14).unwrap(),
                         end_time: Some(350),
@@ -164,6 +174,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(300),
+                    variables: None,
                     properties: CommandProperties::Parameter {
                         easing: <Easing as VersionedFrom<Integer>>::from(5, 14).unwrap(),
                         end_time: Some(350),
@@ -173,15 +184,18 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(500),
+                    variables: None,
                     properties: CommandProperties::Loop {
                         loop_count: 10,
                         commands: vec![Command {
                             start_time: Some(10),
+                            variables: None,
                             properties: CommandProperties::Loop {
                                 loop_count: 10,
                                 commands: vec![
                                     Command {
                                         start_time: Some(100),
+                                        variables: None,
                                         properties: CommandProperties::Move {
                                             easing: <Easing as VersionedFrom<Integer>>::from(3, 14)
                                                 .unwrap(),
@@ -195,6 +209,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                                     },
                                     Command {
                                         start_time: Some(-28),
+                                        variables: None,
                                         properties: CommandProperties::Scale {
                                             easing: <Easing as VersionedFrom<Integer>>::from(0, 14)
                                                 .unwrap(),
@@ -210,6 +225,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(0),
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type: TriggerType::HitSound {
                             sample_set: None,
@@ -221,10 +237,12 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                         group_number: None,
                         commands: vec![Command {
                             start_time: Some(10),
+                            variables: None,
                             properties: CommandProperties::Loop {
                                 loop_count: 10,
                                 commands: vec![Command {
                                     start_time: Some(100),
+                                    variables: None,
                                     properties: CommandProperties::Move {
                                         easing: <Easing as VersionedFrom<Integer>>::from(3, 14)
                                             .unwrap(),
@@ -261,6 +279,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
             commands: vec![
                 Command {
                     start_time: Some(0),
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type: TriggerType::HitSound {
                             sample_set: None,
@@ -275,6 +294,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(0),
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type: TriggerType::HitSound {
                             sample_set: None,
@@ -289,6 +309,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(0),
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type: TriggerType::HitSound {
                             sample_set: None,
@@ -303,6 +324,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(0),
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type: TriggerType::HitSound {
                             sample_set: Some(SampleSet::Drum),
@@ -317,6 +339,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(0),
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type: TriggerType::HitSound {
                             sample_set: Some(SampleSet::Soft),
@@ -331,6 +354,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(0),
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type: TriggerType::HitSound {
                             sample_set: Some(SampleSet::All),
@@ -345,6 +369,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(0),
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type: TriggerType::HitSound {
                             sample_set: Some(SampleSet::Drum),
@@ -359,6 +384,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(0),
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type: TriggerType::HitSound {
                             sample_set: None,
@@ -373,6 +399,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(0),
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type: TriggerType::Passing,
                         end_time: Some(10),
@@ -382,6 +409,7 @@ Animation,Fail,BottomCentre,\"Other\\Play3\\explosion.png\",418,108,12,31,LoopFo
                 },
                 Command {
                     start_time: Some(0),
+                    variables: None,
                     properties: CommandProperties::Trigger {
                         trigger_type: TriggerType::Failing,
                         end_time: Some(10),
@@ -404,6 +432,7 @@ fn colours() {
 
     let cmd = Command {
         start_time: Some(0),
+        variables: None,
         properties: CommandProperties::Colour {
             easing: <Easing as VersionedFrom<Integer>>::from(0, 14).unwrap(),
             end_time: Some(0),
@@ -424,6 +453,7 @@ fn parameters() {
 
     let cmd = Command {
         start_time: Some(0),
+        variables: None,
         properties: CommandProperties::Parameter {
             easing: <Easing as VersionedFrom<Integer>>::from(0, 14).unwrap(),
             end_time: None,
@@ -467,6 +497,7 @@ fn trigger_group_number() {
 
     let cmd = Command {
         start_time: Some(0),
+        variables: None,
         properties: CommandProperties::Trigger {
             trigger_type: TriggerType::HitSound {
                 sample_set: None,
@@ -490,6 +521,7 @@ fn move_command() {
 
     let cmd = Command {
         start_time: Some(0),
+        variables: None,
         properties: CommandProperties::Move {
             easing: <Easing as VersionedFrom<Integer>>::from(0, 14).unwrap(),
             end_time: Some(0),
@@ -511,6 +543,7 @@ fn fade_chain() {
 
     let cmd = Command {
         start_time: Some(0),
+        variables: None,
         properties: CommandProperties::Fade {
             easing: <Easing as VersionedFrom<Integer>>::from(0, 14).unwrap(),
             end_time: Some(0),
@@ -0,0 +1,414 @@
+use rust_decimal_macros::dec;
+
+use crate::osu_file::events::storyboard::types::Easing;
+use crate::osu_file::Decimal;
+
+/// Values at `t` of 0, 0.1, 0.2, ..., 1.
+/// - Evaluated from the published osu! stable easing formulas with 50 digits of precision, separately from this crate.
+/// - The `InOut` easings were evaluated as the `In` easing on the first half and the `Out` easing on the second half.
+#[allow(clippy::approx_constant)]
+const REFERENCE: [(Easing, [f64; 11]); 35] = [
+    (
+        Easing::Linear,
+        [0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0],
+    ),
+    (
+        Easing::EasingOut,
+        [
+            0.0, 0.19, 0.36, 0.51, 0.64, 0.75, 0.84, 0.91, 0.96, 0.99, 1.0,
+        ],
+    ),
+    (
+        Easing::EasingIn,
+        [
+            0.0, 0.01, 0.04, 0.09, 0.16, 0.25, 0.36, 0.49, 0.64, 0.81, 1.0,
+        ],
+    ),
+    (
+        Easing::QuadIn,
+        [
+            0.0, 0.01, 0.04, 0.09, 0.16, 0.25, 0.36, 0.49, 0.64, 0.81, 1.0,
+        ],
+    ),
+    (
+        Easing::QuadOut,
+        [
+            0.0, 0.19, 0.36, 0.51, 0.64, 0.75, 0.84, 0.91, 0.96, 0.99, 1.0,
+        ],
+    ),
+    (
+        Easing::QuadInOut,
+        [
+            0.0, 0.02, 0.08, 0.18, 0.32, 0.5, 0.68, 0.82, 0.92, 0.98, 1.0,
+        ],
+    ),
+    (
+        Easing::CubicIn,
+        [
+            0.0, 0.001, 0.008, 0.027, 0.064, 0.125, 0.216, 0.343, 0.512, 0.729, 1.0,
+        ],
+    ),
+    (
+        Easing::CubicOut,
+        [
+            0.0, 0.271, 0.488, 0.657, 0.784, 0.875, 0.936, 0.973, 0.992, 0.999, 1.0,
+        ],
+    ),
+    (
+        Easing::CubicInOut,
+        [
+            0.0, 0.004, 0.032, 0.108, 0.256, 0.5, 0.744, 0.892, 0.968, 0.996, 1.0,
+        ],
+    ),
+    (
+        Easing::QuartIn,
+        [
+            0.0, 0.0001, 0.0016, 0.0081, 0.0256, 0.0625, 0.1296, 0.2401, 0.4096, 0.6561, 1.0,
+        ],
+    ),
+    (
+        Easing::QuartOut,
+        [
+            0.0, 0.3439, 0.5904, 0.7599, 0.8704, 0.9375, 0.9744, 0.9919, 0.9984, 0.9999, 1.0,
+        ],
+    ),
+    (
+        Easing::QuartInOut,
+        [
+            0.0, 0.0008, 0.0128, 0.0648, 0.2048, 0.5, 0.7952, 0.9352, 0.9872, 0.9992, 1.0,
+        ],
+    ),
+    (
+        Easing::QuintIn,
+        [
+            0.0, 0.00001, 0.00032, 0.00243, 0.01024, 0.03125, 0.07776, 0.16807, 0.32768, 0.59049,
+            1.0,
+        ],
+    ),
+    (
+        Easing::QuintOut,
+        [
+            0.0, 0.40951, 0.67232, 0.83193, 0.92224, 0.96875, 0.98976, 0.99757, 0.99968, 0.99999,
+            1.0,
+        ],
+    ),
+    (
+        Easing::QuintInOut,
+        [
+            0.0, 0.00016, 0.00512, 0.03888, 0.16384, 0.5, 0.83616, 0.96112, 0.99488, 0.99984, 1.0,
+        ],
+    ),
+    (
+        Easing::SineIn,
+        [
+            0.0,
+            0.0123116594048623,
+            0.0489434837048464,
+            0.108993475811632,
+            0.190983005625053,
+            0.292893218813452,
+            0.412214747707527,
+            0.546009500260453,
+            0.690983005625053,
+            0.843565534959769,
+            1.0,
+        ],
+    ),
+    (
+        Easing::SineOut,
+        [
+            0.0,
+            0.156434465040231,
+            0.309016994374947,
+            0.453990499739547,
+            0.587785252292473,
+            0.707106781186548,
+            0.809016994374947,
+            0.891006524188368,
+            0.951056516295154,
+            0.987688340595138,
+            1.0,
+        ],
+    ),
+    (
+        Easing::SineInOut,
+        [
+            0.0,
+            0.0244717418524232,
+            0.0954915028125263,
+            0.206107373853763,
+            0.345491502812526,
+            0.5,
+            0.654508497187474,
+            0.793892626146237,
+            0.904508497187474,
+            0.975528258147577,
+            1.0,
+        ],
+    ),
+    (
+        Easing::ExpoIn,
+        [
+            0.0009765625,
+            0.001953125,
+            0.00390625,
+            0.0078125,
+            0.015625,
+            0.03125,
+            0.0625,
+            0.125,
+            0.25,
+            0.5,
+            1.0,
+        ],
+    ),
+    (
+        Easing::ExpoOut,
+        [
+            0.0,
+            0.5,
+            0.75,
+            0.875,
+            0.9375,
+            0.96875,
+            0.984375,
+            0.9921875,
+            0.99609375,
+            0.998046875,
+            0.9990234375,
+        ],
+    ),
+    (
+        Easing::ExpoInOut,
+        [
+            0.00048828125,
+            0.001953125,
+            0.0078125,
+            0.03125,
+            0.125,
+            0.5,
+            0.875,
+            0.96875,
+            0.9921875,
+            0.998046875,
+            0.99951171875,
+        ],
+    ),
+    (
+        Easing::CircIn,
+        [
+            0.0,
+            0.00501256289338005,
+            0.0202041028867288,
+            0.0460607985830544,
+            0.083484861008832,
+            0.133974596215561,
+            0.2,
+            0.285857157145715,
+            0.4,
+            0.564110105645933,
+            1.0,
+        ],
+    ),
+    (
+        Easing::CircOut,
+        [
+            0.0,
+            0.435889894354067,
+            0.6,
+            0.714142842854285,
+            0.8,
+            0.866025403784439,
+            0.916515138991168,
+            0.953939201416946,
+            0.979795897113271,
+            0.99498743710662,
+            1.0,
+        ],
+    ),
+    (
+        Easing::CircInOut,
+        [
+            0.0,
+            0.0101020514433644,
+            0.041742430504416,
+            0.1,
+            0.2,
+            0.5,
+            0.8,
+            0.9,
+            0.958257569495584,
+            0.989897948556636,
+            1.0,
+        ],
+    ),
+    (
+        Easing::ElasticIn,
+        [
+            -0.00048828125,
+            0.001953125,
+            -0.001953125,
+            -0.00390625,
+            0.015625,
+            -0.015625,
+            -0.03125,
+            0.125,
+            -0.125,
+            -0.25,
+            1.0,
+        ],
+    ),
+    (
+        Easing::ElasticOut,
+        [
+            0.0,
+            1.25,
+            1.125,
+            0.875,
+            1.03125,
+            1.015625,
+            0.984375,
+            1.00390625,
+            1.001953125,
+            0.998046875,
+            1.00048828125,
+        ],
+    ),
+    (
+        Easing::ElasticHalfOut,
+        [
+            0.0,
+            0.75,
+            1.125,
+            1.125,
+            1.03125,
+            0.984375,
+            0.984375,
+            0.99609375,
+            1.001953125,
+            1.001953125,
+            1.00048828125,
+        ],
+    ),
+    (
+        Easing::ElasticQuarterOut,
+        [
+            0.0,
+            0.566987298107781,
+            0.875,
+            1.0,
+            1.03125,
+            1.02706329386826,
+            1.015625,
+            1.00676582346707,
+            1.001953125,
+            1.0,
+            0.99951171875,
+        ],
+    ),
+    (
+        Easing::ElasticInOut,
+        [
+            0.0000847891492514308,
+            0.000339156597005723,
+            -0.00390625,
+            0.0239388888474681,
+            -0.117461577598239,
+            0.5,
+            1.11746157759824,
+            0.976061111152532,
+            1.00390625,
+            0.999660843402994,
+            0.999915210850749,
+        ],
+    ),
+    (
+        Easing::BackIn,
+        [
+            0.0,
+            -0.01431422,
+            -0.04645056,
+            -0.08019954,
+            -0.09935168,
+            -0.0876975,
+            -0.02902752,
+            0.09286774,
+            0.29419776,
+            0.59117202,
+            1.0,
+        ],
+    ),
+    (
+        Easing::BackOut,
+        [
+            0.0, 0.40882798, 0.70580224, 0.90713226, 1.02902752, 1.0876975, 1.09935168, 1.08019954,
+            1.04645056, 1.01431422, 1.0,
+        ],
+    ),
+    (
+        Easing::BackInOut,
+        [
+            0.0,
+            -0.037518552,
+            -0.092555656,
+            -0.078833484,
+            0.089925792,
+            0.5,
+            0.910074208,
+            1.078833484,
+            1.092555656,
+            1.037518552,
+            1.0,
+        ],
+    ),
+    (
+        Easing::BounceIn,
+        [
+            0.0, 0.011875, 0.06, 0.069375, 0.2275, 0.234375, 0.09, 0.319375, 0.6975, 0.924375, 1.0,
+        ],
+    ),
+    (
+        Easing::BounceOut,
+        [
+            0.0, 0.075625, 0.3025, 0.680625, 0.91, 0.765625, 0.7725, 0.930625, 0.94, 0.988125, 1.0,
+        ],
+    ),
+    (
+        Easing::BounceInOut,
+        [
+            0.0, 0.03, 0.11375, 0.045, 0.34875, 0.5, 0.65125, 0.955, 0.88625, 0.97, 1.0,
+        ],
+    ),
+];
+
+#[test]
+fn easing_reference_table() {
+    for (easing, values) in REFERENCE {
+        for (i, expected) in values.into_iter().enumerate() {
+            let t = i as f64 / 10.0;
+            let value = easing.apply(t);
+
+            assert!(
+                (value - expected).abs() < 1e-12,
+                "{easing:?} at {t}: expected {expected}, got {value}"
+            );
+        }
+    }
+}
+
+#[test]
+fn easing_other_is_linear() {
+    assert_eq!(Easing::Other(100).apply(0.3), 0.3);
+}
+
+#[test]
+fn easing_decimal() {
+    assert_eq!(
+        Easing::QuadIn.apply_decimal(&dec!(0.5).into()),
+        Some(dec!(0.25).into())
+    );
+    assert_eq!(
+        Easing::Linear.apply_decimal(&Decimal::new_from_str("foo")),
+        None
+    );
+}
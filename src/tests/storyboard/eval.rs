@@ -0,0 +1,62 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::events::storyboard::eval::ObjectTimeline;
+use crate::osu_file::events::Event;
+use crate::osu_file::{Events, VersionedFromStr};
+
+const OBJECT: &str = "Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1000,2000,0,1,0.5
+ M,1,1000,2000,0,0,100,100
+ S,0,500,,2
+ C,0,1000,2000,255,0,0,0,255
+ P,0,1500,1500,H
+ L,3000,2
+  R,0,0,100,0,1";
+
+fn timeline() -> ObjectTimeline {
+    let events = Events::from_str(OBJECT, 14).unwrap().unwrap();
+
+    match &events.0[0] {
+        Event::StoryboardObject(object) => object.timeline(),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn object_state_initial_values() {
+    let timeline = timeline();
+    let state = timeline.state_at(500.0);
+
+    assert_eq!(timeline.lifetime(), Some((500.0, 3200.0)));
+    assert_eq!(state.position, (0.0, 0.0));
+    assert_eq!(state.scale, 2.0);
+    assert_eq!(state.vector_scale, (1.0, 1.0));
+    assert_eq!(state.opacity, 0.0);
+    assert_eq!(state.colour, (255.0, 0.0, 0.0));
+    assert_eq!(state.origin, (0.5, 0.5));
+    assert!(!state.flip_horizontal);
+    assert!(!state.visible);
+}
+
+#[test]
+fn object_state_interpolation() {
+    let timeline = timeline();
+
+    let state = timeline.state_at(1500.0);
+    assert_eq!(state.opacity, 0.5);
+    // `EasingOut` at half way
+    assert_eq!(state.position, (75.0, 75.0));
+    assert_eq!(state.colour, (127.5, 127.5, 0.0));
+    assert!(state.flip_horizontal);
+    assert!(state.visible);
+
+    // continuing opacity
+    assert_eq!(timeline.state_at(2500.0).opacity, 0.75);
+    assert_eq!(timeline.state_at(2500.0).position, (100.0, 100.0));
+
+    // loop iterations
+    assert_eq!(timeline.state_at(3050.0).rotation, 0.5);
+    assert_eq!(timeline.state_at(3150.0).rotation, 0.5);
+    assert_eq!(timeline.state_at(3200.0).opacity, 0.5);
+    assert!(!timeline.state_at(3300.0).visible);
+}
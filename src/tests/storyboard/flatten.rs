@@ -0,0 +1,94 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::events::storyboard::flatten::{FlattenedCommands, TriggerEvent};
+use crate::osu_file::events::storyboard::types::SampleSet;
+use crate::osu_file::events::Event;
+use crate::osu_file::{Events, VersionedFromStr};
+
+const OBJECT: &str = "Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1000,2000,0,1,0.5
+ L,3000,2
+  R,0,0,100,0,1
+  L,100,2
+   S,0,0,50,1,2
+ T,HitSoundClap,0,10000,1
+  F,0,0,500,1,0
+  F,0,1000,,0";
+
+fn flattened() -> FlattenedCommands {
+    let events = Events::from_str(OBJECT, 14).unwrap().unwrap();
+
+    match &events.0[0] {
+        Event::StoryboardObject(object) => {
+            assert_eq!(object.lifetime(), Some((1000, 3400)));
+            object.flatten()
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn times(commands: &[crate::osu_file::events::storyboard::cmds::Command]) -> Vec<(i32, i32)> {
+    commands
+        .iter()
+        .map(|c| (c.start_time.unwrap(), c.end_time().unwrap_or(-1)))
+        .collect()
+}
+
+#[test]
+fn flatten_loops() {
+    let flattened = flattened();
+
+    // outer iterations last 200ms from the rotation and the nested loop
+    assert_eq!(
+        times(&flattened.commands),
+        vec![
+            (1000, 2000),
+            (3000, 3100),
+            (3100, 3150),
+            (3150, 3200),
+            (3200, 3300),
+            (3300, 3350),
+            (3350, 3400),
+        ]
+    );
+    assert_eq!(flattened.lifetime(), Some((1000, 3400)));
+    assert_eq!(flattened.triggers.len(), 1);
+    assert_eq!(flattened.triggers[0].group_number, 1);
+    assert_eq!(flattened.triggers[0].start_time, Some(0));
+    assert_eq!(flattened.triggers[0].end_time, Some(10000));
+}
+
+#[test]
+fn expand_triggers() {
+    let flattened = flattened();
+    let clap = |time| TriggerEvent::HitSound {
+        time,
+        sample_set: SampleSet::Normal,
+        additions_sample_set: SampleSet::Normal,
+        whistle: false,
+        finish: false,
+        clap: true,
+        custom_sample_set: 0,
+    };
+    let whistle = TriggerEvent::HitSound {
+        time: 4000,
+        sample_set: SampleSet::Normal,
+        additions_sample_set: SampleSet::Normal,
+        whistle: true,
+        finish: false,
+        clap: false,
+        custom_sample_set: 0,
+    };
+
+    // whistles, passing and events outside of the trigger don't fire it
+    let commands =
+        flattened.expand_triggers(&[whistle, TriggerEvent::Passing { time: 4000 }, clap(20000)]);
+    assert_eq!(commands, flattened.commands);
+
+    // the second firing stops the first firing's commands that haven't started
+    let commands = flattened.expand_triggers(&[clap(5000), clap(5500)]);
+    assert_eq!(
+        times(&commands[7..]),
+        vec![(5000, 5500), (5500, 6000), (6500, -1)]
+    );
+}
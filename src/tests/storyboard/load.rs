@@ -0,0 +1,86 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::events::storyboard::load::{LoadOptions, LoadRange, ObjectLoad};
+use crate::osu_file::{Events, VersionedFromStr};
+
+const EVENTS: &str = "Sprite,Background,Centre,\"bg.png\",320,240
+ F,0,0,1000,1
+Sprite,Foreground,Centre,\"small.png\",320,240
+ S,0,500,1500,2
+Sprite,Foreground,Centre,\"unknown.png\",320,240
+ F,0,0,2000,1";
+
+#[test]
+fn load() {
+    let events = Events::from_str(EVENTS, 14).unwrap().unwrap();
+    let options = LoadOptions {
+        frame_interval: 100.0,
+        threshold: 1.5,
+        ..Default::default()
+    };
+
+    let report = events.load(&options, |path| match path {
+        "bg.png" => Some((640, 480)),
+        "small.png" => Some((320, 240)),
+        _ => None,
+    });
+
+    assert_eq!(report.peak, 2.0);
+    assert_eq!(report.peak_time, 500.0);
+    assert_eq!(report.average, 22.0 / 16.0);
+    assert_eq!(report.frames.len(), 16);
+    assert_eq!(report.frame_interval, 100.0);
+    assert_eq!(report.unknown_sizes, vec![2]);
+    assert_eq!(
+        report.exceeding,
+        vec![LoadRange {
+            start: 500.0,
+            end: 1000.0,
+            peak: 2.0,
+            objects: vec![
+                ObjectLoad {
+                    index: 0,
+                    peak: 1.0
+                },
+                ObjectLoad {
+                    index: 1,
+                    peak: 1.0
+                },
+            ],
+        }]
+    );
+}
+
+#[test]
+fn load_max_frames() {
+    let events = Events::from_str(EVENTS, 14).unwrap().unwrap();
+    let options = LoadOptions {
+        frame_interval: 100.0,
+        max_frames: 4,
+        ..Default::default()
+    };
+
+    let report = events.load(&options, |_| Some((640, 480)));
+
+    assert_eq!(report.frame_interval, 2000.0 / 3.0);
+    assert_eq!(
+        report
+            .frames
+            .iter()
+            .map(|(time, _)| *time)
+            .collect::<Vec<_>>(),
+        vec![0.0, 2000.0 / 3.0, 4000.0 / 3.0, 2000.0]
+    );
+
+    // a storyboard lasting for weeks is still sampled quickly
+    let events = Events::from_str(
+        "Sprite,Background,Centre,\"bg.png\",320,240\n F,0,-1000000000,1000000000,1",
+        14,
+    )
+    .unwrap()
+    .unwrap();
+    let report = events.load(&LoadOptions::default(), |_| Some((640, 480)));
+
+    assert_eq!(report.frames.len(), LoadOptions::default().max_frames);
+    assert_eq!(report.peak, 1.0);
+}
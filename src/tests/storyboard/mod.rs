@@ -6,8 +6,18 @@ use crate::{
     Position, VersionedFromStr, VersionedToString,
 };
 
+mod builder;
 mod cmds;
+mod easing;
+mod eval;
+mod flatten;
+mod load;
+mod optimize;
+#[cfg(feature = "render")]
+mod render;
 mod sprites;
+mod validate;
+mod variables;
 
 #[test]
 fn sprite_legacy_parse() {
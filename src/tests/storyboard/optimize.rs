@@ -0,0 +1,158 @@
+use pretty_assertions::assert_eq;
+
+use crate::osb::Osb;
+use crate::osu_file::events::storyboard::optimize::{OptimizeOptions, OptimizeReport};
+use crate::osu_file::events::storyboard::sprites::Object;
+use crate::osu_file::events::Event;
+use crate::osu_file::{VersionedFromStr, VersionedToString};
+
+const OSB: &str = r#"[Events]
+Sprite,Foreground,Centre,"a.png",320,240
+ M,0,0,500,0,0,100,100
+ F,0,1000,1500,0,1
+ M,0,1000,2000,100,100,200,200
+ M,0,2000,3000,200,200,300,300
+ S,0,1500,2500,1
+ S,0,3000,3500,1
+ F,0,4000,4500,1,0
+ M,0,5000,6000,0,0,10,10
+Sprite,Foreground,Centre,"b.png",320,240
+ F,0,0,100,0,1
+ S,0,0,100,1,2
+ F,0,100,200,0,1
+ S,0,100,200,1,2
+ F,0,200,300,0,1
+ S,0,200,300,1,2
+Sprite,Foreground,Centre,"c.png",320,240
+ F,0,0,1000,0
+ M,0,0,1000,0,0,100,100
+Sprite,Foreground,Centre,"d.png",320,240
+ M,0,0,1000,320,240,400,300
+ T,HitSoundClap,0,1000
+  F,0,0,100,1,0
+Sprite,Foreground,Centre,"e.png",320,240
+ M,0,0,1000,320,240,400,300
+Sprite,Foreground,Centre,"f.png",320,240
+ M,0,0,1000,320,240,400,300
+Sprite,Foreground,Centre,"g.png",320,240
+ M,0,0,1000,320,240,400,300"#;
+
+fn objects(osb: &Osb) -> Vec<&Object> {
+    osb.events
+        .as_ref()
+        .unwrap()
+        .0
+        .iter()
+        .filter_map(|e| match e {
+            Event::StoryboardObject(object) => Some(object),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn optimize_osb() {
+    let original = Osb::from_str(OSB, 14).unwrap().unwrap();
+    let mut optimized = original.clone();
+    let report = optimized.optimize(&OptimizeOptions::default());
+
+    assert_eq!(
+        report,
+        OptimizeReport {
+            merged_commands: 1,
+            folded_loops: 1,
+            removed_commands: 3,
+            dropped_objects: 1,
+            variables: 1,
+        }
+    );
+
+    let written = optimized.to_string(14).unwrap();
+    assert!(written.len() < OSB.len());
+    let reparsed = Osb::from_str(&written, 14).unwrap().unwrap();
+    assert_eq!(reparsed, optimized);
+
+    let mut before = objects(&original);
+    before.remove(2);
+    let after = objects(&reparsed);
+    assert_eq!(before.len(), after.len());
+
+    for (before, after) in before.iter().zip(after) {
+        let (before, after) = (before.timeline(), after.timeline());
+
+        for time in -100..7000 {
+            let (before, after) = (before.state_at(time as f64), after.state_at(time as f64));
+
+            assert_eq!(before.visible, after.visible, "visibility at {time}");
+            if before.visible {
+                assert_eq!(before, after, "state at {time}");
+            }
+        }
+    }
+}
+
+#[test]
+fn fold_loops_with_variables() {
+    let osb = r#"[Variables]
+$fade=0,1
+
+[Events]
+Sprite,Foreground,Centre,"a.png",320,240
+ F,0,0,100,$fade
+ F,0,100,200,0,1
+ F,0,200,300,$fade
+ F,0,300,400,0,1"#;
+
+    let mut osb = Osb::from_str(osb, 14).unwrap().unwrap();
+    let report = osb.optimize(&OptimizeOptions {
+        merge_commands: false,
+        fold_loops: true,
+        remove_redundant: false,
+        drop_invisible: false,
+        variables: false,
+    });
+
+    assert_eq!(report.folded_loops, 1);
+    assert_eq!(
+        osb.to_string(14).unwrap(),
+        r#"[Variables]
+$fade=0,1
+
+[Events]
+Sprite,Foreground,Centre,"a.png",320,240
+ L,0,4
+  F,0,0,100,$fade"#
+    );
+}
+
+#[test]
+fn variables_cut_off() {
+    let options = OptimizeOptions {
+        merge_commands: false,
+        fold_loops: false,
+        remove_redundant: false,
+        drop_invisible: false,
+        variables: true,
+    };
+    let osb = |count: usize| {
+        let mut osb = "[Events]\nSprite,Foreground,Centre,\"a.png\",320,240".to_string();
+        for i in 0..count {
+            osb.push_str(&format!("\n F,0,{i},,0.25,0.5"));
+        }
+        Osb::from_str(&osb, 14).unwrap().unwrap()
+    };
+
+    // each use saves 6 bytes, which just pays for the `$a=0.25,0.5` line
+    let mut optimized = osb(2);
+    assert_eq!(optimized.optimize(&options).variables, 0);
+    assert_eq!(optimized, osb(2));
+
+    // 6 bytes saved by the values don't pay for the `[Variables]` section
+    let mut optimized = osb(3);
+    assert_eq!(optimized.optimize(&options).variables, 0);
+
+    let original = osb(5);
+    let mut optimized = original.clone();
+    assert_eq!(optimized.optimize(&options).variables, 1);
+    assert!(optimized.to_string(14).unwrap().len() < original.to_string(14).unwrap().len());
+}
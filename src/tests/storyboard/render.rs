@@ -0,0 +1,75 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::events::storyboard::render::{Renderer, RgbaImage};
+use crate::osu_file::{Events, VersionedFromStr};
+
+const EVENTS: &str = "Sprite,Foreground,TopLeft,\"SB\\Blue.png\",10,10
+ F,0,0,1000,0.5
+ S,0,0,,10
+Sprite,Background,TopLeft,\"red.png\",0,0
+ S,0,0,1000,10
+Sprite,Fail,TopLeft,\"red.png\",100,100
+ S,0,0,1000,10
+Sprite,Overlay,TopLeft,\"sb/green.png\",0,0
+ S,0,0,1000,5
+ P,0,0,1000,A
+Sprite,Foreground,TopRight,\"halves.png\",200,0
+ S,0,0,1000,10
+ P,0,0,1000,H
+Animation,Foreground,TopLeft,\"frame.png\",300,0,2,100,LoopOnce
+ S,0,0,1000,10";
+
+fn write_image(path: std::path::PathBuf, width: u32, pixels: &[[u8; 4]]) {
+    let image = RgbaImage {
+        width,
+        height: pixels.len() as u32 / width,
+        pixels: pixels.concat(),
+    };
+    std::fs::write(path, image.to_png().unwrap()).unwrap();
+}
+
+#[test]
+fn render() {
+    let folder =
+        std::env::temp_dir().join(format!("osu-file-parser-render-{}", std::process::id()));
+    std::fs::create_dir_all(folder.join("sb")).unwrap();
+    write_image(folder.join("red.png"), 2, &[[255, 0, 0, 255]; 4]);
+    write_image(folder.join("sb/blue.png"), 2, &[[0, 0, 255, 255]; 4]);
+    write_image(folder.join("sb/green.png"), 2, &[[0, 255, 0, 255]; 4]);
+    write_image(
+        folder.join("halves.png"),
+        2,
+        &[[255, 255, 255, 255], [0, 0, 0, 0]],
+    );
+    write_image(folder.join("frame0.png"), 1, &[[10, 10, 10, 255]]);
+    write_image(folder.join("frame1.png"), 1, &[[20, 20, 20, 255]]);
+
+    let events = Events::from_str(EVENTS, 14).unwrap().unwrap();
+    let mut renderer = Renderer::new(&folder);
+    let frame = renderer.render(&events, 150.0);
+    let widescreen = Renderer::new(&folder)
+        .widescreen(true)
+        .render(&events, 150.0);
+    std::fs::remove_dir_all(&folder).unwrap();
+
+    let frame = frame.unwrap();
+    assert_eq!((frame.width, frame.height), (640, 480));
+    // green is added on top of red
+    assert_eq!(frame.pixel(5, 5), Some([255, 255, 0, 255]));
+    // half transparent blue over red and black
+    assert_eq!(frame.pixel(15, 15), Some([128, 0, 128, 255]));
+    assert_eq!(frame.pixel(25, 25), Some([0, 0, 128, 255]));
+    // the fail layer isn't drawn while passing
+    assert_eq!(frame.pixel(105, 105), Some([0, 0, 0, 255]));
+    // flipping also flips the top right origin to the top left, and the white half to the right
+    assert_eq!(frame.pixel(195, 5), Some([0, 0, 0, 255]));
+    assert_eq!(frame.pixel(205, 5), Some([0, 0, 0, 255]));
+    assert_eq!(frame.pixel(215, 5), Some([255, 255, 255, 255]));
+    // second frame of the animation
+    assert_eq!(frame.pixel(305, 5), Some([20, 20, 20, 255]));
+
+    let widescreen = widescreen.unwrap();
+    assert_eq!((widescreen.width, widescreen.height), (854, 480));
+    assert_eq!(widescreen.pixel(5, 5), Some([0, 0, 0, 255]));
+    assert_eq!(widescreen.pixel(112, 5), Some([255, 255, 0, 255]));
+}
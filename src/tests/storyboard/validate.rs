@@ -0,0 +1,68 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::events::storyboard::validate::{validate, Issue, IssueKind};
+
+const OSB: &str = "[Variables]
+$end=500
+
+[Events]
+//Storyboard Layer 0 (Background)
+Sprite,Background,Centre,\"bg.png\",320,240
+ F,0,1000,$end,1
+ L,0,0
+  F,0,0,100,1
+ L,2000,2
+ T,HitSoundNothing,0,1000
+  F,0,0,100,1
+ M,0,0,1000,0,0,100,100
+ M,0,500,1500,100,100,0,0
+ MX,0,500,1500,100,0
+Sprite,Foreground,12,\"C:\\sb\\star.png\",320,240
+ S,0,0,,1
+Animation,Foreground,Centre,\"anim.png\",320,240,0,100
+ F,0,0,,1
+ X,0,0,,1
+";
+
+fn issue(line_index: usize, kind: IssueKind) -> Issue {
+    Issue { line_index, kind }
+}
+
+#[test]
+fn validate_osb() {
+    assert_eq!(
+        validate(OSB, 14),
+        vec![
+            issue(
+                6,
+                IssueKind::EndBeforeStart {
+                    start: 1000,
+                    end: 500
+                }
+            ),
+            issue(7, IssueKind::LoopCountZero),
+            issue(9, IssueKind::EmptyLoop),
+            issue(
+                10,
+                IssueKind::UnknownTriggerType("HitSoundNothing".to_string())
+            ),
+            issue(
+                13,
+                IssueKind::OverlappingCommands {
+                    other_line_index: 12
+                }
+            ),
+            issue(15, IssueKind::UndocumentedOrigin(12)),
+            issue(15, IssueKind::AbsolutePath("C:\\sb\\star.png".to_string())),
+            issue(17, IssueKind::NoFrames),
+            issue(
+                19,
+                IssueKind::InvalidLine("Unknown command type".to_string())
+            ),
+        ]
+    );
+    assert_eq!(
+        validate(OSB, 14)[4].to_string(),
+        "Line 14, Overlaps with the command on line 13"
+    );
+}
@@ -0,0 +1,113 @@
+use pretty_assertions::assert_eq;
+
+use crate::osb::{Osb, Variable, VariableToken};
+use crate::osu_file::events::storyboard::cmds::CommandProperties;
+use crate::osu_file::events::Event;
+use crate::osu_file::{VersionedFromStr, VersionedToString};
+
+const OSB: &str = "[Variables]
+$a=1
+$ab=0.5
+$pos=320,240
+
+[Events]
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1000,1320,$ab,$a
+ F,0,2000,,1
+ M,0,1000,,$pos
+ MX,0,$a$a,,$$ab
+";
+
+fn variables() -> Vec<Variable> {
+    vec![
+        Variable {
+            name: "a".to_string(),
+            value: "1".to_string(),
+        },
+        Variable {
+            name: "ab".to_string(),
+            value: "0.5".to_string(),
+        },
+    ]
+}
+
+#[test]
+fn tokenize_longest_match() {
+    let variables = variables();
+    let tokens = VariableToken::tokenize("$ab,$a,$abc,$", &variables);
+
+    assert_eq!(
+        tokens,
+        vec![
+            VariableToken::Variable("ab".to_string()),
+            VariableToken::Text(",".to_string()),
+            VariableToken::Variable("a".to_string()),
+            VariableToken::Text(",".to_string()),
+            VariableToken::Variable("ab".to_string()),
+            VariableToken::Text("c,$".to_string()),
+        ]
+    );
+    assert_eq!(
+        VariableToken::expand(&tokens, &variables).unwrap(),
+        "0.5,1,0.5c,$"
+    );
+}
+
+#[test]
+fn substitute_whole_fields() {
+    let variables = variables();
+    let tokens = VariableToken::substitute("0,1320,,1,0.5,10.5", &variables);
+
+    assert_eq!(
+        tokens.iter().map(|t| t.to_string()).collect::<String>(),
+        "0,1320,,$a,$ab,10.5"
+    );
+}
+
+#[test]
+fn osb_round_trip() {
+    let mut osb = Osb::from_str(OSB, 14).unwrap().unwrap();
+
+    assert_eq!(osb.to_string(14).unwrap(), OSB.trim_end());
+
+    let Some(Event::StoryboardObject(object)) = &mut osb.events.as_mut().unwrap().0.first_mut()
+    else {
+        panic!("expected a storyboard object");
+    };
+    assert_eq!(object.commands[3].start_time, Some(11));
+    match &object.commands[3].properties {
+        CommandProperties::MoveX { start_x, .. } => assert_eq!(start_x, &"$0.5".into()),
+        properties => panic!("unexpected command {properties:?}"),
+    }
+
+    // edited commands no longer match how they were written, so variables are used for whole fields
+    object.commands[1].start_time = Some(1320);
+    object.commands[2].start_time = Some(1);
+
+    let written = osb.to_string(14).unwrap();
+    assert!(written.contains("\n F,0,1320,,$a\n"));
+    assert!(written.contains("\n M,0,$a,,$pos\n"));
+}
+
+#[test]
+fn osb_insert_event() {
+    let mut osb = Osb::from_str(OSB, 14).unwrap().unwrap();
+    let events = &mut osb.events.as_mut().unwrap().0;
+
+    // how commands were written moves with them
+    let first = events[0].clone();
+    events.insert(0, first);
+
+    let written = osb.to_string(14).unwrap();
+    assert_eq!(written.matches("\n MX,0,$a$a,,$$ab").count(), 2);
+    assert_eq!(written.matches("\n F,0,1000,1320,$ab,$a").count(), 2);
+
+    // how commands were written isn't part of their equality
+    let expanded = Osb::from_str(&OSB.replace(",,$pos", ",,320,240"), 14)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        expanded.events.as_ref().unwrap().0[0],
+        osb.events.as_ref().unwrap().0[1]
+    );
+}
@@ -0,0 +1,358 @@
+use pretty_assertions::assert_eq;
+use rust_decimal_macros::dec;
+
+use crate::osu_file::{
+    hitobjects::HitObjectParams,
+    transform::{Affine, SnapMove, SnapTarget, TransformError},
+    HitObjects, OsuFile, VersionedToString,
+};
+
+const SMALL: &str = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: 1000
+
+[Editor]
+Bookmarks: 500,1500
+BeatDivisor: 4
+
+[Events]
+0,0,\"bg.jpg\",0,0
+2,3000,4500
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1000,2000,0,1
+ L,3000,2
+  M,0,0,300,0,0,10,10
+
+[TimingPoints]
+0,300,4,2,1,60,1,0
+1500,-50,4,2,1,60,0,0
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:
+256,192,600,12,0,1200,0:0:0:0:";
+
+#[test]
+fn shift_time() {
+    let mut osu_file = SMALL.parse::<OsuFile>().unwrap();
+    osu_file.shift_time(100);
+
+    assert_eq!(
+        osu_file.to_string(),
+        "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: 1100
+
+[Editor]
+Bookmarks: 600,1600
+BeatDivisor: 4
+
+[Events]
+0,100,\"bg.jpg\",0,0
+2,3100,4600
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1100,2100,0,1
+ L,3100,2
+  M,0,0,300,0,0,10,10
+
+[TimingPoints]
+100,300,4,2,1,60,1,0
+1600,-50,4,2,1,60,0,0
+
+[HitObjects]
+256,192,100,1,0,0:0:0:0:
+256,192,700,12,0,1300,0:0:0:0:"
+    );
+}
+
+#[test]
+fn scale_rate() {
+    let mut osu_file = SMALL.parse::<OsuFile>().unwrap();
+    osu_file.scale_rate(dec!(1.5), false).unwrap();
+
+    assert_eq!(
+        osu_file.to_string(),
+        "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: 667
+
+[Editor]
+Bookmarks: 333,1000
+BeatDivisor: 4
+
+[Events]
+0,0,\"bg.jpg\",0,0
+2,2000,3000
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,667,1333,0,1
+ L,2000,2
+  M,0,0,200,0,0,10,10
+
+[TimingPoints]
+0,200,4,2,1,60,1,0
+1000,-50,4,2,1,60,0,0
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:
+256,192,400,12,0,800,0:0:0:0:"
+    );
+    assert_eq!(
+        osu_file.scale_rate(dec!(0), false),
+        Err(TransformError::InvalidRate)
+    );
+}
+
+#[test]
+fn scale_rate_resnap() {
+    let mut osu_file = SMALL
+        .replace("256,192,600,12", "256,192,610,12")
+        .parse::<OsuFile>()
+        .unwrap();
+    osu_file.scale_rate(dec!(1.5), true).unwrap();
+
+    let times = osu_file
+        .hitobjects
+        .unwrap()
+        .0
+        .iter()
+        .map(|h| h.time.to_string())
+        .collect::<Vec<_>>();
+
+    // 610 / 1.5 = 407, closest 1/4 tick of 200ms beats is 400
+    assert_eq!(times, vec!["0", "400"]);
+}
+
+const GEOMETRY: &str = "osu file format v14
+
+[HitObjects]
+100,50,0,1,0,0:0:0:0:
+0,0,500,2,0,B|100:0|500:400,1,200
+300,100,1000,1,0,0:0:0:0:";
+
+fn positions(hitobjects: &HitObjects) -> String {
+    hitobjects.to_string(14).unwrap()
+}
+
+#[test]
+fn flip_and_clamp() {
+    let mut osu_file = GEOMETRY.parse::<OsuFile>().unwrap();
+    let hitobjects = osu_file.hitobjects.as_mut().unwrap();
+
+    hitobjects
+        .transform(&Affine::flip_vertical(), .., false)
+        .unwrap();
+    hitobjects
+        .transform(&Affine::flip_horizontal(), ..dec!(1000), true)
+        .unwrap();
+
+    assert_eq!(
+        positions(hitobjects),
+        "412,334,0,1,0,0:0:0:0:
+512,384,500,2,0,B|412:384|12:-16,1,200
+300,284,1000,1,0,0:0:0:0:"
+    );
+}
+
+#[test]
+fn clamp_slider() {
+    let mut hitobjects = "osu file format v14
+
+[HitObjects]
+400,100,0,2,0,L|500:100,1,100
+100,200,500,2,0,P|70:210|100:300,1,157.08"
+        .parse::<OsuFile>()
+        .unwrap()
+        .hitobjects
+        .unwrap();
+
+    hitobjects
+        .transform(&Affine::translate(50.0, 0.0), ..dec!(500), true)
+        .unwrap();
+
+    // moved back as a whole, so the shape and length stay the same
+    assert_eq!(
+        positions(&hitobjects).lines().next().unwrap(),
+        "412,100,0,2,0,L|512:100,1,100"
+    );
+
+    // the arc goes further left than its curve points
+    hitobjects
+        .transform(&Affine::translate(-80.0, 0.0), dec!(500).., true)
+        .unwrap();
+    assert_eq!(
+        positions(&hitobjects).lines().last().unwrap(),
+        "50,200,500,2,0,P|20:210|50:300,1,157.08"
+    );
+    let HitObjectParams::Slider(params) = &hitobjects.0[1].obj_params else {
+        unreachable!()
+    };
+    let path = params.path(&hitobjects.0[1].position).unwrap();
+    assert!(path.points().iter().all(|&(x, _)| x >= 0.0));
+}
+
+#[test]
+fn rotate_scale_translate() {
+    let mut osu_file = GEOMETRY.parse::<OsuFile>().unwrap();
+    let hitobjects = osu_file.hitobjects.as_mut().unwrap();
+
+    hitobjects
+        .transform(
+            &Affine::rotate(90.0)
+                .then(Affine::scale(2.0, 2.0))
+                .then(Affine::translate(10.0, -10.0)),
+            dec!(1000)..,
+            true,
+        )
+        .unwrap();
+    // (300, 100) -> rotate (348, 236) -> scale (440, 280) -> translate (450, 270)
+    assert_eq!(
+        positions(hitobjects).lines().last().unwrap(),
+        "450,270,1000,1,0,0:0:0:0:"
+    );
+
+    hitobjects
+        .transform(&Affine::scale(3.0, 3.0), dec!(1000).., true)
+        .unwrap();
+    assert_eq!(
+        positions(hitobjects).lines().last().unwrap(),
+        "512,384,1000,1,0,0:0:0:0:"
+    );
+}
+
+fn slider_end(hitobjects: &HitObjects) -> (f64, f64) {
+    let slider = &hitobjects.0[1];
+    let HitObjectParams::Slider(params) = &slider.obj_params else {
+        unreachable!()
+    };
+
+    params
+        .path(&slider.position)
+        .unwrap()
+        .position_at(params.length.to_f64().unwrap())
+}
+
+#[test]
+fn scale_slider_length() {
+    let mut osu_file = GEOMETRY.parse::<OsuFile>().unwrap();
+    let hitobjects = osu_file.hitobjects.as_mut().unwrap();
+    let transform = Affine::rotate(90.0).then(Affine::scale(2.0, 2.0));
+    let (x, y) = slider_end(hitobjects);
+    let (expected_x, expected_y) = transform.apply_point(x, y);
+
+    hitobjects.transform(&transform, .., false).unwrap();
+
+    let HitObjectParams::Slider(params) = &hitobjects.0[1].obj_params else {
+        unreachable!()
+    };
+    assert_eq!(params.length, dec!(400).into());
+    let (x, y) = slider_end(hitobjects);
+    assert!((x - expected_x).abs() < 1.0, "{x} != {expected_x}");
+    assert!((y - expected_y).abs() < 1.0, "{y} != {expected_y}");
+}
+
+#[test]
+fn non_uniform_scale() {
+    let mut osu_file = GEOMETRY.parse::<OsuFile>().unwrap();
+    let hitobjects = osu_file.hitobjects.as_mut().unwrap();
+    let original = hitobjects.clone();
+
+    assert_eq!(
+        hitobjects.transform(&Affine::scale(2.0, 1.0), .., false),
+        Err(TransformError::NonUniformScale)
+    );
+    assert_eq!(*hitobjects, original);
+
+    // no sliders in the range
+    hitobjects
+        .transform(&Affine::scale(2.0, 1.0), dec!(1000).., false)
+        .unwrap();
+    assert_eq!(
+        positions(hitobjects).lines().last().unwrap(),
+        "344,100,1000,1,0,0:0:0:0:"
+    );
+}
+
+#[test]
+fn mirror_mania_columns() {
+    let mut osu_file = "osu file format v14
+
+[HitObjects]
+64,192,0,1,0,0:0:0:0:
+200,192,100,1,0,0:0:0:0:
+448,192,200,128,0,400:0:0:0:0:"
+        .parse::<OsuFile>()
+        .unwrap();
+
+    let hitobjects = osu_file.hitobjects.as_mut().unwrap();
+    hitobjects.mirror_mania_columns(4, ..);
+
+    assert_eq!(
+        positions(hitobjects),
+        "448,192,0,1,0,0:0:0:0:
+320,192,100,1,0,0:0:0:0:
+64,192,200,128,0,400:0:0:0:0:"
+    );
+}
+
+#[test]
+fn resnap() {
+    let mut osu_file = "osu file format v14
+
+[Editor]
+BeatDivisor: 4
+
+[Difficulty]
+SliderMultiplier:1.4
+
+[TimingPoints]
+0,300,4,2,1,60,1,0
+1201,-100,4,2,1,60,0,0
+
+[HitObjects]
+256,192,152,1,0,0:0:0:0:
+256,192,300,2,0,L|356:192,1,150
+256,192,1000,12,0,2002,0:0:0:0:"
+        .parse::<OsuFile>()
+        .unwrap();
+
+    let report = osu_file.resnap(&[4, 3]);
+
+    assert_eq!(
+        report.moves,
+        vec![
+            SnapMove {
+                target: SnapTarget::TimingPoint(1),
+                old: dec!(1201),
+                new: dec!(1200),
+            },
+            SnapMove {
+                target: SnapTarget::HitObject(0),
+                old: dec!(152),
+                new: dec!(150),
+            },
+            SnapMove {
+                target: SnapTarget::HitObjectEnd(1),
+                old: dec!(621),
+                new: dec!(600),
+            },
+            SnapMove {
+                target: SnapTarget::HitObjectEnd(2),
+                old: dec!(2002),
+                new: dec!(2000),
+            },
+        ]
+    );
+    assert_eq!(report.max_distance(), dec!(21));
+    assert_eq!(
+        osu_file.hitobjects.as_ref().unwrap().to_string(14).unwrap(),
+        "256,192,150,1,0,0:0:0:0:
+256,192,300,2,0,L|356:192,1,140
+256,192,1000,12,0,2000,0:0:0:0:"
+    );
+    assert!(osu_file.resnap(&[4, 3]).is_empty());
+}
@@ -0,0 +1,119 @@
+//! Smoke tests of the `osu-file` command line tool.
+
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+use osu_file_parser::OsuFile;
+
+fn file(name: &str) -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/osu_files/files")
+        .join(name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_osu-file"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn help() {
+    let output = run(&["help"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Commands:"));
+
+    let output = run(&["foo"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown command `foo`"));
+}
+
+#[test]
+fn info() {
+    let output = run(&["info", &file("acid_rain.osu")]);
+    assert!(output.status.success());
+
+    let stdout = stdout(&output);
+    assert!(stdout.contains("File format: v14"));
+    assert!(stdout.contains("Acid Rain"));
+}
+
+#[test]
+fn validate() {
+    let output = run(&["validate", &file("acid_rain.osu"), &file("osb.osb")]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).matches(": ok").count(), 2);
+
+    let output = run(&["validate", &file("error_line_index.osb")]);
+    assert!(!output.status.success());
+    assert!(!stdout(&output).contains(": ok"));
+}
+
+#[test]
+fn fmt() {
+    let path = file("acid_rain.osu");
+    let osu_file = std::fs::read_to_string(&path)
+        .unwrap()
+        .parse::<OsuFile>()
+        .unwrap();
+
+    let output = run(&["fmt", &path]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("{osu_file}\n"));
+
+    // reading from the standard input
+    let mut child = Command::new(env!("CARGO_BIN_EXE_osu-file"))
+        .args(["fmt", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(std::fs::read(&path).unwrap().as_slice())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("{osu_file}\n"));
+}
+
+#[test]
+fn convert() {
+    let output = run(&["convert", "--to-version", "128", &file("acid_rain.osu")]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("osu file format v128"));
+
+    let output = run(&["convert", "--to-version", "2", &file("acid_rain.osu")]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid version `2`"));
+}
+
+#[test]
+fn json() {
+    let output = run(&["json", &file("acid_rain.osu")]);
+    assert!(output.status.success());
+
+    let json = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+    assert_eq!(json["version"], 14);
+    assert_eq!(json["metadata"]["title"], "Acid Rain");
+    // every hitobject is written with all of its fields
+    let hitobject = &json["hitobjects"][0];
+    assert_eq!(hitobject["time"], "28238");
+    assert!(hitobject["obj_params"]["Slider"]["edge_sets"].is_array());
+    assert!(hitobject["hitsound"].is_object());
+    assert!(json["events"]
+        .as_array()
+        .is_some_and(|events| !events.is_empty()));
+}